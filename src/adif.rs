@@ -0,0 +1,452 @@
+use std::io::{self, Write};
+use std::collections::HashMap;
+use std::str;
+use crate::*;
+
+const ADIF_VERSION: &str = "3.1.4";
+
+/// ADIF band names for the Cabrillo bands which correspond to a single band.
+const ADIF_BANDS: [(Band, &str); 25] = [
+	(Band::Band2200M, "2190m"),
+	(Band::Band630M , "630m"),
+	(Band::Band160M , "160m"),
+	(Band::Band80M  , "80m"),
+	(Band::Band40M  , "40m"),
+	(Band::Band20M  , "20m"),
+	(Band::Band15M  , "15m"),
+	(Band::Band10M  , "10m"),
+	(Band::Band6M   , "6m"),
+	(Band::Band4M   , "4m"),
+	(Band::Band2M   , "2m"),
+	(Band::Band222  , "1.25m"),
+	(Band::Band432  , "70cm"),
+	(Band::Band902  , "33cm"),
+	(Band::Band1_2G , "23cm"),
+	(Band::Band2_3G , "13cm"),
+	(Band::Band3_4G , "9cm"),
+	(Band::Band5_7G , "6cm"),
+	(Band::Band10G  , "3cm"),
+	(Band::Band24G  , "1.25cm"),
+	(Band::Band47G  , "6mm"),
+	(Band::Band75G  , "4mm"),
+	(Band::Band123G , "2.5mm"),
+	(Band::Band134G , "2mm"),
+	(Band::Band241G , "1mm")
+];
+
+/// ADIF band name for a Cabrillo band, if it corresponds to a single band.
+fn adif_band(band: &Band) -> Option<&'static str> {
+	ADIF_BANDS.iter()
+		.find(|(adif_band, _)| adif_band == band)
+		.map(|(_, name)| *name)
+}
+
+/// ADIF mode for a Cabrillo mode. ADIF has no generic digital mode, so digital
+/// and mixed QSOs are written without one.
+fn adif_mode(mode: &Mode) -> Option<&'static str> {
+	match mode {
+		Mode::Cw    => Some("CW"),
+		Mode::Phone => Some("SSB"),
+		Mode::Fm    => Some("FM"),
+		Mode::Rtty  => Some("RTTY"),
+		_ => None
+	}
+}
+
+/// ADIF mode and submode for a digital mode.
+fn adif_submode(submode: &DigitalMode) -> (&'static str, Option<&'static str>) {
+	match submode {
+		DigitalMode::Ft8    => ("FT8", None),
+		DigitalMode::Ft4    => ("MFSK", Some("FT4")),
+		DigitalMode::Psk31  => ("PSK", Some("PSK31")),
+		DigitalMode::Psk63  => ("PSK", Some("PSK63")),
+		DigitalMode::Jt65   => ("JT65", None),
+		DigitalMode::Jt9    => ("JT9", None),
+		DigitalMode::Js8    => ("MFSK", Some("JS8")),
+		DigitalMode::Msk144 => ("MSK144", None),
+		DigitalMode::Olivia => ("OLIVIA", None)
+	}
+}
+
+/// Split an exchange into its signal report, if it starts with one, and the remainder.
+pub(crate) fn split_rst(exchange: &str) -> (Option<&str>, &str) {
+	let mut parts = exchange.splitn(2, ' ');
+	let first = parts.next().unwrap_or("");
+	let is_rst = (2..=3).contains(&first.len())
+		&& first.chars().all(|c| c.is_ascii_digit())
+		&& matches!(first.chars().next(), Some('1'..='5'));
+
+	if is_rst {
+		(Some(first), parts.next().unwrap_or("").trim())
+	} else {
+		(None, exchange)
+	}
+}
+
+/// Which ADIF fields make up the sent and received exchanges when importing QSOs.
+/// The values of the listed fields are joined with spaces, skipping any which are
+/// missing from a record.
+#[derive(Debug, Clone)]
+pub struct AdifExchange {
+	sent: Vec<String>,
+	received: Vec<String>
+}
+
+impl AdifExchange {
+	pub fn new<I: IntoIterator<Item = S>, S: Into<String>>(sent: I, received: I) -> Self {
+		Self {
+			sent: sent.into_iter().map(Into::into).collect(),
+			received: received.into_iter().map(Into::into).collect()
+		}
+	}
+
+	pub fn sent(&self) -> &Vec<String> {
+		&self.sent
+	}
+
+	pub fn received(&self) -> &Vec<String> {
+		&self.received
+	}
+}
+
+/// Signal report followed by the exchange string, the reverse of how `to_adif()`
+/// splits exchanges.
+impl Default for AdifExchange {
+	fn default() -> Self {
+		Self::new(["RST_SENT", "STX_STRING"], ["RST_RCVD", "SRX_STRING"])
+	}
+}
+
+struct AdifRecord {
+	line: usize,
+	fields: HashMap<String, String>
+}
+
+impl AdifRecord {
+	fn get(&self, name: &str) -> Option<&str> {
+		self.fields.get(name)
+			.map(|value| value.trim())
+			.filter(|value| !value.is_empty())
+	}
+
+	fn require(&self, name: &str) -> CabrilloResult<&str> {
+		self.get(name).ok_or_else(|| self.error(name, "Missing field".into()))
+	}
+
+	fn error(&self, name: &str, message: String) -> CabrilloError {
+		CabrilloError::new(name, self.line, CabrilloErrorKind::ParseError(message))
+	}
+
+	fn join(&self, names: &[String]) -> String {
+		names.iter()
+			.filter_map(|name| self.get(name))
+			.collect::<Vec<_>>()
+			.join(" ")
+	}
+}
+
+fn line_of(input: &str, pos: usize) -> usize {
+	input[..pos].matches('\n').count()
+}
+
+/// Split an ADIF (.adi) document into records. Field names are case-insensitive
+/// and stored uppercase; the header, if any, is skipped.
+fn adif_records(input: &str) -> CabrilloResult<Vec<AdifRecord>> {
+	let mut pos = 0;
+
+	// A header is present unless the document starts with a field.
+	if !input.starts_with('<') {
+		pos = input.to_ascii_uppercase().find("<EOH>")
+			.map(|eoh| eoh + "<EOH>".len())
+			.ok_or_else(|| CabrilloError::new("EOH", line_of(input, input.len()),
+				CabrilloErrorKind::ParseError("Missing end of header".into())))?;
+	}
+
+	let mut records = Vec::new();
+	let mut fields = HashMap::new();
+	let mut record_start = None;
+
+	while let Some(offset) = input[pos..].find('<') {
+		let start = pos + offset;
+		let line = line_of(input, start);
+		let end = input[start..].find('>')
+			.map(|end| start + end)
+			.ok_or_else(|| CabrilloError::new("", line,
+				CabrilloErrorKind::ParseError("Unterminated field".into())))?;
+
+		let mut spec = input[start + 1..end].split(':');
+		let name = spec.next().unwrap_or("").trim().to_ascii_uppercase();
+		let record_line = *record_start.get_or_insert(line);
+		pos = end + 1;
+
+		if name == "EOR" {
+			records.push(AdifRecord {
+				line: record_line,
+				fields: std::mem::take(&mut fields)
+			});
+			record_start = None;
+			continue;
+		}
+
+		let len = spec.next()
+			.map(|len| len.trim().parse::<usize>())
+			.transpose()
+			.map_err(|err| CabrilloError::new(&name, line,
+				CabrilloErrorKind::ParseError(format!("Invalid field length: {}", err))))?
+			.unwrap_or(0);
+
+		let value_end = input[pos..].char_indices()
+			.nth(len)
+			.map(|(idx, _)| pos + idx)
+			.unwrap_or(input.len());
+
+		fields.insert(name, input[pos..value_end].to_string());
+		pos = value_end;
+	}
+
+	Ok(records)
+}
+
+/// The FREQ of a record, or for a record with only a BAND, as written for QSOs
+/// on a band designator, that band.
+fn adif_frequency(record: &AdifRecord) -> CabrilloResult<Frequency> {
+	if let (None, Some(band)) = (record.get("FREQ"), record.get("BAND")) {
+		return ADIF_BANDS.iter()
+			.find(|(_, name)| name.eq_ignore_ascii_case(band))
+			.filter(|(band, _)| band_designator(band).is_some())
+			.map(|(band, _)| Frequency::Band(*band))
+			.ok_or_else(|| record.error("BAND", format!("Band '{}' needs a FREQ", band)));
+	}
+
+	let freq = record.require("FREQ")?;
+	let mhz = freq.parse::<f64>()
+		.map_err(|_| record.error("FREQ", format!("Invalid frequency '{}'", freq)))?;
+
+	Ok(Frequency::from_mhz(mhz))
+}
+
+/// Cabrillo mode for an ADIF mode. Everything which isn't CW, phone, or RTTY is
+/// treated as a digital mode.
+fn adif_mode_from_name(mode: &str) -> Mode {
+	match mode.to_ascii_uppercase().as_str() {
+		"CW"                          => Mode::Cw,
+		"SSB" | "AM" | "DIGITALVOICE" => Mode::Phone,
+		"FM"                          => Mode::Fm,
+		"RTTY"                        => Mode::Rtty,
+		_                             => Mode::Digital
+	}
+}
+
+fn adif_datetime(record: &AdifRecord) -> CabrilloResult<DateTime> {
+	let date = record.require("QSO_DATE")?;
+	let time = record.require("TIME_ON")?;
+	let hhmm = time.get(..4)
+		.ok_or_else(|| record.error("TIME_ON", format!("Invalid time '{}'", time)))?;
+
+	datetime::parse(&format!("{}{}", date, hhmm), "%Y%m%d%H%M")
+		.ok_or_else(|| record.error("QSO_DATE", format!("Invalid date or time '{} {}'", date, time)))
+}
+
+fn adif_qso(record: &AdifRecord, exchange: &AdifExchange) -> CabrilloResult<Qso> {
+	let call_sent = record.get("STATION_CALLSIGN")
+		.or_else(|| record.get("OPERATOR"))
+		.ok_or_else(|| record.error("STATION_CALLSIGN", "Missing field".into()))?;
+
+	Ok(Qso::new(
+		adif_frequency(record)?,
+		adif_mode_from_name(record.require("MODE")?),
+		adif_datetime(record)?,
+		call_sent.to_string(),
+		record.join(&exchange.sent),
+		record.require("CALL")?.to_string(),
+		record.join(&exchange.received)
+	))
+}
+
+fn write_field<W: Write>(writer: &mut W, name: &str, value: &str) -> io::Result<()> {
+	if value.is_empty() {
+		return Ok(());
+	}
+
+	write!(writer, "<{}:{}>{} ", name, value.chars().count(), value)
+}
+
+fn write_record<W: Write>(writer: &mut W, log: &CabrilloLog, qso: &Qso) -> io::Result<()> {
+	write_field(writer, "CALL", qso.call_recvd.as_str())?;
+	write_field(writer, "QSO_DATE", &datetime::format(&qso.datetime, "%Y%m%d"))?;
+	write_field(writer, "TIME_ON", &datetime::format(&qso.datetime, "%H%M"))?;
+
+	if let Frequency::Hz(hz) = qso.frequency {
+		let mhz = format!("{}.{:06}", hz / 1000000, hz % 1000000);
+		write_field(writer, "FREQ", &mhz[..mhz.trim_end_matches('0').len().max(mhz.len() - 3)])?;
+	}
+
+	if let Some(band) = qso.band().as_ref().and_then(adif_band) {
+		write_field(writer, "BAND", band)?;
+	}
+
+	if let Some((mode, submode)) = qso.submode.as_ref().map(adif_submode) {
+		write_field(writer, "MODE", mode)?;
+
+		if let Some(submode) = submode {
+			write_field(writer, "SUBMODE", submode)?;
+		}
+	} else if let Some(mode) = adif_mode(&qso.mode) {
+		write_field(writer, "MODE", mode)?;
+	}
+
+	write_field(writer, "STATION_CALLSIGN", qso.call_sent.as_str())?;
+
+	let (rst_sent, exch_sent) = split_rst(&qso.exch_sent);
+	let (rst_rcvd, exch_rcvd) = split_rst(&qso.exch_recvd);
+	write_field(writer, "RST_SENT", rst_sent.unwrap_or(""))?;
+	write_field(writer, "STX_STRING", exch_sent)?;
+	write_field(writer, "RST_RCVD", rst_rcvd.unwrap_or(""))?;
+	write_field(writer, "SRX_STRING", exch_rcvd)?;
+
+	if let Some(ref contest) = log.contest {
+		write_field(writer, "CONTEST_ID", contest)?;
+	}
+
+	if let Some(ref grid) = log.grid_locator {
+		write_field(writer, "MY_GRIDSQUARE", grid.as_str())?;
+	}
+
+	writeln!(writer, "<EOR>")
+}
+
+impl CabrilloLog {
+	/// Export the QSO entries of this log as an ADIF (.adi) file. Ignored (X-QSO)
+	/// entries are not exported. Signal reports at the start of an exchange are
+	/// written as RST_SENT/RST_RCVD and the rest as STX_STRING/SRX_STRING.
+	pub fn to_adif<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+		writeln!(writer, "Generated from a Cabrillo log")?;
+		write_field(writer, "ADIF_VER", ADIF_VERSION)?;
+		write_field(writer, "PROGRAMID", env!("CARGO_PKG_NAME"))?;
+		write_field(writer, "PROGRAMVERSION", env!("CARGO_PKG_VERSION"))?;
+		writeln!(writer, "<EOH>")?;
+
+		for qso in &self.entries {
+			write_record(writer, self, qso)?;
+		}
+
+		Ok(())
+	}
+
+	/// Build a log from the QSO records of an ADIF (.adi) file, for submission to
+	/// `contest`. The log callsign and grid locator are taken from the first record.
+	pub fn from_adif(buf: &[u8], contest: &str, exchange: &AdifExchange) -> CabrilloResult<Self> {
+		let input = str::from_utf8(buf)
+			.map_err(|err| {
+				CabrilloError::new("", 0,
+					CabrilloErrorKind::IoError(format!("{}", err)))
+			})?;
+
+		let mut log = Self::new();
+		log.contest = Some(contest.to_string());
+
+		for record in adif_records(input)? {
+			let qso = adif_qso(&record, exchange)?;
+
+			if log.callsign.is_none() {
+				log.callsign = Some(qso.call_sent.to_string());
+			}
+
+			if log.grid_locator.is_none() {
+				log.grid_locator = record.get("MY_GRIDSQUARE").and_then(GridSquare::normalize);
+			}
+
+			log.entries.push(qso);
+		}
+
+		Ok(log)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn export_adif() {
+		let log = CabrilloLog::from_path("test_data/cqww.txt").unwrap();
+		let mut output = Vec::new();
+		log.to_adif(&mut output).unwrap();
+		let output = String::from_utf8(output).unwrap();
+
+		assert!(output.contains("<ADIF_VER:5>3.1.4 "));
+		assert!(output.contains(concat!(
+			"<CALL:5>K9QZO <QSO_DATE:8>20001026 <TIME_ON:4>0711 <FREQ:5>3.799 <BAND:3>80m ",
+			"<MODE:3>SSB <STATION_CALLSIGN:6>AA1ZZZ <RST_SENT:2>59 <STX_STRING:2>05 ",
+			"<RST_RCVD:2>59 <SRX_STRING:2>04 <CONTEST_ID:9>CQ-WW-SSB <MY_GRIDSQUARE:6>FN20ib <EOR>\n"
+		)));
+		assert_eq!(output.matches("<EOR>").count(), log.entries().len());
+
+		let mut log = CabrilloLog::new();
+		let datetime = datetime::parse("2023-06-24 1200", "%Y-%m-%d %H%M").unwrap();
+		log.add_entry(Qso::new(Frequency::from_khz(14080), Mode::Digital, datetime, "K1AB", "FN42", "W9XYZ", "EN52")
+			.with_submode(DigitalMode::Ft4));
+		let mut output = Vec::new();
+		log.to_adif(&mut output).unwrap();
+		assert!(String::from_utf8(output).unwrap().contains("<MODE:4>MFSK <SUBMODE:3>FT4 "));
+	}
+
+	#[test]
+	fn import_adif() {
+		let log = CabrilloLog::from_path("test_data/cqww.txt").unwrap();
+		let mut output = Vec::new();
+		log.to_adif(&mut output).unwrap();
+
+		let imported = CabrilloLog::from_adif(&output, "CQ-WW-SSB", &AdifExchange::default()).unwrap();
+		assert_eq!(imported.callsign(), &Some("AA1ZZZ".to_string()));
+		assert_eq!(imported.entries().len(), log.entries().len());
+
+		for (a, b) in imported.entries().iter().zip(log.entries()) {
+			assert_eq!(a.frequency(), b.frequency());
+			assert_eq!(a.mode(), b.mode());
+			assert_eq!(a.datetime(), b.datetime());
+			assert_eq!(a.call_received(), b.call_received());
+			assert_eq!(a.exchange_sent(), b.exchange_sent());
+			assert_eq!(a.exchange_received(), b.exchange_received());
+		}
+	}
+
+	#[test]
+	fn import_adif_band() {
+		let log = CabrilloLog::from_path("test_data/cqww_vhf.txt").unwrap();
+		let mut output = Vec::new();
+		log.to_adif(&mut output).unwrap();
+
+		let imported = CabrilloLog::from_adif(&output, "CQ-VHF", &AdifExchange::default()).unwrap();
+		assert_eq!(imported.entries().len(), log.entries().len());
+
+		for (a, b) in imported.entries().iter().zip(log.entries()) {
+			assert_eq!(a.frequency(), b.frequency());
+			assert_eq!(a.datetime(), b.datetime());
+			assert_eq!(a.call_received(), b.call_received());
+		}
+
+		let input = "<call:4>W1AW<qso_date:8>20230114<time_on:4>1805<band:3>20M<mode:2>CW<eor>";
+		assert!(CabrilloLog::from_adif(input.as_bytes(), "CQ-WW-CW", &AdifExchange::default()).is_err());
+	}
+
+	#[test]
+	fn import_adif_mapping() {
+		let input = concat!(
+			"<call:4>W1AW<qso_date:8>20230114<time_on:6>180512<freq:7:N>14.0256",
+			"<mode:3>FT8<operator:4>K1AB<stx:3>001<srx:2>42<eor>\n"
+		);
+		let exchange = AdifExchange::new(["STX"], ["SRX"]);
+		let log = CabrilloLog::from_adif(input.as_bytes(), "TEST", &exchange).unwrap();
+		let qso = &log.entries()[0];
+
+		assert_eq!(qso.frequency(), &Frequency::from_hz(14025600));
+		assert_eq!(qso.mode(), &Mode::Digital);
+		assert_eq!(qso.call_sent(), "K1AB");
+		assert_eq!(qso.exchange_sent(), "001");
+		assert_eq!(qso.exchange_received(), "42");
+
+		let err = CabrilloLog::from_adif(b"<CALL:4>W1AW<EOR>", "TEST", &exchange).unwrap_err();
+		assert_eq!(err.tag(), "STATION_CALLSIGN");
+	}
+}
@@ -0,0 +1,120 @@
+use std::io::Write;
+use std::sync::Arc;
+use arrow_array::{ArrayRef, RecordBatch, StringArray, TimestampSecondArray, UInt32Array, UInt8Array};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use parquet::arrow::ArrowWriter;
+use crate::*;
+
+fn arrow_error<E: Display>(err: E) -> CabrilloError {
+	CabrilloError::new("", 0, CabrilloErrorKind::IoError(err.to_string()))
+}
+
+fn string_column<'a, F: Fn(&'a Qso) -> &'a str>(entries: &[&'a Qso], f: F) -> ArrayRef {
+	Arc::new(entries.iter().map(|qso| Some(f(qso))).collect::<StringArray>())
+}
+
+impl Qso {
+	/// Arrow schema of the record batches made by `Qso::to_record_batch()`.
+	///
+	/// Columns are `frequency_khz` (null for light, and the frequency named by band
+	/// designators such as `144`), `mode` (CW, SSB, FM, RTTY, DIGI),
+	/// `datetime` (seconds, UTC), `call_sent`, `exchange_sent`, `call_received`,
+	/// `exchange_received`, and `transmitter_id` (null if the QSO line had none).
+	pub fn arrow_schema() -> SchemaRef {
+		Arc::new(Schema::new(vec![
+			Field::new("frequency_khz", DataType::UInt32, true),
+			Field::new("mode", DataType::Utf8, false),
+			Field::new("datetime", DataType::Timestamp(TimeUnit::Second, Some("UTC".into())), false),
+			Field::new("call_sent", DataType::Utf8, false),
+			Field::new("exchange_sent", DataType::Utf8, false),
+			Field::new("call_received", DataType::Utf8, false),
+			Field::new("exchange_received", DataType::Utf8, false),
+			Field::new("transmitter_id", DataType::UInt8, true)
+		]))
+	}
+
+	/// Convert QSOs into a single Arrow record batch. QSOs from many logs can be
+	/// collected into one batch this way.
+	pub fn to_record_batch<'a, I: IntoIterator<Item = &'a Qso>>(entries: I) -> CabrilloResult<RecordBatch> {
+		let entries: Vec<&Qso> = entries.into_iter().collect();
+
+		let frequency = entries.iter()
+			.map(|qso| qso.frequency.as_khz())
+			.collect::<UInt32Array>();
+
+		let mode = entries.iter()
+			.map(|qso| Some(qso.mode.to_string()))
+			.collect::<StringArray>();
+
+		let datetime = entries.iter()
+			.map(|qso| Some(datetime::timestamp(&qso.datetime_utc())))
+			.collect::<TimestampSecondArray>()
+			.with_timezone("UTC");
+
+		let transmitter_id = entries.iter()
+			.map(|qso| qso.transmitter_id)
+			.collect::<UInt8Array>();
+
+		RecordBatch::try_new(Self::arrow_schema(), vec![
+			Arc::new(frequency),
+			Arc::new(mode),
+			Arc::new(datetime),
+			string_column(&entries, |qso| qso.call_sent.as_str()),
+			string_column(&entries, |qso| &qso.exch_sent),
+			string_column(&entries, |qso| qso.call_recvd.as_str()),
+			string_column(&entries, |qso| &qso.exch_recvd),
+			Arc::new(transmitter_id)
+		]).map_err(arrow_error)
+	}
+}
+
+impl CabrilloLog {
+	/// Convert the QSO entries of this log into an Arrow record batch, using the
+	/// schema from `Qso::arrow_schema()`. Ignored (X-QSO) entries are not included.
+	pub fn to_record_batch(&self) -> CabrilloResult<RecordBatch> {
+		Qso::to_record_batch(&self.entries)
+	}
+
+	/// Write the QSO entries of this log as a Parquet file.
+	pub fn to_parquet<W: Write + Send>(&self, writer: W) -> CabrilloResult<()> {
+		let batch = self.to_record_batch()?;
+		let mut writer = ArrowWriter::try_new(writer, batch.schema(), None).map_err(arrow_error)?;
+
+		writer.write(&batch).map_err(arrow_error)?;
+		writer.close().map_err(arrow_error)?;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use arrow_array::{Array, StringArray, TimestampSecondArray, UInt32Array, UInt8Array};
+	use crate::*;
+
+	#[test]
+	fn record_batch() {
+		let log = CabrilloLog::from_path("test_data/cqww.txt").unwrap();
+		let batch = log.to_record_batch().unwrap();
+		assert_eq!(batch.num_rows(), log.entries().len());
+		assert_eq!(batch.num_columns(), 8);
+
+		let frequency = batch.column(0).as_any().downcast_ref::<UInt32Array>().unwrap();
+		let datetime = batch.column(2).as_any().downcast_ref::<TimestampSecondArray>().unwrap();
+		let call_received = batch.column(5).as_any().downcast_ref::<StringArray>().unwrap();
+		let transmitter_id = batch.column(7).as_any().downcast_ref::<UInt8Array>().unwrap();
+		assert_eq!(frequency.value(0), 3799);
+		assert_eq!(datetime.value(0), datetime::timestamp(&log.entries()[0].datetime_utc()));
+		assert_eq!(call_received.value(0), "K9QZO");
+		assert_eq!(transmitter_id.value(0), 0);
+	}
+
+	#[test]
+	fn write_parquet() {
+		let log = CabrilloLog::from_path("test_data/cqww.txt").unwrap();
+		let mut output = Vec::new();
+		log.to_parquet(&mut output).unwrap();
+
+		assert!(output.starts_with(b"PAR1"));
+		assert!(output.ends_with(b"PAR1"));
+	}
+}
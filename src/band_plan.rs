@@ -0,0 +1,139 @@
+use crate::*;
+
+/// The three regions of the International Amateur Radio Union, each of which
+/// has its own band plan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IaruRegion {
+	/// Europe, Africa, the Middle East, and northern Asia.
+	Region1,
+	/// The Americas.
+	Region2,
+	/// The rest of Asia and the Pacific.
+	Region3
+}
+
+/// Where the CW segment of a band ends and the phone segment begins, in kHz.
+struct Segments {
+	band: Band,
+	cw_end: u32,
+	phone_start: u32
+}
+
+const fn segments(band: Band, cw_end: u32, phone_start: u32) -> Segments {
+	Segments { band, cw_end, phone_start }
+}
+
+const REGION1: [Segments; 6] = [
+	segments(Band::Band160M, 1838, 1840),
+	segments(Band::Band80M, 3580, 3600),
+	segments(Band::Band40M, 7040, 7050),
+	segments(Band::Band20M, 14070, 14100),
+	segments(Band::Band15M, 21070, 21150),
+	segments(Band::Band10M, 28070, 28300)
+];
+
+const REGION2: [Segments; 6] = [
+	segments(Band::Band160M, 1840, 1843),
+	segments(Band::Band80M, 3580, 3600),
+	segments(Band::Band40M, 7040, 7050),
+	segments(Band::Band20M, 14070, 14100),
+	segments(Band::Band15M, 21070, 21150),
+	segments(Band::Band10M, 28070, 28300)
+];
+
+const REGION3: [Segments; 6] = [
+	segments(Band::Band160M, 1830, 1840),
+	segments(Band::Band80M, 3535, 3600),
+	segments(Band::Band40M, 7025, 7040),
+	segments(Band::Band20M, 14070, 14112),
+	segments(Band::Band15M, 21070, 21150),
+	segments(Band::Band10M, 28050, 28300)
+];
+
+/// Reports QSOs whose frequency is in the wrong part of the band for their
+/// mode according to the band plan of an IARU region: CW and digital QSOs in
+/// the phone segment, and phone QSOs in the CW segment. The segments are a
+/// simplification of the HF contest bands meant to catch typos, such as a CW
+/// QSO logged on 14300 kHz, rather than to enforce the band plan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BandPlan {
+	region: IaruRegion
+}
+
+impl BandPlan {
+	pub fn new(region: IaruRegion) -> Self {
+		Self { region }
+	}
+
+	pub fn region(&self) -> IaruRegion {
+		self.region
+	}
+
+	fn segments(&self, band: Band) -> Option<&'static Segments> {
+		let plan: &'static [Segments] = match self.region {
+			IaruRegion::Region1 => &REGION1,
+			IaruRegion::Region2 => &REGION2,
+			IaruRegion::Region3 => &REGION3
+		};
+
+		plan.iter().find(|segments| segments.band == band)
+	}
+
+	/// Why `qso` looks out of place in the band plan, if it does.
+	pub fn check_qso(&self, qso: &Qso) -> Option<String> {
+		let segments = self.segments(qso.band()?)?;
+		let khz = qso.frequency.as_khz()?;
+
+		match qso.mode {
+			Mode::Cw | Mode::Rtty | Mode::Digital if khz >= segments.phone_start =>
+				Some(format!("{} QSO on {} is in the phone segment", qso.mode, qso.frequency)),
+			Mode::Phone | Mode::Fm if khz < segments.cw_end =>
+				Some(format!("{} QSO on {} is in the CW segment", qso.mode, qso.frequency)),
+			_ => None
+		}
+	}
+}
+
+impl Rule for BandPlan {
+	fn name(&self) -> &str {
+		"band-plan"
+	}
+
+	fn check(&self, log: &CabrilloLog, report: &mut ValidationReport) {
+		for qso in &log.entries {
+			if let Some(message) = self.check_qso(qso) {
+				report.add(Finding::new(self.name(), Severity::Warning, "QSO", message).with_line(qso.line));
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn band_plan() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"QSO: 14025 CW 2023-01-14 0000 W1AW 599 CT K1AB 599 MA\n",
+			"QSO: 14300 CW 2023-01-14 0010 W1AW 599 CT K1AC 599 MA\n",
+			"QSO: 14035 PH 2023-01-14 0020 W1AW 59 CT K1AD 59 MA\n",
+			"QSO: 7030 PH 2023-01-14 0030 W1AW 59 CT K1AE 59 MA\n",
+			"QSO: 14074 DG 2023-01-14 0040 W1AW 599 CT K1AF 599 MA\n",
+			"END-OF-LOG:"
+		);
+
+		let log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+		let mut rules = RuleSet::empty();
+		rules.register(BandPlan::new(IaruRegion::Region2));
+		let report = log.validate(&rules);
+		let lines: Vec<Option<usize>> = report.findings().iter().map(Finding::line).collect();
+		assert_eq!(lines, vec![Some(2), Some(3), Some(4)]);
+		assert_eq!(report.findings()[0].message(), "CW QSO on 14300 KHz is in the phone segment");
+
+		let mut rules = RuleSet::empty();
+		rules.register(BandPlan::new(IaruRegion::Region3));
+		assert_eq!(log.validate(&rules).findings().len(), 2);
+	}
+}
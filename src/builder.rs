@@ -0,0 +1,267 @@
+use nom::{sequence::terminated, combinator::eof};
+use crate::*;
+
+/// Builder for constructing a `CabrilloLog` in code. Required tags are checked,
+/// and free-form values with a defined format are validated, when `build()` is called.
+#[derive(Debug, Clone)]
+pub struct CabrilloLogBuilder {
+	log: CabrilloLog,
+	/// Checked and converted to a `GridSquare` by `build()`.
+	grid_locator: Option<String>
+}
+
+impl Default for CabrilloLogBuilder {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl CabrilloLogBuilder {
+	pub fn new() -> Self {
+		Self {
+			log: CabrilloLog::new(),
+			grid_locator: None
+		}
+	}
+
+	/// Version of the Cabrillo format, either 2.0 or 3.0. Defaults to 3.0.
+	pub fn version(mut self, version: CabrilloVersion) -> Self {
+		self.log.version = version;
+		self
+	}
+
+	pub fn callsign<S: Into<String>>(mut self, callsign: S) -> Self {
+		self.log.callsign = Some(callsign.into());
+		self
+	}
+
+	pub fn contest<S: Into<String>>(mut self, contest: S) -> Self {
+		self.log.contest = Some(contest.into());
+		self
+	}
+
+	pub fn category_assisted(mut self, assisted: bool) -> Self {
+		self.log.category_assisted = Some(assisted);
+		self
+	}
+
+	pub fn category_band(mut self, band: Band) -> Self {
+		self.log.category_band = Some(band);
+		self
+	}
+
+	pub fn category_mode(mut self, mode: Mode) -> Self {
+		self.log.category_mode = Some(mode);
+		self
+	}
+
+	pub fn category_operator(mut self, op: OperatorCategory) -> Self {
+		self.log.category_operator = Some(op);
+		self
+	}
+
+	pub fn category_power(mut self, power: PowerCategory) -> Self {
+		self.log.category_power = Some(power);
+		self
+	}
+
+	pub fn category_station(mut self, station: StationCategory) -> Self {
+		self.log.category_station = Some(station);
+		self
+	}
+
+	pub fn category_time(mut self, time: TimeCategory) -> Self {
+		self.log.category_time = Some(time);
+		self
+	}
+
+	pub fn category_transmitter(mut self, xmitter: TransmitterCategory) -> Self {
+		self.log.category_transmitter = Some(xmitter);
+		self
+	}
+
+	pub fn category_overlay(mut self, overlay: OverlayCategory) -> Self {
+		self.log.category_overlay = Some(overlay);
+		self
+	}
+
+	pub fn certificate(mut self, certificate: bool) -> Self {
+		self.log.certificate = Some(certificate);
+		self
+	}
+
+	pub fn claimed_score(mut self, score: u32) -> Self {
+		self.log.claimed_score = Some(score);
+		self
+	}
+
+	pub fn club<S: Into<String>>(mut self, club: S) -> Self {
+		self.log.club = Some(club.into());
+		self
+	}
+
+	pub fn created_by<S: Into<String>>(mut self, created_by: S) -> Self {
+		self.log.created_by = Some(created_by.into());
+		self
+	}
+
+	pub fn email<S: Into<String>>(mut self, email: S) -> Self {
+		self.log.email = Some(email.into());
+		self
+	}
+
+	pub fn grid_locator<S: Into<String>>(mut self, grid_locator: S) -> Self {
+		self.grid_locator = Some(grid_locator.into());
+		self
+	}
+
+	pub fn location<S: Into<String>>(mut self, location: S) -> Self {
+		self.log.location = Some(location.into());
+		self
+	}
+
+	pub fn arrl_section(mut self, section: Section) -> Self {
+		self.log.arrl_section = Some(section);
+		self
+	}
+
+	pub fn name<S: Into<String>>(mut self, name: S) -> Self {
+		self.log.name = Some(name.into());
+		self
+	}
+
+	/// Append a line to the mailing address.
+	pub fn address_line(mut self, line: &str) -> Self {
+		self.log.push_address_line(line);
+		self
+	}
+
+	/// Add an operator callsign. The host station may be marked with a leading '@'.
+	pub fn operator<S: Into<String>>(mut self, callsign: S) -> Self {
+		self.log.operators.push(Callsign::from(callsign.into()));
+		self
+	}
+
+	/// Add several operator callsigns.
+	pub fn operators<I: IntoIterator<Item = String>>(mut self, callsigns: I) -> Self {
+		self.log.operators.extend(callsigns.into_iter().map(Callsign::from));
+		self
+	}
+
+	pub fn offtime(mut self, begin: DateTime, end: DateTime) -> Self {
+		self.log.offtimes.push(Offtime::new(begin, end));
+		self
+	}
+
+	/// Append a line of comments.
+	pub fn soapbox_line(mut self, line: &str) -> Self {
+		self.log.push_soapbox_line(line);
+		self
+	}
+
+	/// Add a custom or non-standard tag.
+	pub fn other_tag<S: Into<String>>(mut self, tag: S, value: S) -> Self {
+		self.log.other_tags.push(tag, value);
+		self
+	}
+
+	pub fn debug(mut self, debug: bool) -> Self {
+		self.log.debug = debug;
+		self
+	}
+
+	pub fn add_qso(mut self, qso: Qso) -> Self {
+		self.log.entries.push(qso);
+		self
+	}
+
+	/// Add a QSO which will be written as 'X-QSO'.
+	pub fn add_ignored_qso(mut self, qso: Qso) -> Self {
+		self.log.ignored_entries.push(qso);
+		self
+	}
+
+	/// Validate and return the finished log.
+	pub fn build(self) -> CabrilloResult<CabrilloLog> {
+		let mut log = self.log;
+
+		if let CabrilloVersion::Other(version) = &log.version {
+			return Err(invalid("START-OF-LOG", format!("Unsupported version '{}'", version)));
+		}
+
+		if log.callsign.is_none() {
+			return Err(invalid("CALLSIGN", "Missing required tag".into()));
+		}
+
+		if log.contest.is_none() {
+			return Err(invalid("CONTEST", "Missing required tag".into()));
+		}
+
+		if let Some(ref email) = log.email {
+			terminated(cabrillo_email, eof)(email.as_str())
+				.map_err(|_| invalid("EMAIL", format!("Invalid email address '{}'", email)))?;
+		}
+
+		if let Some(grid) = self.grid_locator {
+			log.grid_locator = Some(grid.parse()
+				.map_err(|_| invalid("GRID-LOCATOR", format!("Invalid grid locator '{}'", grid)))?);
+		}
+
+		Ok(log)
+	}
+}
+
+fn invalid(tag: &str, message: String) -> CabrilloError {
+	CabrilloError::new(tag, 0, CabrilloErrorKind::Other(message))
+}
+
+impl CabrilloLog {
+	/// Start building a new log in code.
+	pub fn builder() -> CabrilloLogBuilder {
+		CabrilloLogBuilder::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn build() {
+		let log = CabrilloLog::builder()
+			.callsign("W1AW")
+			.contest("ARRL-DX-CW")
+			.category_operator(OperatorCategory::SingleOp)
+			.category_band(Band::Band20M)
+			.grid_locator("FN31pr")
+			.address_line("225 Main St")
+			.address_line("Newington, CT")
+			.soapbox_line("Great conditions")
+			.build()
+			.unwrap();
+
+		assert_eq!(log.callsign(), &Some("W1AW".to_string()));
+		assert_eq!(log.category_band(), &Some(Band::Band20M));
+		assert_eq!(log.address(), &Some("225 Main St\nNewington, CT".to_string()));
+
+		let output = log.to_string();
+		assert!(output.contains("CALLSIGN: W1AW\n"));
+		assert!(output.contains("ADDRESS: Newington, CT\n"));
+	}
+
+	#[test]
+	fn build_missing_tags() {
+		let result = CabrilloLog::builder().contest("CQ-WW-CW").build();
+		assert_eq!(result.unwrap_err().tag(), "CALLSIGN");
+
+		let result = CabrilloLog::builder().callsign("W1AW").build();
+		assert_eq!(result.unwrap_err().tag(), "CONTEST");
+
+		let result = CabrilloLog::builder()
+			.callsign("W1AW")
+			.contest("CQ-WW-CW")
+			.grid_locator("ZZ99")
+			.build();
+		assert_eq!(result.unwrap_err().tag(), "GRID-LOCATOR");
+	}
+}
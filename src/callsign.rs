@@ -0,0 +1,390 @@
+use crate::*;
+
+/// Designators after the base call which mean the station was not at its
+/// home location: portable, mobile, maritime mobile, and aeronautical mobile.
+const PORTABLE_DESIGNATORS: [&str; 4] = ["P", "M", "MM", "AM"];
+
+/// A callsign as written in a QSO or the OPERATORS tag, such as `W1ABC`,
+/// `EA8/W1ABC/P`, or `@N1MM` for the host station.
+///
+/// The parts around the base call are read from its shape: a part before it
+/// names the entity operated from, and parts after it are designators such as
+/// `P` or a call area.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Callsign {
+	call: String,
+	host: bool
+}
+
+/// Whether `part` has the shape of a base call: a prefix of one to three
+/// characters containing a letter, a digit, and a suffix of letters. The
+/// prefix may start with a digit, as in `4S7ABC`.
+pub(crate) fn callsign_shaped(part: &str) -> bool {
+	let Some(digit) = part.char_indices().skip(1).find(|(_, c)| c.is_ascii_digit()).map(|(idx, _)| idx) else { return false };
+	let (prefix, rest) = part.split_at(digit);
+	let suffix = rest.trim_start_matches(|c: char| c.is_ascii_digit());
+
+	(1..=3).contains(&prefix.len())
+		&& prefix.chars().all(|c| c.is_ascii_alphanumeric())
+		&& prefix.chars().any(|c| c.is_ascii_alphabetic())
+		&& !suffix.is_empty()
+		&& suffix.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+/// How closely callsigns must follow the usual form to be accepted when a log
+/// is parsed, set with `ParseOptions::callsigns`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum CallsignStrictness {
+	/// Any letters and digits, in up to four parts separated by slashes, such
+	/// as `TEST` or `F/W1ABC/P/QRP`.
+	Loose,
+	/// The shape checked by `Callsign::is_valid()`, which accepts short and
+	/// special event calls but not exchange values such as `599`.
+	#[default]
+	Standard,
+	/// The standard shape, with a base call following the ITU Radio
+	/// Regulations: a prefix of one or two characters, or three starting with a
+	/// digit, which does not start with Q, 0, or 1, then a single digit and up
+	/// to four characters ending in a letter.
+	Strict
+}
+
+/// Whether `base` has the form of an amateur call under the ITU Radio
+/// Regulations.
+fn itu_shaped(base: &str) -> bool {
+	let call = base.to_ascii_uppercase();
+	let Some(first) = call.chars().next() else { return false };
+	let max_prefix = if first.is_ascii_digit() { 3 } else { 2 };
+
+	itu_prefix_start(first) && (1..=max_prefix).any(|len| {
+		let (prefix, rest) = call.split_at(len.min(call.len()));
+		let mut rest = rest.chars();
+		let suffix = &call[(len + 1).min(call.len())..];
+
+		prefix.chars().all(|c| c.is_ascii_alphanumeric())
+			&& prefix.chars().any(|c| c.is_ascii_alphabetic())
+			&& rest.next().is_some_and(|c| c.is_ascii_digit())
+			&& (1..=4).contains(&suffix.len())
+			&& suffix.chars().all(|c| c.is_ascii_alphanumeric())
+			&& suffix.ends_with(|c: char| c.is_ascii_alphabetic())
+	})
+}
+
+/// The WPX prefix of one part of a callsign: the letters and digits up to and
+/// including its last digit, or its first two letters followed by 0 if it has
+/// no digits, as in `RA0` for `RAEM`.
+fn wpx_part(part: &str) -> String {
+	let part = part.to_ascii_uppercase();
+	let prefix = part.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+
+	if prefix.is_empty() {
+		format!("{}0", part.chars().take(2).collect::<String>())
+	} else {
+		prefix.to_string()
+	}
+}
+
+/// Q is reserved for the Q code, and no prefixes start with 0 or 1.
+fn itu_prefix_start(first: char) -> bool {
+	first.is_ascii_alphanumeric() && !matches!(first.to_ascii_uppercase(), 'Q' | '0' | '1')
+}
+
+impl Callsign {
+	pub fn new<S: Into<String>>(call: S) -> Self {
+		Self { call: call.into(), host: false }
+	}
+
+	/// The station the log was operated from, written as `@CALL` in the
+	/// OPERATORS tag.
+	pub fn host<S: Into<String>>(call: S) -> Self {
+		Self { call: call.into(), host: true }
+	}
+
+	/// The callsign without the '@' marker.
+	pub fn as_str(&self) -> &str {
+		&self.call
+	}
+
+	pub fn is_host(&self) -> bool {
+		self.host
+	}
+
+	fn parts(&self) -> Vec<&str> {
+		self.call.split('/').collect()
+	}
+
+	/// Index of the base call among the parts: the longest one shaped like a
+	/// callsign, or the longest part if none is.
+	fn base_index(&self) -> usize {
+		let parts = self.parts();
+		let longest = |candidates: &mut dyn Iterator<Item = (usize, &&str)>| candidates
+			.fold(None, |best: Option<(usize, usize)>, (idx, part)| match best {
+				Some((_, len)) if len >= part.len() => best,
+				_ => Some((idx, part.len()))
+			})
+			.map(|(idx, _)| idx);
+
+		longest(&mut parts.iter().enumerate().filter(|(_, part)| callsign_shaped(part)))
+			.or_else(|| longest(&mut parts.iter().enumerate()))
+			.unwrap_or(0)
+	}
+
+	/// The call of the station itself, such as `W1ABC` in `EA8/W1ABC/P`.
+	pub fn base(&self) -> &str {
+		self.parts()[self.base_index()]
+	}
+
+	/// The prefix of the entity operated from, written before the base call,
+	/// such as `EA8` in `EA8/W1ABC/P`.
+	pub fn prefix(&self) -> Option<&str> {
+		let idx = self.base_index();
+		(idx > 0).then(|| self.parts()[idx - 1])
+	}
+
+	/// Designators written after the base call, such as `P` in `EA8/W1ABC/P`.
+	pub fn designators(&self) -> Vec<&str> {
+		let idx = self.base_index();
+		self.parts().split_off(idx + 1)
+	}
+
+	/// The part naming where the station operated from: the prefix, a
+	/// designator of another entity such as `KH6` in `W1ABC/KH6`, or else the
+	/// base call.
+	pub(crate) fn entity_part(&self) -> &str {
+		self.prefix()
+			.or_else(|| self.designators().into_iter().find(|designator| {
+				designator.len() > 1
+					&& designator.chars().any(|c| c.is_ascii_alphabetic())
+					&& !PORTABLE_DESIGNATORS.contains(designator)
+					&& *designator != "QRP"
+			}))
+			.unwrap_or(self.base())
+	}
+
+	/// The prefix of the callsign under the rules of the CQ WPX contest, such
+	/// as `WD8` for `WD8ABC`. A prefix or another entity written with the
+	/// call takes its place, as `EA8` in `EA8/W1ABC` or `PJ0` in `PJ/W1ABC`, and
+	/// a call area written after the call replaces its digits, as `W4` in
+	/// `W1ABC/4`. Portable and mobile designators are ignored.
+	pub fn wpx_prefix(&self) -> String {
+		let base = self.base();
+		let entity = self.entity_part();
+
+		if entity != base {
+			return wpx_part(entity);
+		}
+
+		let call_area = self.designators().into_iter()
+			.find(|designator| designator.len() == 1 && designator.chars().all(|c| c.is_ascii_digit()));
+
+		match call_area {
+			Some(digit) => format!("{}{}", wpx_part(base).trim_end_matches(|c: char| c.is_ascii_digit()), digit),
+			None => wpx_part(base)
+		}
+	}
+
+	/// Whether a designator marks the station as portable or mobile.
+	pub fn is_portable(&self) -> bool {
+		self.designators().iter().any(|designator| PORTABLE_DESIGNATORS.contains(designator))
+	}
+
+	/// Whether the callsign has a base call, at most one prefix before it, and
+	/// only letters and digits between the slashes.
+	pub fn is_valid(&self) -> bool {
+		let parts = self.parts();
+		let idx = self.base_index();
+
+		parts.len() <= 3
+			&& idx <= 1
+			&& callsign_shaped(parts[idx])
+			&& parts.iter().all(|part| (1..=8).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric()))
+	}
+
+	/// Whether the callsign is acceptable at the given level of strictness.
+	pub fn is_valid_at(&self, strictness: CallsignStrictness) -> bool {
+		match strictness {
+			CallsignStrictness::Loose => {
+				let parts = self.parts();
+				parts.len() <= 4 && parts.iter().all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric()))
+			},
+			CallsignStrictness::Standard => self.is_valid(),
+			CallsignStrictness::Strict => {
+				self.is_valid()
+					&& itu_shaped(self.base())
+					&& self.prefix().is_none_or(|prefix| prefix.chars().next().is_some_and(itu_prefix_start))
+					&& self.designators().iter().all(|designator| designator.len() <= 4)
+			}
+		}
+	}
+}
+
+/// Reads the '@' marker of the host station, without checking the callsign.
+impl From<&str> for Callsign {
+	fn from(call: &str) -> Self {
+		match call.strip_prefix('@') {
+			Some(call) => Callsign::host(call),
+			None => Callsign::new(call)
+		}
+	}
+}
+
+impl From<String> for Callsign {
+	fn from(call: String) -> Self {
+		Callsign::from(call.as_str())
+	}
+}
+
+/// Parsing checks the callsign with `is_valid()`.
+impl FromStr for Callsign {
+	type Err = CabrilloErrorKind;
+
+	fn from_str(input: &str) -> Result<Self, Self::Err> {
+		Some(Callsign::from(input))
+			.filter(Callsign::is_valid)
+			.ok_or_else(|| CabrilloErrorKind::ParseError(format!("'{}' is not a valid Callsign", input)))
+	}
+}
+
+impl Display for Callsign {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.host {
+			f.pad(&format!("@{}", self.call))
+		} else {
+			f.pad(&self.call)
+		}
+	}
+}
+
+/// Compares with the callsign as written, including any '@' marker.
+impl PartialEq<str> for Callsign {
+	fn eq(&self, other: &str) -> bool {
+		match other.strip_prefix('@') {
+			Some(call) => self.host && self.call == call,
+			None => !self.host && self.call == other
+		}
+	}
+}
+
+impl PartialEq<&str> for Callsign {
+	fn eq(&self, other: &&str) -> bool {
+		self == *other
+	}
+}
+
+impl PartialEq<String> for Callsign {
+	fn eq(&self, other: &String) -> bool {
+		self == other.as_str()
+	}
+}
+
+impl CabrilloLog {
+	/// Check the callsigns of a CALLSIGN or OPERATORS value before it is parsed.
+	pub(crate) fn check_tag_callsigns(tag: &str, value: &str, strictness: CallsignStrictness) -> Result<(), String> {
+		let calls: Vec<&str> = match tag {
+			"CALLSIGN" => vec![value.trim()],
+			"OPERATORS" => value.split(|c: char| c == ',' || c.is_whitespace()).filter(|call| !call.is_empty()).collect(),
+			_ => return Ok(())
+		};
+
+		match calls.into_iter().find(|call| !Callsign::from(*call).is_valid_at(strictness)) {
+			Some(call) => Err(format!("'{}' is not a valid callsign", call)),
+			None => Ok(())
+		}
+	}
+
+	/// Check the callsigns of the QSO just parsed, removing it if they are not
+	/// acceptable.
+	pub(crate) fn check_qso_callsigns(&mut self, ignored: bool, strictness: CallsignStrictness) -> Result<(), String> {
+		let entries = if ignored { &mut self.ignored_entries } else { &mut self.entries };
+		let Some(qso) = entries.last() else { return Ok(()) };
+
+		let invalid = [&qso.call_sent, &qso.call_recvd].into_iter()
+			.find(|call| !call.is_valid_at(strictness))
+			.map(|call| format!("'{}' is not a valid callsign", call));
+
+		match invalid {
+			Some(message) => {
+				entries.pop();
+				Err(message)
+			},
+			None => Ok(())
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn callsign_parts() {
+		let call: Callsign = "EA8/W1ABC/P".parse().unwrap();
+		assert_eq!(call.prefix(), Some("EA8"));
+		assert_eq!(call.base(), "W1ABC");
+		assert_eq!(call.designators(), vec!["P"]);
+		assert!(call.is_portable());
+
+		let call = Callsign::from("W1ABC/4");
+		assert_eq!(call.prefix(), None);
+		assert_eq!(call.base(), "W1ABC");
+		assert!(!call.is_portable());
+
+		let call = Callsign::from("VP2E/K1ABC");
+		assert_eq!(call.prefix(), Some("VP2E"));
+		assert_eq!(call.base(), "K1ABC");
+
+		let host = Callsign::from("@N1MM");
+		assert!(host.is_host());
+		assert_eq!(host.as_str(), "N1MM");
+		assert_eq!(host, "@N1MM");
+		assert_eq!(format!("{:<6}|", host), "@N1MM |");
+
+		let prefix = |call: &str| Callsign::from(call).wpx_prefix();
+		assert_eq!(prefix("WD8ABC"), "WD8");
+		assert_eq!(prefix("hg19abc"), "HG19");
+		assert_eq!(prefix("LY1000X"), "LY1000");
+		assert_eq!(prefix("2E0ABC"), "2E0");
+		assert_eq!(prefix("RAEM"), "RA0");
+		assert_eq!(prefix("EA8/W1ABC/P"), "EA8");
+		assert_eq!(prefix("PJ/W1ABC"), "PJ0");
+		assert_eq!(prefix("W1ABC/KH6"), "KH6");
+		assert_eq!(prefix("WN5N/7"), "WN7");
+		assert_eq!(prefix("K1ABC/MM"), "K1");
+
+		assert!("599".parse::<Callsign>().is_err());
+		assert!("K1AB/P/QRP/X".parse::<Callsign>().is_err());
+		assert!("W1-AW".parse::<Callsign>().is_err());
+	}
+
+	#[test]
+	fn callsign_strictness() {
+		use CallsignStrictness::*;
+
+		let levels = |call: &str| [Loose, Standard, Strict].map(|level| Callsign::from(call).is_valid_at(level));
+		assert_eq!(levels("W1AW"), [true, true, true]);
+		assert_eq!(levels("F/W1ABC/P"), [true, true, true]);
+		assert_eq!(levels("2E0ABC"), [true, true, true]);
+		assert_eq!(levels("3DA0XYZ"), [true, true, true]);
+		assert_eq!(levels("W1ABCDE"), [true, true, false]);
+		assert_eq!(levels("QA1ABC"), [true, true, false]);
+		assert_eq!(levels("TEST"), [true, false, false]);
+		assert_eq!(levels("W1A-B"), [false, false, false]);
+
+		let buf = "START-OF-LOG: 3.0\nQSO: 14025 CW 2023-01-14 0000 W1AW 599 CT TEST 599 MA\nEND-OF-LOG:\n";
+		let error = CabrilloLog::from_buffer(buf.as_bytes()).unwrap_err();
+		assert_eq!((error.tag().as_str(), error.line()), ("QSO", 1));
+
+		let options = ParseOptions { callsigns: Loose, ..ParseOptions::default() };
+		assert_eq!(CabrilloLog::from_buffer_with(buf.as_bytes(), &options).unwrap().entries().len(), 1);
+
+		let options = ParseOptions { skip_bad_lines: true, ..ParseOptions::default() };
+		let (log, report) = CabrilloLog::from_buffer_validated(buf.as_bytes(), &options).unwrap();
+		assert!(log.entries().is_empty());
+		assert_eq!(report.skipped_lines().len(), 1);
+
+		let options = ParseOptions { callsigns: Strict, ..ParseOptions::default() };
+		assert!(CabrilloLog::from_buffer_with(b"OPERATORS: K1AB, QA1ABC", &options).is_err());
+		assert!(CabrilloLog::from_buffer_with(b"OPERATORS: K1AB, @N1MM", &options).is_ok());
+		assert!(CabrilloLog::from_buffer(b"CALLSIGN: F/W1ABC").is_ok());
+	}
+}
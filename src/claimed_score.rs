@@ -0,0 +1,118 @@
+use crate::*;
+
+/// How far a claimed score may be from a computed one before it is reported
+/// by `CabrilloLog::validate_contest()`, as a fraction of the computed score.
+/// Entrants rarely remove every dupe and busted call, so claims run a little
+/// high.
+const CONTEST_SCORE_TOLERANCE: f64 = 0.02;
+
+/// Reports a CLAIMED-SCORE which cannot be right for the QSOs in the log: more
+/// than every QSO scoring the most points with the most multipliers, a score
+/// without any QSOs, or a score of 0 with QSOs. Checklogs are not scored, so
+/// their claimed score is not checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClaimedScore {
+	max_points: u32,
+	max_multipliers: u32
+}
+
+impl ClaimedScore {
+	/// Bounds for a contest where a QSO is worth at most `max_points`, and no
+	/// log can have more than `max_multipliers`.
+	pub fn new(max_points: u32, max_multipliers: u32) -> Self {
+		Self { max_points, max_multipliers }
+	}
+
+	/// The highest score `log` could have.
+	pub fn upper_bound(&self, log: &CabrilloLog) -> u64 {
+		let multipliers = self.max_multipliers.min(log.entries.len() as u32);
+		log.entries.len() as u64 * self.max_points as u64 * multipliers as u64
+	}
+}
+
+impl Rule for ClaimedScore {
+	fn name(&self) -> &str {
+		"claimed-score"
+	}
+
+	fn check(&self, log: &CabrilloLog, report: &mut ValidationReport) {
+		let Some(claimed) = log.claimed_score.filter(|_| !log.is_checklog()) else { return };
+		let line = log.tag_line("CLAIMED-SCORE", 0);
+		let bound = self.upper_bound(log);
+
+		let finding = match claimed as u64 {
+			0 if !log.entries.is_empty() => Some((Severity::Warning, "Claimed score is 0 but the log has QSOs".to_string())),
+			_ if log.entries.is_empty() => Some((Severity::Warning, format!("Claimed score {} but the log has no QSOs", claimed))),
+			claimed if claimed > bound => Some((Severity::Error,
+				format!("Claimed score {} is more than the most {} QSOs could score, {}", claimed, log.entries.len(), bound))),
+			_ => None
+		};
+
+		if let Some((severity, message)) = finding {
+			report.add(Finding::new(self.name(), severity, "CLAIMED-SCORE", message).with_line(line));
+		}
+	}
+}
+
+impl CabrilloLog {
+	/// Compare CLAIMED-SCORE with a score worked out from the log, returning a
+	/// finding if they differ by more than `tolerance`, a fraction of the
+	/// computed score. Checklogs are never reported.
+	pub fn check_claimed_score(&self, computed: u64, tolerance: f64) -> Option<Finding> {
+		let claimed = self.claimed_score.filter(|_| !self.is_checklog())? as u64;
+
+		(claimed.abs_diff(computed) as f64 > computed as f64 * tolerance).then(|| {
+			Finding::new("claimed-score", Severity::Warning, "CLAIMED-SCORE",
+				format!("Claimed score {} differs from the computed score {}", claimed, computed))
+				.with_line(self.tag_line("CLAIMED-SCORE", 0))
+		})
+	}
+
+	/// Compare CLAIMED-SCORE with the score of the contest rules, if they
+	/// compute one. Checklogs are not scored at all.
+	pub(crate) fn check_contest_score(&self, rules: &dyn ContestRules) -> Option<Finding> {
+		if self.is_checklog() {
+			return None;
+		}
+
+		self.check_claimed_score(rules.score(self)?, CONTEST_SCORE_TOLERANCE)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn claimed_score() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"CLAIMED-SCORE: 100\n",
+			"QSO: 14025 CW 2023-01-14 0000 W1AW 599 CT K1AB 599 MA\n",
+			"QSO: 14025 CW 2023-01-14 0010 W1AW 599 CT K1AC 599 MA\n",
+			"END-OF-LOG:"
+		);
+
+		let mut log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+		let mut rules = RuleSet::empty();
+		rules.register(ClaimedScore::new(3, 100));
+		let report = log.validate(&rules);
+		assert_eq!(report.findings().len(), 1);
+		assert_eq!(report.findings()[0].severity(), Severity::Error);
+		assert_eq!(report.findings()[0].message(), "Claimed score 100 is more than the most 2 QSOs could score, 12");
+
+		log.set_claimed_score(Some(0));
+		assert_eq!(log.validate(&rules).findings()[0].severity(), Severity::Warning);
+
+		log.set_claimed_score(Some(12));
+		assert!(log.validate(&rules).is_empty());
+		assert!(log.check_claimed_score(12, 0.0).is_none());
+		assert!(log.check_claimed_score(10, 0.1).is_some());
+		assert!(log.check_claimed_score(11, 0.1).is_none());
+
+		log.set_claimed_score(Some(100));
+		log.set_category_operator(Some(OperatorCategory::CheckLog));
+		assert!(log.validate(&rules).is_empty());
+		assert!(log.check_claimed_score(12, 0.0).is_none());
+	}
+}
@@ -0,0 +1,341 @@
+use crate::*;
+use ExchangeFieldKind::*;
+
+/// Saturday, in days from Monday.
+const SATURDAY: u32 = 5;
+
+/// The rules of a contest which a log can be checked against with
+/// `CabrilloLog::validate_contest()`: the exchange, inherited from
+/// `ContestSchema`, the bands and modes allowed, when the contest is held,
+/// and how it is scored. Implement this for contests which are not built in.
+pub trait ContestRules: ContestSchema {
+	/// Bands QSOs may be made on, or every band if empty.
+	fn bands(&self) -> &[Band];
+
+	/// Modes QSOs may be made in, or every mode if empty.
+	fn modes(&self) -> &[Mode];
+
+	/// Start and end of the contest in the given year, in UTC, or None if it is
+	/// not held on a fixed schedule.
+	fn period(&self, year: i32) -> Option<(DateTime, DateTime)>;
+
+	/// The score of `log` under these rules, or None if they do not say how to
+	/// score it.
+	fn score(&self, _log: &CabrilloLog) -> Option<u64> {
+		None
+	}
+}
+
+/// Which weekend of a month a contest is held on. Only full weekends count,
+/// so a month which ends on a Saturday has its last full weekend the week
+/// before.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weekend {
+	First,
+	Second,
+	Third,
+	Fourth,
+	Last
+}
+
+/// When a contest is held each year: the full weekend of a month it falls on,
+/// the hour UTC on Saturday it begins, and how many hours it lasts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContestPeriod {
+	month: u32,
+	weekend: Weekend,
+	start_hour: u32,
+	hours: i64
+}
+
+impl ContestPeriod {
+	pub fn new(month: u32, weekend: Weekend, start_hour: u32, hours: i64) -> Self {
+		Self { month, weekend, start_hour, hours }
+	}
+
+	/// Start and end of the period in `year`, or None if the month is invalid.
+	pub fn in_year(&self, year: i32) -> Option<(DateTime, DateTime)> {
+		let first = datetime::date(year, self.month, 1)?;
+		let first_saturday = first + Duration::days(
+			(SATURDAY as i64 - datetime::days_from_monday(&first) as i64).rem_euclid(7)
+		);
+
+		let saturday = match self.weekend {
+			Weekend::First => first_saturday,
+			Weekend::Second => first_saturday + Duration::weeks(1),
+			Weekend::Third => first_saturday + Duration::weeks(2),
+			Weekend::Fourth => first_saturday + Duration::weeks(3),
+			Weekend::Last => {
+				let mut saturday = first_saturday + Duration::weeks(4);
+
+				while datetime::month(&(saturday + Duration::days(1))) != self.month {
+					saturday -= Duration::weeks(1);
+				}
+
+				saturday
+			}
+		};
+
+		let start = datetime::at_hour(saturday, self.start_hour)?;
+		Some((start, start + Duration::hours(self.hours)))
+	}
+}
+
+/// `ContestRules` made from an exchange schema, lists of bands and modes, and
+/// a yearly contest period. The built in rules are of this kind.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StandardRules {
+	schema: ExchangeSchema,
+	bands: Vec<Band>,
+	modes: Vec<Mode>,
+	period: Option<ContestPeriod>
+}
+
+/// The contest bands from 160 to 10 meters, without the WARC bands.
+const HF_CONTEST_BANDS: [Band; 6] = [
+	Band::Band160M,
+	Band::Band80M,
+	Band::Band40M,
+	Band::Band20M,
+	Band::Band15M,
+	Band::Band10M
+];
+
+impl StandardRules {
+	pub fn new(schema: ExchangeSchema, bands: &[Band], modes: &[Mode], period: Option<ContestPeriod>) -> Self {
+		Self {
+			schema,
+			bands: bands.to_vec(),
+			modes: modes.to_vec(),
+			period
+		}
+	}
+
+	/// The rules built into this crate, for CQ-WW-CW, CQ-WW-SSB, CQ-WPX-CW,
+	/// CQ-WPX-SSB, ARRL-SS-CW, ARRL-SS-SSB, and IARU-HF.
+	pub fn builtin() -> &'static [StandardRules] {
+		&BUILTIN_RULES
+	}
+
+	/// The built in rules for `contest`, if there are any.
+	pub fn find(contest: &str) -> Option<&'static StandardRules> {
+		BUILTIN_RULES.iter().find(|rules| rules.matches(contest))
+	}
+
+	fn build_builtin() -> Vec<Self> {
+		let contest = |name: &str, exchange: &[ExchangeFieldKind], modes: &[Mode], period: ContestPeriod| {
+			Self::new(ExchangeSchema::symmetric(&[name], exchange), &HF_CONTEST_BANDS, modes, Some(period))
+		};
+
+		vec![
+			contest("CQ-WW-CW", &[Rst, Zone], &[Mode::Cw], ContestPeriod::new(11, Weekend::Last, 0, 48)),
+			contest("CQ-WW-SSB", &[Rst, Zone], &[Mode::Phone], ContestPeriod::new(10, Weekend::Last, 0, 48)),
+			contest("CQ-WPX-CW", &[Rst, Serial], &[Mode::Cw], ContestPeriod::new(5, Weekend::Last, 0, 48)),
+			contest("CQ-WPX-SSB", &[Rst, Serial], &[Mode::Phone], ContestPeriod::new(3, Weekend::Last, 0, 48)),
+			contest("ARRL-SS-CW", &[Serial, Precedence, Check, Section], &[Mode::Cw], ContestPeriod::new(11, Weekend::First, 21, 30)),
+			contest("ARRL-SS-SSB", &[Serial, Precedence, Check, Section], &[Mode::Phone], ContestPeriod::new(11, Weekend::Third, 21, 30)),
+			contest("IARU-HF", &[Rst, Other], &[Mode::Cw, Mode::Phone], ContestPeriod::new(7, Weekend::Second, 12, 24))
+		]
+	}
+}
+
+lazy_static! {
+	static ref BUILTIN_RULES: Vec<StandardRules> = StandardRules::build_builtin();
+}
+
+impl ContestSchema for StandardRules {
+	fn matches(&self, contest: &str) -> bool {
+		self.schema.matches(contest)
+	}
+
+	fn sent(&self) -> &[ExchangeFieldKind] {
+		self.schema.sent()
+	}
+
+	fn received(&self) -> &[ExchangeFieldKind] {
+		self.schema.received()
+	}
+}
+
+impl ContestRules for StandardRules {
+	fn bands(&self) -> &[Band] {
+		&self.bands
+	}
+
+	fn modes(&self) -> &[Mode] {
+		&self.modes
+	}
+
+	fn period(&self, year: i32) -> Option<(DateTime, DateTime)> {
+		self.period.and_then(|period| period.in_year(year))
+	}
+}
+
+/// Reports QSOs made outside the contest period, which sponsors usually
+/// remove from the log. The period is either given, or that of the built in
+/// `StandardRules` for the CONTEST of the log in the year of its first QSO.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContestWindow {
+	Fixed(DateTime, DateTime),
+	FromContest
+}
+
+impl ContestWindow {
+	/// A window from `start` up to but not including `end`, in UTC.
+	pub fn new(start: DateTime, end: DateTime) -> Self {
+		ContestWindow::Fixed(start, end)
+	}
+
+	/// The window of the contest named by the log.
+	pub fn from_contest() -> Self {
+		ContestWindow::FromContest
+	}
+
+	/// Start and end of the window for `log`, or None if it names no contest
+	/// with a known period.
+	pub fn bounds(&self, log: &CabrilloLog) -> Option<(DateTime, DateTime)> {
+		match self {
+			ContestWindow::Fixed(start, end) => Some((*start, *end)),
+			ContestWindow::FromContest => {
+				let rules = StandardRules::find(log.contest.as_deref()?)?;
+				let first = log.entries.iter().map(|qso| qso.datetime).min()?;
+				rules.period(datetime::year(&first))
+			}
+		}
+	}
+}
+
+fn outside_period(qso: &Qso) -> String {
+	format!("QSO at {} is outside the contest period", datetime::format(&qso.datetime, "%Y-%m-%d %H%M"))
+}
+
+impl Rule for ContestWindow {
+	fn name(&self) -> &str {
+		"contest-period"
+	}
+
+	fn check(&self, log: &CabrilloLog, report: &mut ValidationReport) {
+		let Some((start, end)) = self.bounds(log) else { return };
+
+		for qso in log.entries.iter().filter(|qso| !qso.is_within(&start, &end)) {
+			report.add(Finding::new(self.name(), Severity::Warning, "QSO", outside_period(qso)).with_line(qso.line));
+		}
+	}
+}
+
+impl Qso {
+	/// Whether the QSO was made from `start` up to but not including `end`.
+	pub fn is_within(&self, start: &DateTime, end: &DateTime) -> bool {
+		start <= &self.datetime && &self.datetime < end
+	}
+}
+
+impl CabrilloLog {
+	/// Check this log against the rules of a contest: the CONTEST tag, and the
+	/// band, mode, time, and exchanges of each QSO. Problems are reported as
+	/// findings of the rule `contest`. If the rules compute a score, a claimed
+	/// score which differs from it by more than 2% is reported too.
+	pub fn validate_contest(&self, rules: &dyn ContestRules) -> ValidationReport {
+		let mut report = ValidationReport::new();
+		let mut warn = |tag: &str, line: Option<usize>, message: String| {
+			report.add(Finding::new("contest", Severity::Warning, tag, message).with_line(line));
+		};
+
+		if let Some(contest) = self.contest.as_ref().filter(|contest| !rules.matches(contest)) {
+			warn("CONTEST", self.tag_line("CONTEST", 0), format!("Contest {} does not match the rules", contest));
+		}
+
+		for qso in &self.entries {
+			let band = qso.band();
+
+			if !rules.bands().is_empty() && !band.is_some_and(|band| rules.bands().contains(&band)) {
+				warn("QSO", qso.line, format!("Frequency {} is not on a band of the contest", qso.frequency));
+			}
+
+			if !rules.modes().is_empty() && !rules.modes().contains(&qso.mode) {
+				warn("QSO", qso.line, format!("Mode {} is not allowed in the contest", qso.mode));
+			}
+
+			if let Some((start, end)) = rules.period(datetime::year(&qso.datetime)) {
+				if !qso.is_within(&start, &end) {
+					warn("QSO", qso.line, outside_period(qso));
+				}
+			}
+
+			for message in qso.check_exchanges(rules.sent(), rules.received()) {
+				warn("QSO", qso.line, message);
+			}
+		}
+
+		report.extend(self.check_contest_score(rules));
+		report
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	fn at(year: i32, month: u32, day: u32, hour: u32) -> DateTime {
+		datetime::at_hour(datetime::date(year, month, day).unwrap(), hour).unwrap()
+	}
+
+	#[test]
+	fn contest_period() {
+		// November 2023 ends on a Thursday, and 2024 on a Saturday
+		let cqww = StandardRules::find("CQ-WW-CW").unwrap();
+		assert_eq!(cqww.period(2023), Some((at(2023, 11, 25, 0), at(2023, 11, 27, 0))));
+		assert_eq!(cqww.period(2024), Some((at(2024, 11, 23, 0), at(2024, 11, 25, 0))));
+
+		let sweepstakes = StandardRules::find("ARRL-SS-CW").unwrap();
+		assert_eq!(sweepstakes.period(2023), Some((at(2023, 11, 4, 21), at(2023, 11, 6, 3))));
+		assert!(StandardRules::find("NAQP-CW").is_none());
+	}
+
+	#[test]
+	fn validate_contest() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"CONTEST: CQ-WW-CW\n",
+			"CALLSIGN: W1AW\n",
+			"QSO: 14025 CW 2023-11-25 0000 W1AW 599 05 K1AB 599 05\n",
+			"QSO: 10110 CW 2023-11-25 0010 W1AW 599 05 K1AC 599 05\n",
+			"QSO: 14200 PH 2023-11-25 0020 W1AW 59 05 K1AD 59 05\n",
+			"QSO: 14025 CW 2023-11-27 0000 W1AW 599 05 K1AE 599 05\n",
+			"QSO: 14025 CW 2023-11-25 0030 W1AW 599 05 K1AF 599 MA\n",
+			"END-OF-LOG:"
+		);
+
+		let log = CabrilloLog::from_buffer_with(buf.as_bytes(), &ParseOptions::lenient()).unwrap();
+		let report = log.validate_contest(StandardRules::find("CQ-WW-CW").unwrap());
+		let lines: Vec<Option<usize>> = report.findings().iter().map(Finding::line).collect();
+		assert_eq!(lines, vec![Some(4), Some(5), Some(6), Some(7)]);
+		assert!(report.findings().iter().all(|finding| finding.rule() == "contest"));
+
+		let report = log.validate_contest(StandardRules::find("CQ-WPX-CW").unwrap());
+		assert_eq!(report.findings()[0].tag(), "CONTEST");
+	}
+
+	#[test]
+	fn contest_window() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"CONTEST: CQ-WW-SSB\n",
+			"QSO: 14200 PH 2023-10-27 2359 W1AW 59 05 K1AB 59 05\n",
+			"QSO: 14200 PH 2023-10-28 0000 W1AW 59 05 K1AC 59 05\n",
+			"QSO: 14200 PH 2023-10-29 2359 W1AW 59 05 K1AD 59 05\n",
+			"QSO: 14200 PH 2023-10-30 0000 W1AW 59 05 K1AE 59 05\n",
+			"END-OF-LOG:"
+		);
+
+		let log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+		let mut rules = RuleSet::empty();
+		rules.register(ContestWindow::from_contest());
+		let lines: Vec<Option<usize>> = log.validate(&rules).findings().iter().map(Finding::line).collect();
+		assert_eq!(lines, vec![Some(2), Some(5)]);
+
+		let mut rules = RuleSet::empty();
+		rules.register(ContestWindow::new(at(2023, 10, 28, 0), at(2023, 10, 29, 0)));
+		assert_eq!(log.validate(&rules).findings().len(), 3);
+	}
+}
@@ -0,0 +1,213 @@
+use crate::*;
+use location::{edit_distance, MAX_SUGGESTIONS};
+
+/// CONTEST identifiers of the Cabrillo specification, each with other names
+/// loggers and entrants commonly write for it.
+const BUILTIN_CONTESTS: &[(&str, &[&str])] = &[
+	("AP-SPRINT", &[]),
+	("ARRL-10", &["ARRL-10M", "ARRL-10-METER"]),
+	("ARRL-10-GHZ", &[]),
+	("ARRL-160", &["ARRL-160M"]),
+	("ARRL-222", &[]),
+	("ARRL-DX-CW", &["ARRLDX-CW", "ARRL-DXCW", "ARRL-INTL-DX-CW"]),
+	("ARRL-DX-SSB", &["ARRLDX-SSB", "ARRL-DXSSB", "ARRL-INTL-DX-SSB"]),
+	("ARRL-EME", &[]),
+	("ARRL-FD", &["FIELD-DAY", "ARRL-FIELD-DAY"]),
+	("ARRL-RR-CW", &[]),
+	("ARRL-RR-DIG", &[]),
+	("ARRL-RR-SSB", &[]),
+	("ARRL-RTTY", &["ARRL-RTTY-RU", "RTTY-ROUNDUP"]),
+	("ARRL-SCR", &[]),
+	("ARRL-SS-CW", &["SS-CW", "SWEEPSTAKES-CW", "ARRL-SWEEPSTAKES-CW"]),
+	("ARRL-SS-SSB", &["SS-SSB", "SWEEPSTAKES-SSB", "ARRL-SWEEPSTAKES-SSB"]),
+	("ARRL-UHF-AUG", &[]),
+	("ARRL-VHF-JAN", &[]),
+	("ARRL-VHF-JUN", &[]),
+	("ARRL-VHF-SEP", &[]),
+	("BARTG-RTTY", &[]),
+	("BARTG-SPRINT", &[]),
+	("CQ-160-CW", &["CQ160-CW", "CQ-160M-CW"]),
+	("CQ-160-SSB", &["CQ160-SSB", "CQ-160M-SSB"]),
+	("CQ-M", &[]),
+	("CQ-VHF", &[]),
+	("CQ-WPX-CW", &["CQWPX-CW", "WPX-CW"]),
+	("CQ-WPX-RTTY", &["CQWPX-RTTY", "WPX-RTTY"]),
+	("CQ-WPX-SSB", &["CQWPX-SSB", "WPX-SSB"]),
+	("CQ-WW-CW", &["CQWW-CW", "CQ-WW-DX-CW", "CQWWDX-CW"]),
+	("CQ-WW-RTTY", &["CQWW-RTTY", "CQ-WW-DX-RTTY"]),
+	("CQ-WW-SSB", &["CQWW-SSB", "CQ-WW-DX-SSB", "CQWWDX-SSB"]),
+	("DARC-WAEDC-CW", &["WAE-CW", "WAEDC-CW"]),
+	("DARC-WAEDC-RTTY", &["WAE-RTTY", "WAEDC-RTTY"]),
+	("DARC-WAEDC-SSB", &["WAE-SSB", "WAEDC-SSB"]),
+	("DL-DX-RTTY", &[]),
+	("DRCG-WW-RTTY", &[]),
+	("FCG-FQP", &["FQP"]),
+	("IARU-HF", &["IARU", "IARU-HF-CHAMPIONSHIP"]),
+	("JIDX-CW", &[]),
+	("JIDX-SSB", &[]),
+	("NAQP-CW", &[]),
+	("NAQP-RTTY", &[]),
+	("NAQP-SSB", &[]),
+	("NA-SPRINT-CW", &["NASPRINT-CW"]),
+	("NA-SPRINT-SSB", &["NASPRINT-SSB"]),
+	("NCCC-CQP", &["CQP", "CA-QSO-PARTY"]),
+	("NEQP", &["NEW-ENGLAND-QSO-PARTY"]),
+	("OCEANIA-DX-CW", &["OCDX-CW"]),
+	("OCEANIA-DX-SSB", &["OCDX-SSB"]),
+	("RDXC", &["RUSSIAN-DX"]),
+	("RSGB-IOTA", &["IOTA"]),
+	("SAC-CW", &[]),
+	("SAC-SSB", &[]),
+	("STEW-PERRY", &[]),
+	("TARA-RTTY", &[])
+];
+
+/// Compare contest names in any case, with spaces or underscores for dashes.
+fn canonical(name: &str) -> String {
+	name.trim()
+		.chars()
+		.map(|c| if c == ' ' || c == '_' { '-' } else { c.to_ascii_uppercase() })
+		.collect()
+}
+
+/// A contest of a `ContestRegistry`: its official CONTEST identifier and
+/// other names it is known by.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownContest {
+	id: String,
+	aliases: Vec<String>
+}
+
+impl KnownContest {
+	pub fn new(id: &str, aliases: &[&str]) -> Self {
+		Self {
+			id: canonical(id),
+			aliases: aliases.iter().map(|alias| canonical(alias)).collect()
+		}
+	}
+
+	pub fn id(&self) -> &String {
+		&self.id
+	}
+
+	pub fn aliases(&self) -> &Vec<String> {
+		&self.aliases
+	}
+
+	fn names(&self) -> impl Iterator<Item = &String> {
+		std::iter::once(&self.id).chain(&self.aliases)
+	}
+}
+
+/// The contests a CONTEST tag may name. As a `Rule`, reports a log whose
+/// contest is written as an alias rather than its identifier, or is not known
+/// at all, with the closest identifiers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContestRegistry(Vec<KnownContest>);
+
+impl ContestRegistry {
+	/// A registry without any contests.
+	pub fn empty() -> Self {
+		Self(Vec::new())
+	}
+
+	/// The contests of the Cabrillo specification. This is the default.
+	pub fn builtin() -> Self {
+		Self(BUILTIN_CONTESTS.iter().map(|(id, aliases)| KnownContest::new(id, aliases)).collect())
+	}
+
+	/// Add a contest, such as a state QSO party, which is not built in.
+	pub fn register(&mut self, contest: KnownContest) {
+		self.0.push(contest);
+	}
+
+	/// The contest named by `name`, either its identifier or an alias.
+	pub fn lookup(&self, name: &str) -> Option<&KnownContest> {
+		let name = canonical(name);
+		self.0.iter().find(|contest| contest.names().any(|known| *known == name))
+	}
+
+	/// Identifiers of the contests with a name close to `name`, closest first.
+	pub fn suggest(&self, name: &str) -> Vec<&str> {
+		let name = canonical(name);
+		let mut matches: Vec<(usize, &str)> = self.0.iter()
+			.filter_map(|contest| {
+				contest.names()
+					.map(|known| edit_distance(&name, known))
+					.min()
+					.map(|distance| (distance, contest.id.as_str()))
+			})
+			.filter(|(distance, id)| *distance <= 2 && *distance < id.len())
+			.collect();
+
+		matches.sort_by_key(|(distance, _)| *distance);
+		matches.into_iter().take(MAX_SUGGESTIONS).map(|(_, id)| id).collect()
+	}
+
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+}
+
+impl Default for ContestRegistry {
+	fn default() -> Self {
+		Self::builtin()
+	}
+}
+
+impl Rule for ContestRegistry {
+	fn name(&self) -> &str {
+		"contest-name"
+	}
+
+	fn check(&self, log: &CabrilloLog, report: &mut ValidationReport) {
+		let Some(contest) = &log.contest else { return };
+		let line = log.tag_line("CONTEST", 0);
+
+		let message = match self.lookup(contest) {
+			Some(known) if known.id == *contest => return,
+			Some(known) => format!("Contest '{}' should be written {}", contest, known.id),
+			None => match self.suggest(contest).as_slice() {
+				[] => format!("Unknown contest '{}'", contest),
+				suggestions => format!("Unknown contest '{}', did you mean {}?", contest, suggestions.join(" or "))
+			}
+		};
+
+		report.add(Finding::new(self.name(), Severity::Warning, "CONTEST", message).with_line(line));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn contest_registry() {
+		let registry = ContestRegistry::builtin();
+		assert_eq!(registry.lookup("cq ww cw").map(KnownContest::id), Some(&"CQ-WW-CW".to_string()));
+		assert_eq!(registry.lookup("CQWW-SSB").map(KnownContest::id), Some(&"CQ-WW-SSB".to_string()));
+		assert!(registry.lookup("CQ-WW-FM").is_none());
+		assert_eq!(registry.suggest("CQ-WW-CV"), vec!["CQ-WW-CW"]);
+		assert_eq!(registry.suggest("ARRL-DX-SB"), vec!["ARRL-DX-SSB", "ARRL-DX-CW"]);
+
+		let mut rules = RuleSet::empty();
+		rules.register(registry);
+		let messages = |buf: &str| -> Vec<String> {
+			let log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+			log.validate(&rules).findings().iter().map(|finding| finding.message().to_string()).collect()
+		};
+
+		assert!(messages("CONTEST: IARU-HF\n").is_empty());
+		assert_eq!(messages("CONTEST: CQWW-CW\n"), vec!["Contest 'CQWW-CW' should be written CQ-WW-CW"]);
+		assert_eq!(messages("CONTEST: IARU-HFF\n"), vec!["Unknown contest 'IARU-HFF', did you mean IARU-HF?"]);
+		assert_eq!(messages("CONTEST: MY-PARTY\n"), vec!["Unknown contest 'MY-PARTY'"]);
+
+		let mut registry = ContestRegistry::empty();
+		registry.register(KnownContest::new("MY-PARTY", &["MYQP"]));
+		assert_eq!(registry.lookup("myqp").map(KnownContest::id), Some(&"MY-PARTY".to_string()));
+	}
+}
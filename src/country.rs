@@ -0,0 +1,229 @@
+use crate::*;
+
+/// The continents used by contest rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Continent {
+	Africa,
+	Antarctica,
+	Asia,
+	Europe,
+	NorthAmerica,
+	Oceania,
+	SouthAmerica
+}
+
+impl Continent {
+	fn abbreviation(&self) -> &'static str {
+		match self {
+			Continent::Africa       => "AF",
+			Continent::Antarctica   => "AN",
+			Continent::Asia         => "AS",
+			Continent::Europe       => "EU",
+			Continent::NorthAmerica => "NA",
+			Continent::Oceania      => "OC",
+			Continent::SouthAmerica => "SA"
+		}
+	}
+}
+
+impl Display for Continent {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.abbreviation())
+	}
+}
+
+impl FromStr for Continent {
+	type Err = CabrilloErrorKind;
+
+	/// Read the two letter abbreviation of a continent, such as `NA`.
+	fn from_str(input: &str) -> Result<Self, Self::Err> {
+		match input.trim() {
+			"AF" => Ok(Continent::Africa),
+			"AN" => Ok(Continent::Antarctica),
+			"AS" => Ok(Continent::Asia),
+			"EU" => Ok(Continent::Europe),
+			"NA" => Ok(Continent::NorthAmerica),
+			"OC" => Ok(Continent::Oceania),
+			"SA" => Ok(Continent::SouthAmerica),
+			_ => Err(CabrilloErrorKind::ParseError(format!("'{}' is not a valid Continent", input)))
+		}
+	}
+}
+
+/// A DXCC entity, or the part of one a callsign was found in when the entity
+/// spans several zones or continents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Country {
+	name: String,
+	prefix: String,
+	continent: Continent,
+	cq_zone: u8,
+	itu_zone: u8
+}
+
+impl Country {
+	pub fn new<S: Into<String>>(name: S, prefix: S, continent: Continent, cq_zone: u8, itu_zone: u8) -> Self {
+		Self { name: name.into(), prefix: prefix.into(), continent, cq_zone, itu_zone }
+	}
+
+	pub fn name(&self) -> &String {
+		&self.name
+	}
+
+	/// The primary prefix of the entity, such as `K` or `VE`, which stands for
+	/// it as a multiplier.
+	pub fn prefix(&self) -> &String {
+		&self.prefix
+	}
+
+	pub fn continent(&self) -> Continent {
+		self.continent
+	}
+
+	pub fn cq_zone(&self) -> u8 {
+		self.cq_zone
+	}
+
+	pub fn itu_zone(&self) -> u8 {
+		self.itu_zone
+	}
+}
+
+/// A prefix or exact callsign of a `CountryTable`, with the zones and
+/// continent it overrides.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PrefixEntry {
+	country: usize,
+	cq_zone: Option<u8>,
+	itu_zone: Option<u8>,
+	continent: Option<Continent>
+}
+
+/// Countries looked up by callsign, read from a country file in the `cty.dat`
+/// format used by most contest loggers. No table is built in, since country
+/// files change several times a year.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CountryTable {
+	countries: Vec<Country>,
+	prefixes: HashMap<String, PrefixEntry>,
+	calls: HashMap<String, PrefixEntry>
+}
+
+/// Read one alias of a country file record, such as `=VE2ABC(2)[4]{NA}`.
+fn cty_alias(country: usize, token: &str) -> Result<(bool, String, PrefixEntry), CabrilloErrorKind> {
+	let invalid = || CabrilloErrorKind::ParseError(format!("'{}' is not a valid country file prefix", token));
+	let (exact, token) = match token.strip_prefix('=') {
+		Some(rest) => (true, rest),
+		None => (false, token)
+	};
+
+	let end = token.find(['(', '[', '<', '{', '~']).unwrap_or(token.len());
+	let override_of = |open: char, close: char| -> Option<&str> {
+		let start = token.find(open)? + 1;
+		let len = token[start..].find(close)?;
+		Some(&token[start..start + len])
+	};
+
+	let entry = PrefixEntry {
+		country,
+		cq_zone: override_of('(', ')').map(|zone| zone.parse().map_err(|_| invalid())).transpose()?,
+		itu_zone: override_of('[', ']').map(|zone| zone.parse().map_err(|_| invalid())).transpose()?,
+		continent: override_of('{', '}').map(str::parse).transpose()?
+	};
+
+	if end == 0 {
+		return Err(invalid());
+	}
+
+	Ok((exact, token[..end].to_ascii_uppercase(), entry))
+}
+
+impl CountryTable {
+	/// Read a country file in the `cty.dat` format: a record for each entity,
+	/// ending with a semicolon, of its name, CQ zone, ITU zone, continent,
+	/// latitude, longitude, UTC offset, and primary prefix, followed by its
+	/// prefixes and callsigns separated by commas.
+	pub fn from_cty(text: &str) -> Result<Self, CabrilloErrorKind> {
+		let mut table = Self::default();
+
+		for record in text.split(';').map(str::trim).filter(|record| !record.is_empty()) {
+			let fields: Vec<&str> = record.splitn(9, ':').map(str::trim).collect();
+			let [name, cq_zone, itu_zone, continent, _, _, _, prefix, aliases] = fields[..] else {
+				return Err(CabrilloErrorKind::ParseError(format!("Country file record '{}' has too few fields", record)));
+			};
+
+			let zone = |zone: &str| zone.parse::<u8>()
+				.map_err(|_| CabrilloErrorKind::ParseError(format!("'{}' is not a valid zone", zone)));
+
+			// entities counted only for the WAE list are marked with '*'
+			let prefix = prefix.trim_start_matches('*');
+			let country = table.countries.len();
+			table.countries.push(Country::new(name, prefix, continent.parse()?, zone(cq_zone)?, zone(itu_zone)?));
+
+			for token in aliases.split([',', '\n', '\r']).map(str::trim).filter(|token| !token.is_empty()) {
+				let (exact, key, entry) = cty_alias(country, token)?;
+				if exact { table.calls.insert(key, entry) } else { table.prefixes.insert(key, entry) };
+			}
+
+			table.prefixes.entry(prefix.to_ascii_uppercase())
+				.or_insert(PrefixEntry { country, cq_zone: None, itu_zone: None, continent: None });
+		}
+
+		Ok(table)
+	}
+
+	/// Read a country file in the `cty.dat` format from `path`.
+	pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, CabrilloErrorKind> {
+		let text = std::fs::read_to_string(path).map_err(|error| CabrilloErrorKind::IoError(error.to_string()))?;
+		Self::from_cty(&text)
+	}
+
+	pub fn countries(&self) -> &Vec<Country> {
+		&self.countries
+	}
+
+	/// The country of `call`: that of an exact match for the callsign, or
+	/// else of the longest prefix of the part which names where it was
+	/// operated from, such as `EA8` in `EA8/W1ABC` or `KH6` in `W1ABC/KH6`.
+	pub fn lookup(&self, call: &Callsign) -> Option<Country> {
+		let full = call.as_str().to_ascii_uppercase();
+
+		let entry = self.calls.get(&full).or_else(|| {
+			let location = call.entity_part().to_ascii_uppercase();
+
+			(1..=location.len()).rev().find_map(|len| self.prefixes.get(&location[..len]))
+		})?;
+
+		let country = &self.countries[entry.country];
+
+		Some(Country {
+			cq_zone: entry.cq_zone.unwrap_or(country.cq_zone),
+			itu_zone: entry.itu_zone.unwrap_or(country.itu_zone),
+			continent: entry.continent.unwrap_or(country.continent),
+			..country.clone()
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn country_table() {
+		let table = CountryTable::from_path("test_data/cty.dat").unwrap();
+		assert_eq!(table.countries().len(), 6);
+
+		let lookup = |call: &str| table.lookup(&Callsign::from(call)).map(|country| (country.prefix().clone(), country.cq_zone()));
+		assert_eq!(lookup("W1AW"), Some(("K".to_string(), 5)));
+		assert_eq!(lookup("ve3xx"), Some(("VE".to_string(), 4)));
+		assert_eq!(lookup("DL/W1AW/P"), Some(("DL".to_string(), 14)));
+		assert_eq!(lookup("W1AW/KH6"), Some(("KH6".to_string(), 31)));
+		assert_eq!(lookup("KH6ABC"), Some(("K".to_string(), 31)));
+		assert_eq!(lookup("ZS1ABC"), None);
+		assert_eq!(table.lookup(&Callsign::from("KH6ABC")).unwrap().continent(), Continent::Oceania);
+
+		assert!(CountryTable::from_cty("Nowhere: 1: 2: XX: 0: 0: 0: X: X;").is_err());
+		assert!(CountryTable::from_cty("Nowhere: 1: 2;").is_err());
+	}
+}
@@ -0,0 +1,366 @@
+use std::io::{self, Read, Write};
+use std::str::FromStr;
+use crate::*;
+
+const CSV_DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+
+const CSV_COLUMNS: [&str; 8] = [
+	"frequency_khz",
+	"mode",
+	"datetime",
+	"call_sent",
+	"exchange_sent",
+	"call_received",
+	"exchange_received",
+	"transmitter_id"
+];
+
+/// Quote a field if it contains a separator, quote, or line break.
+fn csv_field(value: &str) -> String {
+	if value.contains([',', '"', '\n', '\r']) {
+		format!("\"{}\"", value.replace('"', "\"\""))
+	} else {
+		value.to_string()
+	}
+}
+
+fn write_row<W: Write>(writer: &mut W, fields: &[String]) -> io::Result<()> {
+	let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+	writeln!(writer, "{}", row.join(","))
+}
+
+/// Maps the columns of a CSV file to QSO fields for `Qso::from_csv()`. Columns are
+/// matched by their header names, ignoring case. The default mapping reads the
+/// files written by `entries_to_csv()`.
+#[derive(Debug, Clone)]
+pub struct CsvColumns {
+	frequency: String,
+	mode: String,
+	datetime: Vec<String>,
+	datetime_format: String,
+	call_sent: String,
+	exchange_sent: String,
+	call_received: String,
+	exchange_received: String,
+	transmitter_id: Option<String>
+}
+
+impl Default for CsvColumns {
+	fn default() -> Self {
+		Self {
+			frequency: "frequency_khz".into(),
+			mode: "mode".into(),
+			datetime: vec!["datetime".into()],
+			datetime_format: CSV_DATETIME_FORMAT.into(),
+			call_sent: "call_sent".into(),
+			exchange_sent: "exchange_sent".into(),
+			call_received: "call_received".into(),
+			exchange_received: "exchange_received".into(),
+			transmitter_id: Some("transmitter_id".into())
+		}
+	}
+}
+
+impl CsvColumns {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Column holding the frequency in KHz. An empty value is read as light.
+	pub fn frequency<S: Into<String>>(mut self, column: S) -> Self {
+		self.frequency = column.into();
+		self
+	}
+
+	/// Column holding the mode, as a Cabrillo mode token (CW, PH, SSB, RY, ...).
+	pub fn mode<S: Into<String>>(mut self, column: S) -> Self {
+		self.mode = column.into();
+		self
+	}
+
+	/// Column holding the UTC date and time, parsed with a strftime-style `format` string.
+	/// With the `time` feature instead of `chrono`, `format` may only use `%Y`, `%m`,
+	/// `%d`, `%H`, `%M`, `%S`, and `%%`: `%y` is accepted for formatting only, since a
+	/// two digit year does not give the century.
+	pub fn datetime<S: Into<String>>(mut self, column: S, format: S) -> Self {
+		self.datetime = vec![column.into()];
+		self.datetime_format = format.into();
+		self
+	}
+
+	/// Separate date and time columns. Their values are joined with a space before
+	/// being parsed with `format`, which is limited as for `datetime()`.
+	pub fn date_time<S: Into<String>>(mut self, date: S, time: S, format: S) -> Self {
+		self.datetime = vec![date.into(), time.into()];
+		self.datetime_format = format.into();
+		self
+	}
+
+	pub fn call_sent<S: Into<String>>(mut self, column: S) -> Self {
+		self.call_sent = column.into();
+		self
+	}
+
+	pub fn exchange_sent<S: Into<String>>(mut self, column: S) -> Self {
+		self.exchange_sent = column.into();
+		self
+	}
+
+	pub fn call_received<S: Into<String>>(mut self, column: S) -> Self {
+		self.call_received = column.into();
+		self
+	}
+
+	pub fn exchange_received<S: Into<String>>(mut self, column: S) -> Self {
+		self.exchange_received = column.into();
+		self
+	}
+
+	/// Column holding the transmitter ID, or None if the file has none.
+	pub fn transmitter_id<S: Into<String>>(mut self, column: Option<S>) -> Self {
+		self.transmitter_id = column.map(Into::into);
+		self
+	}
+}
+
+/// Split CSV text into rows of fields, along with the line each row starts on.
+/// Quoted fields may contain separators, doubled quotes, and line breaks.
+fn csv_rows(input: &str) -> Vec<(usize, Vec<String>)> {
+	let mut rows = Vec::new();
+	let mut row = Vec::new();
+	let mut field = String::new();
+	let mut quoted = false;
+	let mut line = 0;
+	let mut row_line = 0;
+	let mut chars = input.chars().peekable();
+
+	while let Some(c) = chars.next() {
+		match c {
+			'"' if quoted && chars.peek() == Some(&'"') => {
+				chars.next();
+				field.push('"');
+			},
+			'"' => quoted = !quoted,
+			',' if !quoted => row.push(std::mem::take(&mut field)),
+			'\r' if !quoted => (),
+			'\n' if !quoted => {
+				row.push(std::mem::take(&mut field));
+				rows.push((row_line, std::mem::take(&mut row)));
+				line += 1;
+				row_line = line;
+			},
+			_ => {
+				if c == '\n' {
+					line += 1;
+				}
+				field.push(c);
+			}
+		}
+	}
+
+	if !field.is_empty() || !row.is_empty() {
+		row.push(field);
+		rows.push((row_line, row));
+	}
+
+	rows.retain(|(_, row)| !(row.len() == 1 && row[0].trim().is_empty()));
+	rows
+}
+
+struct CsvRow<'a> {
+	line: usize,
+	header: &'a [String],
+	fields: &'a [String]
+}
+
+impl CsvRow<'_> {
+	fn get(&self, column: &str) -> CabrilloResult<&str> {
+		let index = self.header.iter()
+			.position(|name| name.trim().eq_ignore_ascii_case(column))
+			.ok_or_else(|| self.error(column, "Missing column".into()))?;
+
+		Ok(self.fields.get(index).map(|field| field.trim()).unwrap_or(""))
+	}
+
+	fn error(&self, column: &str, message: String) -> CabrilloError {
+		CabrilloError::new(column, self.line, CabrilloErrorKind::ParseError(message))
+	}
+
+	fn qso(&self, columns: &CsvColumns) -> CabrilloResult<Qso> {
+		let freq = self.get(&columns.frequency)?;
+		let frequency = if freq.is_empty() {
+			Frequency::Light
+		} else {
+			let khz = freq.parse::<f64>()
+				.map_err(|_| self.error(&columns.frequency, format!("Invalid frequency '{}'", freq)))?;
+			Frequency::from_mhz(khz / 1000.0)
+		};
+
+		let mode = self.get(&columns.mode)?;
+		let mode = Mode::from_str(&mode.to_ascii_uppercase())
+			.map_err(|_| self.error(&columns.mode, format!("Invalid mode '{}'", mode)))?;
+
+		let datetime = columns.datetime.iter()
+			.map(|column| self.get(column))
+			.collect::<CabrilloResult<Vec<_>>>()?
+			.join(" ");
+		let datetime = datetime::parse(&datetime, &columns.datetime_format)
+			.ok_or_else(|| self.error(&columns.datetime[0], format!("Invalid date or time '{}'", datetime)))?;
+
+		let transmitter_id = match columns.transmitter_id {
+			Some(ref column) => match self.get(column)? {
+				"" => None,
+				other => Some(other.parse::<u8>().map_err(|_| self.error(column,
+					format!("Invalid transmitter ID '{}'", other)))?)
+			},
+			None => None
+		};
+
+		let qso = Qso::new(
+			frequency,
+			mode,
+			datetime,
+			self.get(&columns.call_sent)?,
+			self.get(&columns.exchange_sent)?,
+			self.get(&columns.call_received)?,
+			self.get(&columns.exchange_received)?
+		);
+
+		Ok(match transmitter_id {
+			Some(transmitter_id) => qso.with_transmitter_id(transmitter_id),
+			None => qso
+		})
+	}
+}
+
+impl Qso {
+	/// Read QSOs from CSV with a header row, using `columns` to find each field.
+	/// Columns which aren't mapped are ignored.
+	pub fn from_csv<R: Read>(reader: &mut R, columns: &CsvColumns) -> CabrilloResult<Vec<Qso>> {
+		let mut input = String::new();
+		reader.read_to_string(&mut input)
+			.map_err(|err| {
+				CabrilloError::new("", 0,
+					CabrilloErrorKind::IoError(err.to_string()))
+			})?;
+
+		let rows = csv_rows(&input);
+		let Some(((_, header), rows)) = rows.split_first() else {
+			return Ok(Vec::new());
+		};
+
+		rows.iter()
+			.map(|(line, fields)| CsvRow { line: *line, header, fields }.qso(columns))
+			.collect()
+	}
+}
+
+impl CabrilloLog {
+	/// Write the QSO entries of this log as CSV, one row per QSO after a header row.
+	///
+	/// Columns are `frequency_khz` (empty for light, and the frequency named by band
+	/// designators such as `144`), `mode` (CW, SSB, FM, RTTY, DIGI),
+	/// `datetime` (UTC, RFC 3339), `call_sent`, `exchange_sent`, `call_received`,
+	/// `exchange_received`, and `transmitter_id` (empty if there is none). Ignored (X-QSO) entries
+	/// are not written.
+	pub fn entries_to_csv<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+		let header: Vec<String> = CSV_COLUMNS.iter().map(|column| column.to_string()).collect();
+		write_row(writer, &header)?;
+
+		for qso in &self.entries {
+			let frequency = qso.frequency.as_hz()
+				.map(|hz| Frequency::from_hz(hz).token())
+				.unwrap_or_default();
+
+			write_row(writer, &[
+				frequency,
+				qso.mode.to_string(),
+				datetime::format(&qso.datetime, CSV_DATETIME_FORMAT),
+				qso.call_sent.to_string(),
+				qso.exch_sent.clone(),
+				qso.call_recvd.to_string(),
+				qso.exch_recvd.clone(),
+				qso.transmitter_id.map(|id| id.to_string()).unwrap_or_default()
+			])?;
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn export_csv() {
+		let log = CabrilloLog::from_path("test_data/cqww.txt").unwrap();
+		let mut output = Vec::new();
+		log.entries_to_csv(&mut output).unwrap();
+		let output = String::from_utf8(output).unwrap();
+		let mut rows = output.lines();
+
+		assert_eq!(rows.next(), Some(concat!(
+			"frequency_khz,mode,datetime,call_sent,exchange_sent,",
+			"call_received,exchange_received,transmitter_id"
+		)));
+		assert_eq!(rows.next(), Some("3799,SSB,2000-10-26T07:11:00Z,AA1ZZZ,59 05,K9QZO,59 04,0"));
+		assert_eq!(rows.count(), log.entries().len() - 1);
+	}
+
+	#[test]
+	fn import_csv() {
+		let log = CabrilloLog::from_path("test_data/cqww.txt").unwrap();
+		let mut output = Vec::new();
+		log.entries_to_csv(&mut output).unwrap();
+
+		let entries = Qso::from_csv(&mut output.as_slice(), &CsvColumns::default()).unwrap();
+		assert_eq!(entries.len(), log.entries().len());
+
+		for (a, b) in entries.iter().zip(log.entries()) {
+			assert_eq!(a.frequency(), b.frequency());
+			assert_eq!(a.mode(), b.mode());
+			assert_eq!(a.datetime(), b.datetime());
+			assert_eq!(a.call_sent(), b.call_sent());
+			assert_eq!(a.exchange_sent(), b.exchange_sent());
+			assert_eq!(a.call_received(), b.call_received());
+			assert_eq!(a.exchange_received(), b.exchange_received());
+		}
+	}
+
+	#[test]
+	fn import_csv_mapping() {
+		let input = concat!(
+			"Date,Time,Freq,Mode,Call,Sent,Rcvd,MyCall\r\n",
+			"2023-01-14,18:05,14025,cw,W1AW,\"599 CT\",599 MA,K1AB\r\n"
+		);
+		let columns = CsvColumns::new()
+			.date_time("Date", "Time", "%Y-%m-%d %H:%M")
+			.frequency("Freq")
+			.mode("Mode")
+			.call_sent("MyCall")
+			.exchange_sent("Sent")
+			.call_received("Call")
+			.exchange_received("Rcvd")
+			.transmitter_id(None::<String>);
+
+		let entries = Qso::from_csv(&mut input.as_bytes(), &columns).unwrap();
+		assert_eq!(entries.len(), 1);
+		assert_eq!(entries[0].frequency(), &Frequency::from_khz(14025));
+		assert_eq!(entries[0].mode(), &Mode::Cw);
+		assert_eq!(entries[0].call_sent(), "K1AB");
+		assert_eq!(entries[0].exchange_sent(), "599 CT");
+		assert_eq!(entries[0].call_received(), "W1AW");
+
+		let err = Qso::from_csv(&mut input.as_bytes(), &CsvColumns::default()).unwrap_err();
+		assert_eq!(err.tag(), "frequency_khz");
+		assert_eq!(err.line(), 1);
+	}
+
+	#[test]
+	fn quote_fields() {
+		assert_eq!(super::csv_field("59 05"), "59 05");
+		assert_eq!(super::csv_field("A,B"), "\"A,B\"");
+		assert_eq!(super::csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+	}
+}
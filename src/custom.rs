@@ -0,0 +1,132 @@
+use std::any::Any;
+use std::sync::Arc;
+use crate::*;
+
+type TagHandler = Arc<dyn Fn(&str) -> Result<CustomValue, String> + Send + Sync>;
+
+/// A value produced by a custom tag handler. Use `CabrilloLog::custom_tag()` to
+/// get it back as its original type.
+#[derive(Clone)]
+pub struct CustomValue(Arc<dyn Any + Send + Sync>);
+
+impl CustomValue {
+	pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+		self.0.downcast_ref()
+	}
+}
+
+impl fmt::Debug for CustomValue {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("CustomValue(..)")
+	}
+}
+
+/// Parsers for tags which this crate does not know, registered with
+/// `ParseOptions::register_tag()`.
+#[derive(Clone, Default)]
+pub struct TagHandlers(HashMap<String, TagHandler>);
+
+impl TagHandlers {
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	/// Run the handler for `tag`, if there is one and the tag is not one this
+	/// crate parses itself.
+	pub(crate) fn parse(&self, tag: &str, value: &str) -> Option<Result<CustomValue, String>> {
+		if TAGS.contains_key(tag) {
+			return None;
+		}
+
+		self.0.get(tag).map(|handler| handler(value))
+	}
+}
+
+impl fmt::Debug for TagHandlers {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_set().entries(self.0.keys()).finish()
+	}
+}
+
+impl PartialEq for TagHandlers {
+	fn eq(&self, other: &Self) -> bool {
+		self.0.len() == other.0.len() && self.0.iter().all(|(tag, handler)| {
+			other.0.get(tag).is_some_and(|other| Arc::ptr_eq(handler, other))
+		})
+	}
+}
+
+impl ParseOptions {
+	/// Parse the value of `tag` with `handler`, storing the result so that it
+	/// can be read with `CabrilloLog::custom_tag()`. The raw value is still kept
+	/// in `other_tags`, so the log is written back out unchanged. An error from
+	/// the handler fails parsing like any other malformed value.
+	///
+	/// Handlers are only called for tags which this crate does not parse itself.
+	pub fn register_tag<T, E, F>(&mut self, tag: &str, handler: F)
+	where
+		T: Any + Send + Sync,
+		E: Display,
+		F: Fn(&str) -> Result<T, E> + Send + Sync + 'static
+	{
+		self.tag_handlers.0.insert(tag.to_string(), Arc::new(move |value: &str| {
+			handler(value)
+				.map(|value| CustomValue(Arc::new(value)))
+				.map_err(|err| err.to_string())
+		}));
+	}
+}
+
+impl CabrilloLog {
+	/// The value of a tag parsed by a handler registered with
+	/// `ParseOptions::register_tag()`, if it was present and has type `T`.
+	pub fn custom_tag<T: Any>(&self, tag: &str) -> Option<&T> {
+		self.custom_tags.get(tag).and_then(|value| value.downcast_ref())
+	}
+
+	/// All values parsed by custom tag handlers, by tag.
+	pub fn custom_tags(&self) -> &HashMap<String, CustomValue> {
+		&self.custom_tags
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[derive(Debug, PartialEq)]
+	struct Sponsor {
+		id: u32,
+		name: String
+	}
+
+	fn options() -> ParseOptions {
+		let mut options = ParseOptions::strict();
+		options.register_tag("X-SPONSOR", |value: &str| {
+			let (id, name) = value.split_once(' ').ok_or("missing sponsor name")?;
+			let id = id.parse::<u32>().map_err(|_| "invalid sponsor id")?;
+
+			Ok::<_, &str>(Sponsor { id, name: name.to_string() })
+		});
+		options
+	}
+
+	#[test]
+	fn custom_tag() {
+		let buf = "START-OF-LOG: 3.0\nX-SPONSOR: 42 Acme Antennas\nX-OTHER: value\nEND-OF-LOG:";
+		let log = CabrilloLog::from_buffer_with(buf.as_bytes(), &options()).unwrap();
+
+		assert_eq!(log.custom_tag::<Sponsor>("X-SPONSOR"), Some(&Sponsor { id: 42, name: "Acme Antennas".into() }));
+		assert_eq!(log.custom_tag::<String>("X-SPONSOR"), None);
+		assert_eq!(log.custom_tag::<Sponsor>("X-OTHER"), None);
+		assert_eq!(log.other_tags().get("X-SPONSOR"), Some(&"42 Acme Antennas".to_string()));
+
+		let options = options();
+		assert_eq!(options, options.clone());
+		assert_ne!(options, ParseOptions::strict());
+
+		let error = CabrilloLog::from_buffer_with(b"X-SPONSOR: abc Acme", &options).unwrap_err();
+		assert_eq!(error.tag(), "X-SPONSOR");
+		assert_eq!(error.kind(), &CabrilloErrorKind::ParseError("invalid sponsor id".into()));
+	}
+}
@@ -0,0 +1,206 @@
+#[cfg(not(any(feature = "chrono", feature = "time")))]
+compile_error!("either the `chrono` or the `time` feature must be enabled for QSO and offtime dates");
+
+/// Dates and times backed by chrono, the default, which is also used when
+/// the `time` feature is enabled alongside it.
+#[cfg(feature = "chrono")]
+mod backend {
+	use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
+
+	/// A date and time in UTC, as given in QSO and OFFTIME lines.
+	pub type DateTime = NaiveDateTime;
+	pub type Date = NaiveDate;
+	pub type Duration = chrono::Duration;
+	/// A `DateTime` with its UTC offset attached.
+	pub type UtcDateTime = chrono::DateTime<Utc>;
+
+	pub fn parse(input: &str, format: &str) -> Option<DateTime> {
+		NaiveDateTime::parse_from_str(input, format).ok()
+	}
+
+	pub fn format(datetime: &DateTime, format: &str) -> String {
+		datetime.format(format).to_string()
+	}
+
+	pub fn date(year: i32, month: u32, day: u32) -> Option<Date> {
+		NaiveDate::from_ymd_opt(year, month, day)
+	}
+
+	pub fn at_hour(date: Date, hour: u32) -> Option<DateTime> {
+		date.and_hms_opt(hour, 0, 0)
+	}
+
+	pub fn year(datetime: &DateTime) -> i32 {
+		datetime.year()
+	}
+
+	pub fn month(date: &Date) -> u32 {
+		date.month()
+	}
+
+	pub fn days_from_monday(date: &Date) -> u32 {
+		date.weekday().num_days_from_monday()
+	}
+
+	pub fn start_of_hour(datetime: &DateTime) -> DateTime {
+		NaiveTime::from_hms_opt(datetime.hour(), 0, 0).map_or(*datetime, |time| datetime.date().and_time(time))
+	}
+
+	pub fn whole_hours(duration: &Duration) -> i64 {
+		duration.num_hours()
+	}
+
+	pub fn whole_minutes(duration: &Duration) -> i64 {
+		duration.num_minutes()
+	}
+
+	pub fn to_utc(datetime: &DateTime) -> UtcDateTime {
+		datetime.and_utc()
+	}
+
+	#[cfg(any(feature = "arrow", test))]
+	pub fn timestamp(datetime: &UtcDateTime) -> i64 {
+		datetime.timestamp()
+	}
+}
+
+/// Dates and times backed by the time crate, with strftime-style formats
+/// translated to its format descriptions. It is only used when the `chrono`
+/// feature is disabled: with both features enabled, chrono is silently
+/// preferred.
+#[cfg(all(feature = "time", not(feature = "chrono")))]
+mod backend {
+	use time::{format_description, Month, OffsetDateTime, PrimitiveDateTime, Time};
+
+	/// A date and time in UTC, as given in QSO and OFFTIME lines.
+	pub type DateTime = PrimitiveDateTime;
+	pub type Date = time::Date;
+	pub type Duration = time::Duration;
+	/// A `DateTime` with its UTC offset attached.
+	pub type UtcDateTime = OffsetDateTime;
+
+	/// The format description for a strftime-style `format`. Only the fields the
+	/// crate uses are translated, and anything else is kept as literal text.
+	fn description(format: &str) -> String {
+		let mut description = String::with_capacity(format.len() * 2);
+		let mut chars = format.chars();
+
+		while let Some(c) = chars.next() {
+			match c {
+				'%' => match chars.next() {
+					Some('Y') => description.push_str("[year]"),
+					Some('y') => description.push_str("[year repr:last_two]"),
+					Some('m') => description.push_str("[month]"),
+					Some('d') => description.push_str("[day]"),
+					Some('H') => description.push_str("[hour]"),
+					Some('M') => description.push_str("[minute]"),
+					Some('S') => description.push_str("[second]"),
+					Some('%') => description.push('%'),
+					Some(other) => {
+						description.push('%');
+						description.push(other);
+					},
+					None => description.push('%')
+				},
+				'[' => description.push_str("[["),
+				c => description.push(c)
+			}
+		}
+
+		description
+	}
+
+	/// Parse `input` with a strftime-style `format`. A two digit year (`%y`)
+	/// does not give the century, so the time crate cannot read one.
+	pub fn parse(input: &str, format: &str) -> Option<DateTime> {
+		let description = description(format);
+		let items = format_description::parse_borrowed::<1>(&description).ok()?;
+		PrimitiveDateTime::parse(input, &items).ok()
+	}
+
+	pub fn format(datetime: &DateTime, format: &str) -> String {
+		let description = description(format);
+		format_description::parse_borrowed::<1>(&description).ok()
+			.and_then(|items| datetime.format(&items).ok())
+			.unwrap_or_default()
+	}
+
+	pub fn date(year: i32, month: u32, day: u32) -> Option<Date> {
+		let month = Month::try_from(u8::try_from(month).ok()?).ok()?;
+		Date::from_calendar_date(year, month, u8::try_from(day).ok()?).ok()
+	}
+
+	pub fn at_hour(date: Date, hour: u32) -> Option<DateTime> {
+		date.with_hms(u8::try_from(hour).ok()?, 0, 0).ok()
+	}
+
+	pub fn year(datetime: &DateTime) -> i32 {
+		datetime.year()
+	}
+
+	pub fn month(date: &Date) -> u32 {
+		u8::from(date.month()) as u32
+	}
+
+	pub fn days_from_monday(date: &Date) -> u32 {
+		date.weekday().number_days_from_monday() as u32
+	}
+
+	pub fn start_of_hour(datetime: &DateTime) -> DateTime {
+		Time::from_hms(datetime.hour(), 0, 0).map_or(*datetime, |time| datetime.replace_time(time))
+	}
+
+	pub fn whole_hours(duration: &Duration) -> i64 {
+		duration.whole_hours()
+	}
+
+	pub fn whole_minutes(duration: &Duration) -> i64 {
+		duration.whole_minutes()
+	}
+
+	pub fn to_utc(datetime: &DateTime) -> UtcDateTime {
+		datetime.assume_utc()
+	}
+
+	#[cfg(any(feature = "arrow", test))]
+	pub fn timestamp(datetime: &UtcDateTime) -> i64 {
+		datetime.unix_timestamp()
+	}
+}
+
+pub use self::backend::{DateTime, Duration, UtcDateTime};
+pub(crate) use self::backend::{
+	parse,
+	format,
+	date,
+	at_hour,
+	year,
+	month,
+	days_from_monday,
+	start_of_hour,
+	whole_hours,
+	whole_minutes,
+	to_utc
+};
+#[cfg(any(feature = "arrow", test))]
+pub(crate) use self::backend::timestamp;
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn datetime_formats() {
+		let time = datetime::parse("2023-01-14 1805", "%Y-%m-%d %H%M").unwrap();
+		assert_eq!(datetime::format(&time, "%y%m%d %H:%M:%S [UTC]"), "230114 18:05:00 [UTC]");
+		assert_eq!(datetime::format(&datetime::start_of_hour(&time), "%H%M"), "1800");
+		assert_eq!(datetime::year(&time), 2023);
+		assert!(datetime::parse("2023-01-14 18:05", "%Y-%m-%d %H%M").is_none());
+
+		let saturday = datetime::date(2023, 1, 14).unwrap();
+		assert_eq!(datetime::days_from_monday(&saturday), 5);
+		assert_eq!(datetime::at_hour(saturday, 12), datetime::parse("2023-01-14 1200", "%Y-%m-%d %H%M"));
+		assert!(datetime::date(2023, 2, 30).is_none());
+		assert_eq!(datetime::whole_minutes(&(time - datetime::at_hour(saturday, 12).unwrap())), 365);
+	}
+}
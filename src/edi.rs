@@ -0,0 +1,345 @@
+use std::io::{self, Write};
+use std::str;
+use crate::*;
+use crate::adif::split_rst;
+
+/// Bands which can be written to EDI, with their PBand names.
+const EDI_BANDS: [(Band, &str); 17] = [
+	(Band::Band6M  , "50 MHz"),
+	(Band::Band4M  , "70 MHz"),
+	(Band::Band2M  , "144 MHz"),
+	(Band::Band222 , "222 MHz"),
+	(Band::Band432 , "432 MHz"),
+	(Band::Band902 , "902 MHz"),
+	(Band::Band1_2G, "1,3 GHz"),
+	(Band::Band2_3G, "2,3 GHz"),
+	(Band::Band3_4G, "3,4 GHz"),
+	(Band::Band5_7G, "5,7 GHz"),
+	(Band::Band10G , "10 GHz"),
+	(Band::Band24G , "24 GHz"),
+	(Band::Band47G , "47 GHz"),
+	(Band::Band75G , "76 GHz"),
+	(Band::Band123G, "122 GHz"),
+	(Band::Band134G, "134 GHz"),
+	(Band::Band241G, "248 GHz")
+];
+
+fn edi_mode(mode: &Mode) -> u8 {
+	match mode {
+		Mode::Phone => 1,
+		Mode::Cw    => 2,
+		Mode::Fm    => 6,
+		Mode::Rtty  => 7,
+		_ => 0
+	}
+}
+
+/// Cabrillo mode for an EDI mode code. Mixed codes use the mode sent, and
+/// unspecified modes are treated as phone.
+fn edi_mode_from_code(code: &str) -> Mode {
+	match code {
+		"2" | "4" => Mode::Cw,
+		"6"       => Mode::Fm,
+		"7"       => Mode::Rtty,
+		"8" | "9" => Mode::Digital,
+		_         => Mode::Phone
+	}
+}
+
+/// Write a Maidenhead locator the way Cabrillo expects it, with a lowercase
+/// subsquare, or None if the token isn't a locator.
+fn normalize_locator(token: &str) -> Option<String> {
+	GridSquare::normalize(token).map(|grid| grid.to_string())
+}
+
+/// An exchange split into the columns of an EDI QSO record.
+#[derive(Default)]
+struct EdiExchange<'a> {
+	rst: &'a str,
+	number: &'a str,
+	exchange: Vec<&'a str>,
+	locator: String
+}
+
+impl<'a> EdiExchange<'a> {
+	fn split(exchange: &'a str) -> Self {
+		let (rst, rest) = split_rst(exchange);
+		let mut split = EdiExchange {
+			rst: rst.unwrap_or(""),
+			..Default::default()
+		};
+
+		for token in rest.split_whitespace() {
+			if split.number.is_empty() && token.chars().all(|c| c.is_ascii_digit()) {
+				split.number = token;
+			} else if let (true, Some(locator)) = (split.locator.is_empty(), normalize_locator(token)) {
+				split.locator = locator.to_ascii_uppercase();
+			} else {
+				split.exchange.push(token);
+			}
+		}
+
+		split
+	}
+}
+
+fn join<'a, I: IntoIterator<Item = &'a str>>(tokens: I) -> String {
+	tokens.into_iter()
+		.filter(|token| !token.is_empty())
+		.collect::<Vec<_>>()
+		.join(" ")
+}
+
+fn header_line<W: Write>(writer: &mut W, key: &str, value: Option<&str>) -> io::Result<()> {
+	writeln!(writer, "{}={}", key, value.unwrap_or(""))
+}
+
+fn edi_error(key: &str, line: usize, message: String) -> CabrilloError {
+	CabrilloError::new(key, line, CabrilloErrorKind::ParseError(message))
+}
+
+/// The century of a YYMMDD date, with years before 70 taken to be after 2000.
+fn edi_century(date: &str) -> &'static str {
+	match date.get(..2).and_then(|year| year.parse::<u32>().ok()) {
+		Some(year) if year >= 70 => "19",
+		_ => "20"
+	}
+}
+
+fn edi_qso(log: &CabrilloLog, frequency: Frequency, line_no: usize, line: &str) -> CabrilloResult<Qso> {
+	let fields: Vec<&str> = line.split(';').map(str::trim).collect();
+
+	if fields.len() < 10 {
+		return Err(edi_error("QSORecords", line_no, format!("Expected at least 10 fields, found {}", fields.len())));
+	}
+
+	let datetime = datetime::parse(&format!("{}{}{}", edi_century(fields[0]), fields[0], fields[1]), "%Y%m%d%H%M")
+		.ok_or_else(|| edi_error("QSORecords", line_no, format!("Invalid date or time '{} {}'", fields[0], fields[1])))?;
+
+	let own_locator = log.grid_locator.as_ref().map(GridSquare::as_str).unwrap_or("");
+
+	Ok(Qso::new(
+		frequency,
+		edi_mode_from_code(fields[3]),
+		datetime,
+		log.callsign.clone().unwrap_or_default(),
+		join([fields[4], fields[5], own_locator]),
+		fields[2].to_string(),
+		join([fields[6], fields[7], fields[8], fields[9]])
+	))
+}
+
+impl CabrilloLog {
+	/// Write the QSOs made on `band` as an EDI (REG1TEST) log, the format used by
+	/// European VHF contests. EDI logs hold a single band, so one file should be
+	/// written for each band in the log.
+	///
+	/// Exchanges are split into signal report, serial number, locator, and any
+	/// remaining exchange. Only VHF and higher bands can be written.
+	pub fn to_edi<W: Write>(&self, writer: &mut W, band: Band) -> io::Result<()> {
+		let (band, band_name) = EDI_BANDS.iter()
+			.find(|(edi_band, _)| *edi_band == band)
+			.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput,
+				format!("Band '{}' cannot be written to EDI", band)))?;
+
+		let entries: Vec<&Qso> = self.entries.iter()
+			.filter(|qso| qso.band().as_ref() == Some(band))
+			.collect();
+
+		let first = entries.iter().map(|qso| qso.datetime).min();
+		let last = entries.iter().map(|qso| qso.datetime).max();
+		let dates = match (first, last) {
+			(Some(first), Some(last)) => format!("{};{}", datetime::format(&first, "%Y%m%d"), datetime::format(&last, "%Y%m%d")),
+			_ => String::new()
+		};
+
+		let mut address = self.address.iter().flat_map(|address| address.lines());
+		let address1 = address.next();
+		let address2 = address.collect::<Vec<_>>().join(", ");
+		let operators = self.joined_operators(";");
+		let score = self.claimed_score.map(|score| score.to_string());
+		let section = self.category_operator.as_ref().map(|op| op.to_string());
+
+		writeln!(writer, "[REG1TEST;1]")?;
+		header_line(writer, "TName", self.contest.as_deref())?;
+		header_line(writer, "TDate", Some(&dates))?;
+		header_line(writer, "PCall", self.callsign.as_deref())?;
+		header_line(writer, "PWWLo", self.grid_locator.as_ref().map(|grid| grid.as_str().to_ascii_uppercase()).as_deref())?;
+		header_line(writer, "PSect", section.as_deref())?;
+		header_line(writer, "PBand", Some(band_name))?;
+		header_line(writer, "PClub", self.club.as_deref())?;
+		header_line(writer, "RName", self.name.as_deref())?;
+		header_line(writer, "RCall", self.callsign.as_deref())?;
+		header_line(writer, "RAdr1", address1)?;
+		header_line(writer, "RAdr2", Some(&address2))?;
+		header_line(writer, "RHBBS", self.email.as_deref())?;
+		header_line(writer, "MOpe1", Some(&operators))?;
+		header_line(writer, "CQSOs", Some(&format!("{};1", entries.len())))?;
+		header_line(writer, "CToSc", score.as_deref())?;
+
+		writeln!(writer, "[Remarks]")?;
+		for line in &self.soapbox {
+			writeln!(writer, "{}", line)?;
+		}
+
+		writeln!(writer, "[QSORecords;{}]", entries.len())?;
+		for qso in entries {
+			let sent = EdiExchange::split(&qso.exch_sent);
+			let recvd = EdiExchange::split(&qso.exch_recvd);
+
+			writeln!(writer, "{};{};{};{};{};{};{};{};{};{};;;;;",
+				datetime::format(&qso.datetime, "%y%m%d"),
+				datetime::format(&qso.datetime, "%H%M"),
+				qso.call_recvd,
+				edi_mode(&qso.mode),
+				sent.rst,
+				sent.number,
+				recvd.rst,
+				recvd.number,
+				recvd.exchange.join(" "),
+				recvd.locator
+			)?;
+		}
+
+		Ok(())
+	}
+
+	/// Read an EDI (REG1TEST) log. QSOs are given the frequency of the log's band,
+	/// and their sent exchange is rebuilt from the signal report, serial number,
+	/// and the station's own locator.
+	pub fn from_edi(buf: &[u8]) -> CabrilloResult<Self> {
+		let input = str::from_utf8(buf)
+			.map_err(|err| {
+				CabrilloError::new("", 0,
+					CabrilloErrorKind::IoError(format!("{}", err)))
+			})?;
+
+		let mut log = Self::new();
+		let mut band = None;
+		let mut section = None;
+
+		for (line_no, line) in input.lines().enumerate() {
+			let line = line.trim();
+
+			if line.is_empty() {
+				continue;
+			}
+
+			if let Some(name) = line.strip_prefix('[') {
+				let name = name.split([';', ']']).next().unwrap_or("").to_ascii_uppercase();
+				section = Some(name);
+				continue;
+			}
+
+			match section.as_deref() {
+				Some("REG1TEST") => {
+					let (key, value) = line.split_once('=').unwrap_or((line, ""));
+					let value = value.trim();
+
+					if value.is_empty() {
+						continue;
+					}
+
+					match key.trim() {
+						"TName" => log.contest = Some(value.to_string()),
+						"PCall" => log.callsign = Some(value.to_string()),
+						"PWWLo" => log.grid_locator = GridSquare::normalize(value),
+						"PClub" => log.club = Some(value.to_string()),
+						"RName" => log.name = Some(value.to_string()),
+						"RHBBS" => log.email = Some(value.to_string()),
+						"RAdr1" | "RAdr2" | "RCity" | "RPoCo" | "RCoun" => log.push_address_line(value),
+						"MOpe1" | "MOpe2" => {
+							log.operators.extend(value.split([';', ',', ' '])
+								.filter(|op| !op.is_empty())
+								.map(Callsign::from));
+						},
+						"CToSc" => log.claimed_score = value.parse().ok(),
+						"PBand" => {
+							let name = value.replace('.', ",").replace(' ', "");
+							band = EDI_BANDS.iter()
+								.find(|(_, edi_name)| edi_name.replace(' ', "").eq_ignore_ascii_case(&name));
+							log.category_band = band.map(|(band, _)| *band);
+						},
+						_ => ()
+					}
+				},
+				Some("REMARKS") => {
+					log.push_soapbox_line(line);
+				},
+				Some("QSORECORDS") => {
+					let (band, _) = band
+						.ok_or_else(|| edi_error("PBand", line_no, "Missing or unknown band".into()))?;
+					let qso = edi_qso(&log, Frequency::Band(*band), line_no, line)?;
+					log.entries.push(qso);
+				},
+				Some(_) => (),
+				None => {
+					return Err(edi_error("REG1TEST", line_no, "Missing REG1TEST header".into()));
+				}
+			}
+		}
+
+		if section.is_none() {
+			return Err(edi_error("REG1TEST", 0, "Missing REG1TEST header".into()));
+		}
+
+		Ok(log)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn export_edi() {
+		let log = CabrilloLog::from_path("test_data/cqww_vhf.txt").unwrap();
+		let mut output = Vec::new();
+		log.to_edi(&mut output, Band::Band6M).unwrap();
+		let output = String::from_utf8(output).unwrap();
+
+		assert!(output.starts_with("[REG1TEST;1]\nTName=CQ-VHF\nTDate=20010717;20010717\n"));
+		assert!(output.contains("PBand=50 MHz\n"));
+		assert!(output.contains("[Remarks]\nPut your comments here.\n"));
+		assert!(output.contains("[QSORecords;2]\n010717;1817;W2AJM;2;;;;;;FN21;;;;;\n"));
+
+		let mut output = Vec::new();
+		assert!(log.to_edi(&mut output, Band::Band20M).is_err());
+	}
+
+	#[test]
+	fn import_edi() {
+		let input = concat!(
+			"[REG1TEST;1]\r\n",
+			"TName=IARU Region 1 VHF\r\n",
+			"PCall=OK1ABC\r\n",
+			"PWWLo=JN79XX\r\n",
+			"PBand=144 MHz\r\n",
+			"RAdr1=Main St 1\r\n",
+			"MOpe1=OK1ABC;OK1DEF\r\n",
+			"[Remarks]\r\n",
+			"Good conditions\r\n",
+			"[QSORecords;1]\r\n",
+			"230902;1405;DL1XYZ;2;599;001;599;017;;JO50AB;320;;;;\r\n"
+		);
+
+		let log = CabrilloLog::from_edi(input.as_bytes()).unwrap();
+		assert_eq!(log.callsign(), &Some("OK1ABC".to_string()));
+		assert_eq!(log.grid_locator().as_ref().map(GridSquare::as_str), Some("JN79xx"));
+		assert_eq!(log.category_band(), &Some(Band::Band2M));
+		assert_eq!(log.operators(), &vec![Callsign::new("OK1ABC"), Callsign::new("OK1DEF")]);
+		assert_eq!(log.soapbox(), &vec!["Good conditions".to_string()]);
+
+		let qso = &log.entries()[0];
+		assert_eq!(qso.frequency(), &Frequency::Band(Band::Band2M));
+		assert_eq!(qso.mode(), &Mode::Cw);
+		assert_eq!(qso.call_received(), "DL1XYZ");
+		assert_eq!(qso.exchange_sent(), "599 001 JN79xx");
+		assert_eq!(qso.exchange_received(), "599 017 JO50AB");
+
+		let mut output = Vec::new();
+		log.to_edi(&mut output, Band::Band2M).unwrap();
+		assert!(String::from_utf8(output).unwrap()
+			.contains("230902;1405;DL1XYZ;2;599;001;599;017;;JO50AB;;;;;\n"));
+	}
+}
@@ -0,0 +1,339 @@
+use crate::*;
+
+/// The kinds of field found in contest exchanges, used to describe the layout
+/// of an exchange to `Exchange::parse()`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ExchangeFieldKind {
+	/// A signal report, such as `599` or `5NN`.
+	Rst,
+	/// A serial number, such as `001`.
+	Serial,
+	/// A CQ or ITU zone.
+	Zone,
+	/// An ARRL or RAC section.
+	Section,
+	/// A US state or Canadian province, or DX.
+	State,
+	/// A Maidenhead grid locator, such as `FN31` or `FN31pr`.
+	Grid,
+	Name,
+	/// Transmitter power, such as `100`, `5W`, or `KW`.
+	Power,
+	/// The precedence of ARRL Sweepstakes, such as `A` or `Q`.
+	Precedence,
+	/// The two digit check of ARRL Sweepstakes.
+	Check,
+	/// Anything else, kept as text.
+	Other
+}
+
+/// Abbreviations of the US states and the District of Columbia.
+pub const US_STATES: [&str; 51] = [
+	"AL", "AK", "AZ", "AR", "CA", "CO", "CT", "DE", "DC", "FL", "GA", "HI", "ID",
+	"IL", "IN", "IA", "KS", "KY", "LA", "ME", "MD", "MA", "MI", "MN", "MS", "MO",
+	"MT", "NE", "NV", "NH", "NJ", "NM", "NY", "NC", "ND", "OH", "OK", "OR", "PA",
+	"RI", "SC", "SD", "TN", "TX", "UT", "VT", "VA", "WA", "WV", "WI", "WY"
+];
+
+/// Abbreviations of the Canadian provinces and territories.
+pub const CANADIAN_PROVINCES: [&str; 13] = [
+	"AB", "BC", "MB", "NB", "NL", "NS", "NT", "NU", "ON", "PE", "QC", "SK", "YT"
+];
+
+/// Whether `token` is a US state, Canadian province, or `DX`, which stations
+/// outside of North America send in place of one.
+fn is_state(token: &str) -> bool {
+	token == "DX" || US_STATES.contains(&token) || CANADIAN_PROVINCES.contains(&token)
+}
+
+/// One typed field of an exchange.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExchangeField {
+	Rst(SignalReport),
+	Serial(u32),
+	Zone(u8),
+	Section(Section),
+	State(String),
+	Grid(String),
+	Name(String),
+	Power(String),
+	Precedence(char),
+	Check(u8),
+	Other(String)
+}
+
+impl ExchangeField {
+	pub fn kind(&self) -> ExchangeFieldKind {
+		match self {
+			ExchangeField::Rst(_)        => ExchangeFieldKind::Rst,
+			ExchangeField::Serial(_)     => ExchangeFieldKind::Serial,
+			ExchangeField::Zone(_)       => ExchangeFieldKind::Zone,
+			ExchangeField::Section(_)    => ExchangeFieldKind::Section,
+			ExchangeField::State(_)      => ExchangeFieldKind::State,
+			ExchangeField::Grid(_)       => ExchangeFieldKind::Grid,
+			ExchangeField::Name(_)       => ExchangeFieldKind::Name,
+			ExchangeField::Power(_)      => ExchangeFieldKind::Power,
+			ExchangeField::Precedence(_) => ExchangeFieldKind::Precedence,
+			ExchangeField::Check(_)      => ExchangeFieldKind::Check,
+			ExchangeField::Other(_)      => ExchangeFieldKind::Other
+		}
+	}
+
+	/// Read `token` as a field of the given kind.
+	pub fn parse(kind: ExchangeFieldKind, token: &str) -> Result<Self, CabrilloErrorKind> {
+		let invalid = || CabrilloErrorKind::ParseError(format!("'{}' is not a valid {:?} exchange field", token, kind));
+		let digits = !token.is_empty() && token.chars().all(|c| c.is_ascii_digit());
+		let letters = !token.is_empty() && token.chars().all(|c| c.is_ascii_alphabetic());
+
+		match kind {
+			ExchangeFieldKind::Rst => token.parse().map(ExchangeField::Rst),
+			ExchangeFieldKind::Serial if digits => token.parse().map(ExchangeField::Serial).map_err(|_| invalid()),
+			ExchangeFieldKind::Zone if digits => match token.parse() {
+				Ok(zone @ 1..=90) => Ok(ExchangeField::Zone(zone)),
+				_ => Err(invalid())
+			},
+			ExchangeFieldKind::Section => token.parse().map(ExchangeField::Section)
+				.map_err(|_| CabrilloErrorKind::ParseError(format!("Unknown section '{}'", token))),
+			ExchangeFieldKind::State if is_state(token) => Ok(ExchangeField::State(token.to_string())),
+			ExchangeFieldKind::State => Err(CabrilloErrorKind::ParseError(format!("Unknown state or province '{}'", token))),
+			ExchangeFieldKind::Grid if is_grid(token) => Ok(ExchangeField::Grid(token.to_string())),
+			ExchangeFieldKind::Name if letters => Ok(ExchangeField::Name(token.to_string())),
+			ExchangeFieldKind::Power if is_power(token) => Ok(ExchangeField::Power(token.to_string())),
+			ExchangeFieldKind::Precedence => match token.chars().collect::<Vec<_>>()[..] {
+				[precedence] if precedence.is_ascii_alphabetic() => Ok(ExchangeField::Precedence(precedence)),
+				_ => Err(invalid())
+			},
+			ExchangeFieldKind::Check if digits && token.len() == 2 => token.parse().map(ExchangeField::Check).map_err(|_| invalid()),
+			ExchangeFieldKind::Other if !token.is_empty() => Ok(ExchangeField::Other(token.to_string())),
+			_ => Err(invalid())
+		}
+	}
+}
+
+/// Whether `token` is a 4, 6, 8, or 10 character Maidenhead locator.
+fn is_grid(token: &str) -> bool {
+	all_consuming(cabrillo_grid_locator)(token).is_ok()
+}
+
+/// Whether `token` is a power level, either a number of watts optionally
+/// followed by W, or an abbreviation such as KW or QRP.
+fn is_power(token: &str) -> bool {
+	let watts = token.strip_suffix('W').unwrap_or(token);
+
+	(!watts.is_empty() && watts.chars().all(|c| c.is_ascii_digit())) || matches!(token, "K" | "KW" | "QRP")
+}
+
+impl Display for ExchangeField {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ExchangeField::Rst(rst) => write!(f, "{}", rst),
+			ExchangeField::Serial(serial) => write!(f, "{}", serial),
+			ExchangeField::Zone(zone) => write!(f, "{:02}", zone),
+			ExchangeField::Section(section) => write!(f, "{}", section),
+			ExchangeField::Precedence(precedence) => write!(f, "{}", precedence),
+			ExchangeField::Check(check) => write!(f, "{:02}", check),
+			ExchangeField::State(text) | ExchangeField::Grid(text) | ExchangeField::Name(text) |
+			ExchangeField::Power(text) | ExchangeField::Other(text) => f.write_str(text)
+		}
+	}
+}
+
+/// An exchange read into typed fields, such as the `599 05` of CQ-WW, which
+/// is an RST and a zone.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Exchange(Vec<ExchangeField>);
+
+impl Exchange {
+	pub fn new(fields: Vec<ExchangeField>) -> Self {
+		Self(fields)
+	}
+
+	/// Read the whitespace separated fields of `exchange` using `layout`, which
+	/// must have one kind for each field.
+	pub fn parse(exchange: &str, layout: &[ExchangeFieldKind]) -> Result<Self, CabrilloErrorKind> {
+		let tokens: Vec<&str> = exchange.split_whitespace().collect();
+
+		if tokens.len() != layout.len() {
+			return Err(CabrilloErrorKind::ParseError(
+				format!("Exchange '{}' has {} fields, expected {}", exchange, tokens.len(), layout.len())
+			));
+		}
+
+		layout.iter()
+			.zip(tokens)
+			.map(|(kind, token)| ExchangeField::parse(*kind, token))
+			.collect::<Result<Vec<_>, _>>()
+			.map(Self)
+	}
+
+	pub fn fields(&self) -> &Vec<ExchangeField> {
+		&self.0
+	}
+
+	/// The first field of the given kind.
+	pub fn get(&self, kind: ExchangeFieldKind) -> Option<&ExchangeField> {
+		self.0.iter().find(|field| field.kind() == kind)
+	}
+
+	pub fn rst(&self) -> Option<SignalReport> {
+		self.0.iter().find_map(|field| match field {
+			ExchangeField::Rst(rst) => Some(*rst),
+			_ => None
+		})
+	}
+
+	pub fn serial(&self) -> Option<u32> {
+		self.0.iter().find_map(|field| match field {
+			ExchangeField::Serial(serial) => Some(*serial),
+			_ => None
+		})
+	}
+
+	pub fn zone(&self) -> Option<u8> {
+		self.0.iter().find_map(|field| match field {
+			ExchangeField::Zone(zone) => Some(*zone),
+			_ => None
+		})
+	}
+
+	pub fn section(&self) -> Option<Section> {
+		self.0.iter().find_map(|field| match field {
+			ExchangeField::Section(section) => Some(*section),
+			_ => None
+		})
+	}
+
+	pub fn state(&self) -> Option<&str> {
+		self.0.iter().find_map(|field| match field {
+			ExchangeField::State(state) => Some(state.as_str()),
+			_ => None
+		})
+	}
+
+	pub fn grid(&self) -> Option<&str> {
+		self.0.iter().find_map(|field| match field {
+			ExchangeField::Grid(grid) => Some(grid.as_str()),
+			_ => None
+		})
+	}
+
+	pub fn name(&self) -> Option<&str> {
+		self.0.iter().find_map(|field| match field {
+			ExchangeField::Name(name) => Some(name.as_str()),
+			_ => None
+		})
+	}
+
+	pub fn power(&self) -> Option<&str> {
+		self.0.iter().find_map(|field| match field {
+			ExchangeField::Power(power) => Some(power.as_str()),
+			_ => None
+		})
+	}
+}
+
+impl Display for Exchange {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (idx, field) in self.0.iter().enumerate() {
+			if idx > 0 {
+				f.write_str(" ")?;
+			}
+
+			write!(f, "{}", field)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl Qso {
+	/// The sent exchange read into typed fields using `layout`.
+	pub fn exchange_sent_as(&self, layout: &[ExchangeFieldKind]) -> Result<Exchange, CabrilloErrorKind> {
+		Exchange::parse(&self.exch_sent, layout)
+	}
+
+	/// The received exchange read into typed fields using `layout`.
+	pub fn exchange_received_as(&self, layout: &[ExchangeFieldKind]) -> Result<Exchange, CabrilloErrorKind> {
+		Exchange::parse(&self.exch_recvd, layout)
+	}
+
+	/// A message for each half of the exchange which cannot be read using the
+	/// `sent` and `received` layouts.
+	pub(crate) fn check_exchanges(&self, sent: &[ExchangeFieldKind], received: &[ExchangeFieldKind]) -> Vec<String> {
+		[self.exchange_sent_as(sent), self.exchange_received_as(received)]
+			.into_iter()
+			.filter_map(Result::err)
+			.map(CabrilloErrorKind::into_message)
+			.collect()
+	}
+
+	/// A message if the sent and received exchanges have different numbers of
+	/// fields, for contests without a schema.
+	pub(crate) fn check_exchange_lengths(&self) -> Option<String> {
+		let sent = self.exch_sent.split_whitespace().count();
+		let recvd = self.exch_recvd.split_whitespace().count();
+
+		(sent != recvd).then(|| format!("Sent exchange has {} fields but received exchange has {}", sent, recvd))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+	use ExchangeFieldKind::*;
+
+	#[test]
+	fn parse_exchange() {
+		let exchange = Exchange::parse("599 05", &[Rst, Zone]).unwrap();
+		assert_eq!(exchange.rst(), Some(SignalReport(5, 9, 9)));
+		assert_eq!(exchange.zone(), Some(5));
+		assert_eq!(exchange.serial(), None);
+		assert_eq!(exchange.to_string(), "599 05");
+
+		let exchange = Exchange::parse("1 B 74 STX", &[Serial, Precedence, Check, Section]).unwrap();
+		assert_eq!(exchange.serial(), Some(1));
+		assert_eq!(exchange.get(Precedence), Some(&ExchangeField::Precedence('B')));
+		assert_eq!(exchange.section(), Some(crate::Section::Stx));
+
+		let exchange = Exchange::parse("TREY CA", &[Name, State]).unwrap();
+		assert_eq!((exchange.name(), exchange.state()), (Some("TREY"), Some("CA")));
+		assert_eq!(Exchange::parse("fn21", &[Grid]).unwrap().grid(), Some("fn21"));
+		assert_eq!(Exchange::parse("100W", &[Power]).unwrap().power(), Some("100W"));
+
+		assert!(Exchange::parse("599 05", &[Rst]).is_err());
+		assert!(Exchange::parse("599 99", &[Rst, Zone]).is_err());
+		assert!(Exchange::parse("599 XX", &[Rst, Section]).is_err());
+		assert!(Exchange::parse("ZZ99", &[Grid]).is_err());
+
+		let log = CabrilloLog::from_path("test_data/cqww.txt").unwrap();
+		let qso = &log.entries()[0];
+		assert_eq!(qso.exchange_received_as(&[Rst, Zone]).unwrap().zone(), Some(4));
+		assert!(qso.exchange_sent_as(&[Rst, Serial, Other]).is_err());
+	}
+
+	#[test]
+	fn validate_multipliers() {
+		let (_, report) = CabrilloLog::from_buffer_validated(
+			&std::fs::read("test_data/ncj_naqp.txt").unwrap(), &ParseOptions::default()).unwrap();
+		assert!(report.warnings().is_empty());
+
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"CONTEST: NAQP-SSB\n",
+			"QSO: 14250 PH 2023-01-21 1800 N5KO TREY CA VE2XX JEAN QB\n",
+			"QSO: 14250 PH 2023-01-21 1801 N5KO TREY CA G4ABC JOHN DX\n",
+			"END-OF-LOG:"
+		);
+
+		let (log, report) = CabrilloLog::from_buffer_validated(buf.as_bytes(), &ParseOptions::default()).unwrap();
+		let warnings: Vec<(usize, &str)> = report.warnings().iter()
+			.map(|warning| (warning.line(), warning.message().as_str()))
+			.collect();
+		assert_eq!(warnings, vec![(2, "Unknown state or province 'QB'")]);
+		assert!(log.entries()[0].typed_exchange_received().is_none());
+		assert_eq!(log.entries()[1].typed_exchange_received().as_ref().unwrap().state(), Some("DX"));
+
+		let error = Exchange::parse("1 A 99 ENH", &[Serial, Precedence, Check, Section]).unwrap_err();
+		assert_eq!(error, CabrilloErrorKind::ParseError("Unknown section 'ENH'".into()));
+	}
+}
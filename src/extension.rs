@@ -0,0 +1,165 @@
+use std::any::type_name;
+use nom::combinator::all_consuming;
+use crate::*;
+
+/// A non-standard `X-` header tag, such as `X-QTH-COUNTY`, as it appeared in
+/// the log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Extension {
+	tag: String,
+	value: String,
+	line: usize
+}
+
+impl Extension {
+	pub fn tag(&self) -> &String {
+		&self.tag
+	}
+
+	pub fn value(&self) -> &String {
+		&self.value
+	}
+
+	/// Line of the log the tag was found on.
+	pub fn line(&self) -> usize {
+		self.line
+	}
+
+	fn error(&self, message: String) -> CabrilloError {
+		CabrilloError::new(&self.tag, self.line, CabrilloErrorKind::ParseError(message))
+	}
+
+	/// Convert the value to `T`, such as an integer.
+	pub fn parse<T>(&self) -> CabrilloResult<T>
+	where
+		T: FromStr,
+		T::Err: Display
+	{
+		self.value.parse().map_err(|err: T::Err| {
+			self.error(format!("'{}' is not a valid {}: {}", self.value, type_name::<T>(), err))
+		})
+	}
+
+	/// Read the value as a date and time in the format of QSO lines, such as
+	/// `2023-01-14 1805`.
+	pub fn datetime(&self) -> CabrilloResult<DateTime> {
+		all_consuming(cabrillo_datetime)(&self.value)
+			.map(|(_, datetime)| datetime)
+			.map_err(|_| self.error(format!("'{}' is not a valid date and time", self.value)))
+	}
+}
+
+/// The `X-` header tags of a log, in the order they appeared, returned by
+/// `CabrilloLog::extensions()`. A tag which appears more than once is kept
+/// each time.
+#[derive(Debug, Clone, Copy)]
+pub struct Extensions<'a>(&'a [Extension]);
+
+impl<'a> Extensions<'a> {
+	pub fn iter(&self) -> impl Iterator<Item = &'a Extension> {
+		self.0.iter()
+	}
+
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	/// The first occurrence of `tag`.
+	pub fn get(&self, tag: &str) -> Option<&'a Extension> {
+		self.0.iter().find(|extension| extension.tag == tag)
+	}
+
+	/// Every occurrence of `tag`, in order.
+	pub fn get_all<'b>(&self, tag: &'b str) -> impl Iterator<Item = &'a Extension> + 'b
+	where
+		'a: 'b
+	{
+		self.0.iter().filter(move |extension| extension.tag == tag)
+	}
+
+	/// The first value of `tag` converted to `T`, or None if the tag is missing.
+	pub fn parse<T>(&self, tag: &str) -> Option<CabrilloResult<T>>
+	where
+		T: FromStr,
+		T::Err: Display
+	{
+		self.get(tag).map(Extension::parse)
+	}
+}
+
+impl<'a> IntoIterator for Extensions<'a> {
+	type Item = &'a Extension;
+	type IntoIter = std::slice::Iter<'a, Extension>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.0.iter()
+	}
+}
+
+pub(crate) fn is_extension_tag(tag: &str) -> bool {
+	tag.starts_with("X-") && !TAGS.contains_key(tag)
+}
+
+impl CabrilloLog {
+	/// Header tags starting with `X-`, which contests and loggers use for
+	/// information not covered by the specification. Their values are also
+	/// kept in `other_tags()`.
+	pub fn extensions(&self) -> Extensions<'_> {
+		Extensions(&self.extensions)
+	}
+
+	pub(crate) fn record_extension(&mut self, line: usize, tag: &str, value: &str) {
+		if is_extension_tag(tag) {
+			self.extensions.push(Extension {
+				tag: tag.to_string(),
+				value: value.trim().to_string(),
+				line
+			});
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn extensions() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"X-QTH-COUNTY: MIDD\n",
+			"X-ANTENNAS: 3\n",
+			"X-QTH-COUNTY: ESSE\n",
+			"X-LAST-UPLOAD: 2023-01-15 0930\n",
+			"X-QSO: 14025 CW 2023-01-14 1805 W1AW 599 CT K1AB 599 MA\n",
+			"END-OF-LOG:"
+		);
+
+		let log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+		let extensions = log.extensions();
+		let tags: Vec<&str> = extensions.iter().map(|extension| extension.tag().as_str()).collect();
+		assert_eq!(tags, vec!["X-QTH-COUNTY", "X-ANTENNAS", "X-QTH-COUNTY", "X-LAST-UPLOAD"]);
+
+		let counties: Vec<&str> = extensions.get_all("X-QTH-COUNTY").map(|extension| extension.value().as_str()).collect();
+		assert_eq!(counties, vec!["MIDD", "ESSE"]);
+
+		assert_eq!(extensions.parse::<u32>("X-ANTENNAS").unwrap().unwrap(), 3);
+		assert!(extensions.parse::<u32>("X-MISSING").is_none());
+		assert_eq!(
+			datetime::format(&extensions.get("X-LAST-UPLOAD").unwrap().datetime().unwrap(), "%Y-%m-%d %H%M"),
+			"2023-01-15 0930"
+		);
+
+		let error = extensions.parse::<u32>("X-QTH-COUNTY").unwrap().unwrap_err();
+		assert_eq!((error.tag().as_str(), error.line()), ("X-QTH-COUNTY", 1));
+		assert!(extensions.get("X-ANTENNAS").unwrap().datetime().is_err());
+
+		let options = ParseOptions { duplicate_tags: DuplicateTags::Error, ..ParseOptions::strict() };
+		let log = CabrilloLog::from_buffer_with(buf.as_bytes(), &options).unwrap();
+		assert_eq!(log.extensions().len(), 4);
+	}
+}
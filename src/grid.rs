@@ -0,0 +1,168 @@
+use crate::*;
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// A Maidenhead grid square of 4, 6, 8, or 10 characters, such as `FN31` or
+/// `FN31pr`, written with an uppercase field and lowercase subsquares
+/// whatever the case it was read in.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GridSquare(String);
+
+pub(crate) fn cabrillo_grid_square(input: &str) -> IResult<&str, GridSquare> {
+	map(cabrillo_grid_locator, |grid: &str| {
+		let grid = grid.chars().enumerate()
+			.map(|(idx, c)| if idx < 2 { c.to_ascii_uppercase() } else { c.to_ascii_lowercase() })
+			.collect();
+
+		GridSquare(grid)
+	})(input)
+}
+
+/// How many characters a GRID-LOCATOR must have. Contests which score by
+/// square accept any locator, while VHF contests may need the subsquare.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GridPrecision {
+	/// Any of 4, 6, 8, or 10 characters.
+	#[default]
+	Any,
+	/// At least this many characters, such as 6 for a subsquare.
+	AtLeast(usize),
+	/// Exactly this many characters.
+	Exactly(usize)
+}
+
+impl GridPrecision {
+	pub fn allows(&self, grid: &GridSquare) -> bool {
+		match *self {
+			GridPrecision::Any => true,
+			GridPrecision::AtLeast(len) => grid.precision() >= len,
+			GridPrecision::Exactly(len) => grid.precision() == len
+		}
+	}
+}
+
+impl Display for GridPrecision {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			GridPrecision::Any => f.write_str("any number of"),
+			GridPrecision::AtLeast(len) => write!(f, "at least {}", len),
+			GridPrecision::Exactly(len) => write!(f, "exactly {}", len)
+		}
+	}
+}
+
+impl GridSquare {
+	pub fn as_str(&self) -> &str {
+		&self.0
+	}
+
+	/// Number of characters in the locator, from 4 to 10.
+	pub fn precision(&self) -> usize {
+		self.0.len()
+	}
+
+	/// Read a locator from other log formats, such as `JN79XX`.
+	pub(crate) fn normalize(token: &str) -> Option<Self> {
+		token.parse().ok()
+	}
+
+	/// Latitude and longitude of the center of the square, in degrees. North
+	/// and east are positive.
+	pub fn to_lat_lon(&self) -> (f64, f64) {
+		let chars: Vec<u8> = self.0.to_ascii_uppercase().into_bytes();
+		let mut lon = -180.0;
+		let mut lat = -90.0;
+		let mut width = 20.0;
+		let mut height = 10.0;
+
+		for (idx, pair) in chars.chunks(2).enumerate() {
+			let (base, divisions) = match idx {
+				0 => (b'A', 1.0),
+				1 | 3 => (b'0', 10.0),
+				_ => (b'A', 24.0)
+			};
+
+			width /= divisions;
+			height /= divisions;
+			lon += (pair[0] - base) as f64 * width;
+			lat += (pair[1] - base) as f64 * height;
+		}
+
+		(lat + height / 2.0, lon + width / 2.0)
+	}
+
+	/// Great circle distance between the centers of two squares, in kilometres.
+	pub fn distance_km(&self, other: &GridSquare) -> f64 {
+		let (lat1, lon1) = self.to_lat_lon();
+		let (lat2, lon2) = other.to_lat_lon();
+		let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+		let dlat = lat2 - lat1;
+		let dlon = (lon2 - lon1).to_radians();
+
+		let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+		2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+	}
+}
+
+impl CabrilloLog {
+	/// Check that the locator of a GRID-LOCATOR line has the precision asked
+	/// for. Locators which do not parse are left to the tag parser.
+	pub(crate) fn check_tag_grid(tag: &str, value: &str, precision: GridPrecision) -> Result<(), String> {
+		match GridSquare::normalize(value.trim()) {
+			Some(grid) if tag == "GRID-LOCATOR" && !precision.allows(&grid) =>
+				Err(format!("Grid locator '{}' must have {} characters", grid, precision)),
+			_ => Ok(())
+		}
+	}
+}
+
+impl Display for GridSquare {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.0)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	fn assert_near(actual: (f64, f64), expected: (f64, f64)) {
+		assert!((actual.0 - expected.0).abs() < 1e-6 && (actual.1 - expected.1).abs() < 1e-6, "{:?}", actual);
+	}
+
+	#[test]
+	fn grid_square() {
+		assert_near(GridSquare::try_from("FN31").unwrap().to_lat_lon(), (41.5, -73.0));
+		assert_near(GridSquare::try_from("JJ00").unwrap().to_lat_lon(), (0.5, 1.0));
+		assert_near(GridSquare::try_from("FN31pr").unwrap().to_lat_lon(), (41.729166667, -72.708333333));
+		assert_near(GridSquare::try_from("FN31pr45").unwrap().to_lat_lon(), (41.73125, -72.7125));
+		assert!(GridSquare::try_from("ZZ99").is_err());
+		assert_near(GridSquare::try_from("FN31pr45ab").unwrap().to_lat_lon(), (41.729427083, -72.716493056));
+		assert!(GridSquare::try_from("FN3145").is_err());
+		assert!(GridSquare::try_from("FN31pz").is_err());
+		assert_eq!(GridSquare::normalize("jn79XX").unwrap().as_str(), "JN79xx");
+		assert_eq!(GridSquare::try_from("fn31PR45AB").unwrap().as_str(), "FN31pr45ab");
+
+		let fn31 = GridSquare::try_from("FN31").unwrap();
+		let fn42 = GridSquare::try_from("FN42").unwrap();
+		assert!((fn31.distance_km(&fn42) - 195.0).abs() < 5.0);
+
+		let log = CabrilloLog::from_path("test_data/cqww.txt").unwrap();
+		assert_eq!(log.grid_locator().as_ref().map(GridSquare::as_str), Some("FN20ib"));
+	}
+
+	#[test]
+	fn grid_precision() {
+		let buf = "START-OF-LOG: 3.0\nGRID-LOCATOR: fn31\nEND-OF-LOG:\n";
+		let log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+		assert_eq!(log.grid_locator().as_ref().map(GridSquare::as_str), Some("FN31"));
+
+		let options = ParseOptions { grid_precision: GridPrecision::AtLeast(6), ..ParseOptions::default() };
+		let err = CabrilloLog::from_buffer_with(buf.as_bytes(), &options).unwrap_err();
+		assert_eq!(err.tag(), "GRID-LOCATOR");
+		assert_eq!(err.line(), 1);
+
+		let options = ParseOptions { grid_precision: GridPrecision::Exactly(4), ..ParseOptions::default() };
+		assert!(CabrilloLog::from_buffer_with(buf.as_bytes(), &options).is_ok());
+	}
+}
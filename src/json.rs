@@ -0,0 +1,111 @@
+use std::io::Write;
+use crate::*;
+
+fn json_error(err: serde_json::Error) -> CabrilloError {
+	CabrilloError::new("", err.line(), CabrilloErrorKind::ParseError(err.to_string()))
+}
+
+/// A QSO as written by `stream_json_lines()`.
+#[derive(Serialize)]
+struct JsonLine<'a> {
+	#[serde(flatten)]
+	qso: &'a Qso,
+	ignored: bool
+}
+
+fn write_json_line<W: Write>(writer: &mut W, line_no: usize, qso: &Qso, ignored: bool) -> CabrilloResult<()> {
+	serde_json::to_writer(&mut *writer, &JsonLine { qso, ignored })
+		.map_err(|err| err.to_string())
+		.and_then(|_| writeln!(writer).map_err(|err| err.to_string()))
+		.map_err(|err| CabrilloError::new("QSO", line_no, CabrilloErrorKind::IoError(err)))
+}
+
+impl CabrilloLog {
+	/// Serialize this log as a JSON object.
+	///
+	/// The schema is stable across releases of this crate: header fields are keyed
+	/// by their Cabrillo tag (`"CALLSIGN"`, `"CATEGORY-BAND"`, ...), the version is
+	/// stored under `"START-OF-LOG"`, and unrecognized tags under `"OTHER-TAGS"`.
+	/// Unset header fields are `null`. Category values use their Cabrillo tokens.
+	/// `"OPERATORS"` is an array of callsigns, `"OFFTIME"` an array of objects with
+	/// `begin` and `end`, and `"QSO"` and `"X-QSO"` arrays of objects with the
+	/// fields `frequency`, `mode`, `datetime`, `call_sent`, `exchange_sent`,
+	/// `call_received`, `exchange_received`, and `transmitter_id`. Timestamps are
+	/// ISO 8601 strings without a time zone, and are always UTC.
+	pub fn to_json(&self) -> CabrilloResult<String> {
+		serde_json::to_string(self).map_err(json_error)
+	}
+
+	/// Like `to_json()`, but indented for readability.
+	pub fn to_json_pretty(&self) -> CabrilloResult<String> {
+		serde_json::to_string_pretty(self).map_err(json_error)
+	}
+
+	/// Deserialize a log from JSON produced by `to_json()`. Missing fields take
+	/// their default values.
+	pub fn from_json(json: &str) -> CabrilloResult<Self> {
+		serde_json::from_str(json).map_err(json_error)
+	}
+
+	/// Parse a log from `reader`, writing each QSO to `writer` as a JSON object on
+	/// its own line (JSON Lines) as soon as it is read, instead of keeping it in
+	/// memory. QSO objects have the same fields as in `to_json()`, plus `ignored`,
+	/// which is true for X-QSO entries.
+	///
+	/// Returns the header of the log, which has no entries.
+	pub fn stream_json_lines<R: BufRead, W: Write>(reader: &mut R, writer: &mut W) -> CabrilloResult<Self> {
+		let mut header = Self::new();
+		header.read_lines(reader, &mut ParseState::new(&ParseOptions::default()), |log, line_no| {
+			for qso in log.entries.drain(..) {
+				write_json_line(writer, line_no, &qso, false)?;
+			}
+
+			for qso in log.ignored_entries.drain(..) {
+				write_json_line(writer, line_no, &qso, true)?;
+			}
+
+			Ok(())
+		})?;
+
+		Ok(header)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn json_round_trip() {
+		let log = CabrilloLog::from_path("test_data/ncj_naqp.txt").unwrap();
+		let json = log.to_json().unwrap();
+
+		assert!(json.contains("\"CONTEST\":\"NAQP-CW\""));
+		assert!(json.contains("\"CATEGORY-POWER\":\"LOW\""));
+		assert_eq!(CabrilloLog::from_json(&json).unwrap().to_string(), log.to_string());
+
+		let error = CabrilloLog::from_json("{\"CATEGORY-BAND\": \"11M\"}").unwrap_err();
+		assert!(matches!(error.kind(), CabrilloErrorKind::ParseError(_)));
+	}
+
+	#[test]
+	fn stream_json_lines() {
+		let buf = std::fs::read("test_data/cqww.txt").unwrap();
+		let log = CabrilloLog::from_buffer(&buf).unwrap();
+
+		let mut output = Vec::new();
+		let header = CabrilloLog::stream_json_lines(&mut buf.as_slice(), &mut output).unwrap();
+		assert!(header.entries().is_empty());
+		assert_eq!(header.callsign(), log.callsign());
+
+		let output = String::from_utf8(output).unwrap();
+		let lines: Vec<serde_json::Value> = output.lines()
+			.map(|line| serde_json::from_str(line).unwrap())
+			.collect();
+
+		assert_eq!(lines.len(), log.entries().len());
+		assert_eq!(lines[0]["call_received"], "K9QZO");
+		assert_eq!(lines[0]["mode"], "SSB");
+		assert_eq!(lines[0]["ignored"], false);
+	}
+}
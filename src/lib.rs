@@ -1,24 +1,106 @@
 #[macro_use]
 extern crate lazy_static;
 extern crate nom;
+#[cfg(feature = "chrono")]
 extern crate chrono;
+#[cfg(feature = "time")]
+extern crate time;
+
+mod datetime;
+mod writer;
+mod builder;
+mod adif;
+mod csv;
+mod edi;
+mod scp;
+mod summary;
+mod report;
+mod custom;
+mod section;
+mod extension;
+mod exchange;
+mod schema;
+mod other_tags;
+mod operating_time;
+mod grid;
+mod callsign;
+mod validate;
+mod contest_rules;
+mod band_plan;
+mod claimed_score;
+mod normalize;
+mod location;
+mod contests;
+mod country;
+mod scoring;
+mod multipliers;
+mod stats;
+#[cfg(feature = "serde")]
+mod serde_impl;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+#[cfg(feature = "arrow")]
+mod arrow;
+
+pub use builder::CabrilloLogBuilder;
+pub use adif::AdifExchange;
+pub use csv::CsvColumns;
+pub use report::{ValidationReport, ValidationWarning, SkippedLine, PartialParse, Finding, Severity};
+pub use custom::{CustomValue, TagHandlers};
+pub use section::Section;
+pub use extension::{Extension, Extensions};
+pub use other_tags::OtherTags;
+pub use operating_time::OperatingTime;
+pub use validate::{Rule, RuleSet, RequiredTags, LogStructure, ValueRanges, CategoryConsistency, ModeCategory, Chronology, OfftimeRule};
+pub use contest_rules::{ContestRules, ContestPeriod, ContestWindow, Weekend, StandardRules};
+pub use band_plan::{BandPlan, IaruRegion};
+pub use claimed_score::ClaimedScore;
+pub use normalize::NormalizeOptions;
+pub use location::LocationList;
+pub use contests::{ContestRegistry, KnownContest};
+pub use country::{Continent, Country, CountryTable};
+pub use scoring::{Score, ScoreBreakdown, QsoPoints, CqWw, CqWpx, Sweepstakes, FieldDay, FieldDayScore};
+pub use multipliers::{MultiplierKey, Multipliers};
+pub use stats::{RateStats, RateWindow};
+pub use exchange::{Exchange, ExchangeField, ExchangeFieldKind};
+pub use schema::{ContestSchema, ExchangeSchema, SchemaRegistry};
+use schema::BUILTIN_SCHEMAS;
+use section::cabrillo_section;
+use grid::cabrillo_grid_square;
+pub use grid::{GridSquare, GridPrecision};
+use callsign::callsign_shaped;
+pub use callsign::{Callsign, CallsignStrictness};
+pub use scp::{scp_callsigns, write_scp};
+pub use writer::{CabrilloWriter, QsoFormat, WriteOptions, LineEnding, VersionWarning};
+pub use datetime::{DateTime, Duration, UtcDateTime};
 
 use std::str;
-use std::io::BufRead;
+use std::io::{BufRead, BufReader};
+use std::fs::File;
+use std::path::Path;
 use std::fmt::{self, Display};
 use std::error::Error;
 use std::convert::TryFrom;
-use std::collections::HashMap;
-use chrono::NaiveDateTime;
+use std::str::FromStr;
+use std::collections::{HashMap, HashSet};
+use std::borrow::Cow;
+use std::hash::{Hash, Hasher};
+use std::ops::RangeInclusive;
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
 use nom::{
 	IResult,
 	branch::alt,
 	multi::{
 		many1,
 		many_m_n,
-		fold_many1
+		fold_many1,
+		separated_list1
 	},
 	combinator::{
+		all_consuming,
 		eof,
 		not,
 		opt,
@@ -26,23 +108,27 @@ use nom::{
 		recognize,
 		complete,
 		map,
-		map_res
+		map_opt,
+		map_res,
+		verify
 	},
 	sequence::{
 		tuple,
 		preceded,
 		terminated,
-		separated_pair
+		separated_pair,
+		pair
 	},
 	bytes::complete::{
 		tag,
+		take_till1,
+		take_while1,
 		take_while_m_n
 	},
 	character::complete::{
 		digit1,
 		space0,
 		space1,
-		alphanumeric1,
 		not_line_ending,
 		one_of,
 		char
@@ -57,13 +143,42 @@ macro_rules! parser_map {
 	}}
 }
 
+type TagParser = for<'a> fn(&'a str, &'a mut CabrilloLog) -> IResult<&'a str, ()>;
+
+/// Implements `FromStr` and `TryFrom<&str>` for a type using one of the internal
+/// parsers, so that the accepted tokens always match those of the log parser.
+macro_rules! impl_from_str {
+	($type: ty, $parser: ident) => {
+		impl FromStr for $type {
+			type Err = CabrilloErrorKind;
+
+			fn from_str(input: &str) -> Result<Self, Self::Err> {
+				terminated($parser, eof)(input)
+					.map(|(_, value)| value)
+					.map_err(|_| CabrilloErrorKind::ParseError(
+						format!("'{}' is not a valid {}", input, stringify!($type))
+					))
+			}
+		}
+
+		impl TryFrom<&str> for $type {
+			type Error = CabrilloErrorKind;
+
+			fn try_from(input: &str) -> Result<Self, Self::Error> {
+				input.parse()
+			}
+		}
+	}
+}
+
 lazy_static! {
-	static ref TAGS: HashMap<&'static str, for<'a> fn(&'a str, &'a mut CabrilloLog) -> IResult<&'a str, ()>> = {
+	static ref TAGS: HashMap<&'static str, TagParser> = {
 		parser_map![
-			<for<'a> fn(&'a str, &'a mut CabrilloLog) -> IResult<&'a str, ()>> 
+			<TagParser> 
 			"START-OF-LOG"         => cabrillo_log_start,
 			"CALLSIGN"             => cabrillo_log_callsign,
 			"CONTEST"              => cabrillo_log_contest,
+			"CATEGORY"             => cabrillo_log_category_v2,
 			"CATEGORY-ASSISTED"    => cabrillo_log_category_assisted,
 			"CATEGORY-BAND"        => cabrillo_log_category_band,
 			"CATEGORY-MODE"        => cabrillo_log_category_mode,
@@ -80,6 +195,7 @@ lazy_static! {
 			"EMAIL"                => cabrillo_log_email,
 			"GRID-LOCATOR"         => cabrillo_log_grid_locator,
 			"LOCATION"             => cabrillo_log_location,
+			"ARRL-SECTION"         => cabrillo_log_arrl_section,
 			"NAME"                 => cabrillo_log_name,
 			"ADDRESS"              => cabrillo_log_addr_fragment,
 			"ADDRESS-CITY"         => cabrillo_log_addr_fragment,
@@ -97,6 +213,24 @@ lazy_static! {
 	};
 }
 
+/// Byte offset into `input` where a parser returning `error` stopped.
+fn error_column(input: &str, error: &nom::Err<nom::error::Error<&str>>) -> Option<usize> {
+	match error {
+		nom::Err::Error(error) | nom::Err::Failure(error) => Some(input.len() - error.input.len()),
+		nom::Err::Incomplete(_) => None
+	}
+}
+
+/// An error for the value of `tag`, which starts at byte `column` of its line.
+fn tag_error(tag: &str, line_no: usize, value: &str, column: usize, error: &nom::Err<nom::error::Error<&str>>) -> CabrilloError {
+	let error_kind = CabrilloErrorKind::ParseError(error.to_string());
+
+	match error_column(value, error) {
+		Some(offset) => CabrilloError::new(tag, line_no, error_kind).with_column(column + offset),
+		None => CabrilloError::new(tag, line_no, error_kind)
+	}
+}
+
 fn cabrillo_tag(input: &str) -> IResult<&str, (&str, &str)> {
 	alt((
 		complete(
@@ -110,34 +244,69 @@ fn cabrillo_tag(input: &str) -> IResult<&str, (&str, &str)> {
 	))(input)
 }
 
-fn cabrillo_email_chars(input: &str) -> IResult<&str, &str> {
-	recognize(
-		alt((
-			alphanumeric1,
-			recognize(many1(one_of("_-.")))
-		))
+/// Like `cabrillo_tag`, but accepts any amount of whitespace after the colon,
+/// including none.
+fn cabrillo_tag_relaxed(input: &str) -> IResult<&str, (&str, &str)> {
+	complete(
+		separated_pair(
+			recognize(many1(one_of("ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789-' "))),
+			tuple((char(':'), space0)),
+			not_line_ending
+		)
 	)(input)
 }
 
+/// Characters allowed in the local part of an e-mail address besides letters,
+/// digits, and dots.
+const EMAIL_LOCAL_SPECIALS: &str = "!#$%&'*+/=?^_`{|}~-";
+
+/// Whether `address` is a plausible e-mail address: a local part of letters,
+/// digits, and the specials of RFC 5322, with dots only between them, and a
+/// domain of at least two labels of letters, digits, and inner hyphens, ending
+/// in a top level domain of letters or an IDN `xn--` label. Quoted local parts
+/// and IP address domains are not accepted.
+fn is_valid_email(address: &str) -> bool {
+	let Some((local, domain)) = address.rsplit_once('@') else { return false };
+	let labels: Vec<&str> = domain.split('.').collect();
+	let tld = labels[labels.len() - 1];
+
+	(1..=64).contains(&local.len())
+		&& local.split('.').all(|atom| {
+			!atom.is_empty() && atom.chars().all(|c| c.is_ascii_alphanumeric() || EMAIL_LOCAL_SPECIALS.contains(c))
+		})
+		&& domain.len() <= 253
+		&& labels.len() >= 2
+		&& labels.iter().all(|label| {
+			(1..=63).contains(&label.len())
+				&& label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+				&& !label.starts_with('-')
+				&& !label.ends_with('-')
+		})
+		&& tld.len() >= 2
+		&& (tld.chars().all(|c| c.is_ascii_alphabetic()) || tld.to_ascii_lowercase().starts_with("xn--"))
+}
+
 fn cabrillo_email(input: &str) -> IResult<&str, &str> {
-	recognize(
-		tuple((
-			cabrillo_email_chars,
-			tag("@"),
-			cabrillo_email_chars,
-			tag("."),
-			take_while_m_n(2, 5, char::is_alphanumeric)
-		))
-	)(input)
+	verify(take_till1(char::is_whitespace), is_valid_email)(input)
 }
 
+/// A Maidenhead locator of 4, 6, 8, or 10 characters in any case.
 fn cabrillo_grid_locator(input: &str) -> IResult<&str, &str> {
+	let field = |c: char| ('A'..='R').contains(&c.to_ascii_uppercase());
+	let subsquare = |c: char| ('A'..='X').contains(&c.to_ascii_uppercase());
+	let digit = |c: char| c.is_ascii_digit();
+
 	recognize(
 		tuple((
-			many_m_n(2, 2, one_of("ABCDEFGHIJKLMNOPQR")),
-			many_m_n(2, 2, one_of("0123456789")),
-			opt(many_m_n(2, 2, one_of("abcdefghijklmnopqrstuvwxyz"))),
-			opt(many_m_n(2, 2, one_of("0123456789"))),
+			take_while_m_n(2, 2, field),
+			take_while_m_n(2, 2, digit),
+			opt(pair(
+				take_while_m_n(2, 2, subsquare),
+				opt(pair(
+					take_while_m_n(2, 2, digit),
+					opt(take_while_m_n(2, 2, subsquare))
+				))
+			)),
 			eof
 		))
 	)(input)
@@ -148,35 +317,41 @@ fn cabrillo_grid_locator(input: &str) -> IResult<&str, &str> {
 // 	re_match_static!(r"^([A-Ra-r]{2})([0-9]{2})([A-Ra-r]{2}){0,1}$")
 // );
 
+/// A callsign of any shape, which is checked against
+/// `ParseOptions::callsigns` once the line has been parsed.
 fn cabrillo_callsign(input: &str) -> IResult<&str, &str> {
 	recognize(
 		tuple((
 			opt(tag("@")),
-			take_while_m_n(3, 8, char::is_alphanumeric),
-			opt(
+			take_while_m_n(1, 12, char::is_alphanumeric),
+			many_m_n(0, 3,
 				preceded(
 					tag("/"),
-					take_while_m_n(1, 8, char::is_alphanumeric)
+					take_while_m_n(1, 12, char::is_alphanumeric)
 				)
 			)
 		))
 	)(input)
 }
 
-fn cabrillo_datetime(input: &str) -> IResult<&str, NaiveDateTime> {
-	map_res(
-		recognize(
-			tuple((
-				take_while_m_n(4, 4, |c: char| c.is_digit(10)),
-				tag("-"),
-				take_while_m_n(2, 2, |c: char| c.is_digit(10)),
-				tag("-"),
-				take_while_m_n(2, 2, |c: char| c.is_digit(10)),
-				tag(" "),
-				take_while_m_n(4, 4, |c: char| c.is_digit(10))
-			))
+fn cabrillo_datetime(input: &str) -> IResult<&str, DateTime> {
+	map_opt(
+		separated_pair(
+			recognize(
+				tuple((
+					take_while_m_n(4, 4, |c: char| c.is_ascii_digit()),
+					tag("-"),
+					take_while_m_n(2, 2, |c: char| c.is_ascii_digit()),
+					tag("-"),
+					take_while_m_n(2, 2, |c: char| c.is_ascii_digit())
+				))
+			),
+			space1, // some loggers separate fields with tabs
+			take_while_m_n(4, 4, |c: char| c.is_ascii_digit())
 		),
-		|date_str: &str| NaiveDateTime::parse_from_str(date_str, "%Y-%m-%d %H%M")
+		|(date, time): (&str, &str)| {
+			datetime::parse(&format!("{} {}", date, time), "%Y-%m-%d %H%M")
+		}
 	)(input)
 }
 
@@ -191,14 +366,192 @@ fn cabrillo_mode(input: &str) -> IResult<&str, Mode> {
 	))(input)
 }
 
+fn cabrillo_digital_mode(input: &str) -> IResult<&str, DigitalMode> {
+	alt((
+		value(DigitalMode::Ft8   , tag("FT8")),
+		value(DigitalMode::Ft4   , tag("FT4")),
+		value(DigitalMode::Psk31 , tag("PSK31")),
+		value(DigitalMode::Psk63 , tag("PSK63")),
+		value(DigitalMode::Jt65  , tag("JT65")),
+		value(DigitalMode::Jt9   , tag("JT9")),
+		value(DigitalMode::Js8   , tag("JS8")),
+		value(DigitalMode::Msk144, tag("MSK144")),
+		value(DigitalMode::Olivia, tag("OLIVIA"))
+	))(input)
+}
+
+/// The mode of a QSO line, which some loggers give as the digital mode used
+/// rather than DG.
+fn cabrillo_qso_mode(input: &str) -> IResult<&str, (Mode, Option<DigitalMode>)> {
+	alt((
+		map(cabrillo_digital_mode, |submode| (Mode::Digital, Some(submode))),
+		map(cabrillo_mode, |mode| (mode, None))
+	))(input)
+}
+
+fn cabrillo_band(input: &str) -> IResult<&str, Band> {
+	alt((
+		alt((
+			value(Band::All      , tag("ALL")),
+			value(Band::Band160M , tag("160M")),
+			value(Band::Band80M  , tag("80M")),
+			value(Band::Band40M  , tag("40M")),
+			value(Band::Band20M  , tag("20M")),
+			value(Band::Band15M  , tag("15M")),
+			value(Band::Band10M  , tag("10M")),
+			value(Band::Band6M   , tag("6M")),
+			value(Band::Band4M   , tag("4M")),
+			value(Band::Band2M   , tag("2M")),
+			value(Band::Band222  , tag("222")),
+			value(Band::Band432  , tag("432")),
+			value(Band::Band902  , tag("902")),
+			value(Band::Band1_2G , tag("1.2G")),
+			value(Band::Band2_3G , tag("2.3G")),
+			value(Band::Band3_4G , tag("3.4G")),
+			value(Band::Band5_7G , tag("5.7G")),
+			value(Band::Band10G  , tag("10G")),
+			value(Band::Band24G  , tag("24G")),
+			value(Band::Band47G  , tag("47G")),
+		)),
+		alt((
+			value(Band::Band2200M, tag("2200M")),
+			value(Band::Band630M , tag("630M")),
+			value(Band::Band75G  , tag("75G")),
+			value(Band::Band123G , tag("123G")),
+			value(Band::Band134G , tag("134G")),
+			value(Band::Band241G , tag("241G")),
+			value(Band::Light    , tag("LIGHT")),
+			value(Band::Vhf3Band , tag("VHF-3-BAND")),
+			value(Band::VhfFmOnly, tag("VHF-FM-ONLY"))
+		))
+	))(input)
+}
+
+fn cabrillo_operator_category(input: &str) -> IResult<&str, OperatorCategory> {
+	alt((
+		value(OperatorCategory::SingleOp, tag("SINGLE-OP")),
+		value(OperatorCategory::MultiOp , tag("MULTI-OP")),
+		value(OperatorCategory::CheckLog, tag("CHECKLOG"))
+	))(input)
+}
+
+/// The operator part of a Cabrillo 2.0 CATEGORY value, which also carries the
+/// assisted, station, and transmitter categories of 3.0.
+#[derive(Clone)]
+struct V2Operator {
+	operator: Option<OperatorCategory>,
+	assisted: Option<bool>,
+	station: Option<StationCategory>,
+	transmitter: Option<TransmitterCategory>
+}
+
+impl V2Operator {
+	fn new(operator: OperatorCategory) -> Self {
+		Self {
+			operator: Some(operator),
+			assisted: None,
+			station: None,
+			transmitter: None
+		}
+	}
+
+	fn station(station: StationCategory) -> Self {
+		Self {
+			operator: None,
+			station: Some(station),
+			..Self::new(OperatorCategory::SingleOp)
+		}
+	}
+}
+
+fn cabrillo_v2_operator_category(input: &str) -> IResult<&str, V2Operator> {
+	use OperatorCategory::*;
+
+	alt((
+		value(V2Operator { assisted: Some(true), ..V2Operator::new(SingleOp) }, tag("SINGLE-OP-ASSISTED")),
+		value(V2Operator { station: Some(StationCategory::Portable), ..V2Operator::new(SingleOp) }, tag("SINGLE-OP-PORTABLE")),
+		value(V2Operator::new(SingleOp), tag("SINGLE-OP")),
+		value(V2Operator { transmitter: Some(TransmitterCategory::One), ..V2Operator::new(MultiOp) }, tag("MULTI-ONE")),
+		value(V2Operator { transmitter: Some(TransmitterCategory::Two), ..V2Operator::new(MultiOp) }, tag("MULTI-TWO")),
+		value(V2Operator { transmitter: Some(TransmitterCategory::Limited), ..V2Operator::new(MultiOp) }, tag("MULTI-LIMITED")),
+		value(V2Operator { transmitter: Some(TransmitterCategory::Unlimited), ..V2Operator::new(MultiOp) }, tag("MULTI-UNLIMITED")),
+		value(V2Operator::new(MultiOp), tag("MULTI-MULTI")),
+		value(V2Operator::station(StationCategory::Rover), tag("ROVER")),
+		value(V2Operator::station(StationCategory::School), tag("SCHOOL-CLUB")),
+		value(V2Operator::new(CheckLog), tag("CHECKLOG"))
+	))(input)
+}
+
+fn cabrillo_power_category(input: &str) -> IResult<&str, PowerCategory> {
+	alt((
+		value(PowerCategory::High, tag("HIGH")),
+		value(PowerCategory::Low , tag("LOW")),
+		value(PowerCategory::Qrp , tag("QRP"))
+	))(input)
+}
+
+fn cabrillo_station_category(input: &str) -> IResult<&str, StationCategory> {
+	alt((
+		value(StationCategory::Fixed         , tag("FIXED")),
+		value(StationCategory::Mobile        , tag("MOBILE")),
+		value(StationCategory::Portable      , tag("PORTABLE")),
+		value(StationCategory::RoverLimited  , tag("ROVER-LIMITED")),
+		value(StationCategory::RoverUnlimited, tag("ROVER-UNLIMITED")),
+		value(StationCategory::Rover         , tag("ROVER")),
+		value(StationCategory::Expedition    , tag("EXPEDITION")),
+		value(StationCategory::Hq            , tag("HQ")),
+		value(StationCategory::School        , tag("SCHOOL")),
+		value(StationCategory::Distributed   , tag("DISTRIBUTED")),
+		value(StationCategory::Explorer      , tag("EXPLORER")),
+		value(StationCategory::Remote        , tag("REMOTE"))
+	))(input)
+}
+
+fn cabrillo_time_category(input: &str) -> IResult<&str, TimeCategory> {
+	alt((
+		value(TimeCategory::Hours6 , tag("6-HOURS")),
+		value(TimeCategory::Hours12, tag("12-HOURS")),
+		value(TimeCategory::Hours24, tag("24-HOURS"))
+	))(input)
+}
+
+fn cabrillo_xmitter_category(input: &str) -> IResult<&str, TransmitterCategory> {
+	alt((
+		value(TransmitterCategory::One      , tag("ONE")),
+		value(TransmitterCategory::Two      , tag("TWO")),
+		value(TransmitterCategory::Limited  , tag("LIMITED")),
+		value(TransmitterCategory::Unlimited, tag("UNLIMITED")),
+		value(TransmitterCategory::Swl      , tag("SWL"))
+	))(input)
+}
+
+/// Sponsors define their own overlays, so any token is accepted and those
+/// which are not in the specification are kept as `OverlayCategory::Other`.
+fn cabrillo_overlay_category(input: &str) -> IResult<&str, OverlayCategory> {
+	map(
+		take_till1(char::is_whitespace),
+		|token: &str| match token {
+			"CLASSIC"     => OverlayCategory::Classic,
+			"ROOKIE"      => OverlayCategory::Rookie,
+			"TB-WIRES"    => OverlayCategory::TbWires,
+			"NOVICE-TECH" => OverlayCategory::NoviceTech,
+			"OVER-50"     => OverlayCategory::Over50,
+			"YOUTH"       => OverlayCategory::Youth,
+			"YL"          => OverlayCategory::Yl,
+			"FIRST-TIMER" => OverlayCategory::FirstTimer,
+			other         => OverlayCategory::Other(other.to_string())
+		}
+	)(input)
+}
+
 fn cabrillo_offtime(input: &str) -> IResult<&str, Offtime> {
 	map(
 		separated_pair(
 			cabrillo_datetime,
-			char(' '),
+			space1,
 			cabrillo_datetime
 		),
-		|time_pair: (NaiveDateTime, NaiveDateTime)| {
+		|time_pair: (DateTime, DateTime)| {
 			Offtime {
 				begin: time_pair.0,
 				end: time_pair.1
@@ -209,81 +562,171 @@ fn cabrillo_offtime(input: &str) -> IResult<&str, Offtime> {
 
 fn cabrillo_frequency(input: &str) -> IResult<&str, Frequency> {
 	alt((
-		value(Frequency::Khz(50000)    , terminated(tag("50") , space1)),
-		value(Frequency::Khz(70000)    , terminated(tag("70") , space1)),
-		value(Frequency::Khz(144000)   , terminated(tag("144"), space1)),
-		value(Frequency::Khz(222000)   , terminated(tag("222"), space1)),
-		value(Frequency::Khz(432000)   , terminated(tag("432"), space1)),
-		value(Frequency::Khz(902000)   , terminated(tag("902"), space1)),
-		value(Frequency::Khz(1200000)  , tag("1.2G")),
-		value(Frequency::Khz(2300000)  , tag("2.3G")),
-		value(Frequency::Khz(3400000)  , tag("3.4G")),
-		value(Frequency::Khz(5700000)  , tag("5.7G")),
-		value(Frequency::Khz(10000000) , tag("10G")),
-		value(Frequency::Khz(24000000) , tag("24G")),
-		value(Frequency::Khz(47000000) , tag("47G")),
-		value(Frequency::Khz(75000000) , tag("75G")),
-		value(Frequency::Khz(122000000), tag("122G")),
-		value(Frequency::Khz(134000000), tag("134G")),
-		value(Frequency::Khz(241000000), tag("241G")),
 		value(Frequency::Light, tag("LIGHT")),
-		map(
-			map_res(
-				digit1,
-				|digits: &str| digits.parse::<u32>()
-			),
-			|freq: u32| {
-				// TODO: this should accept a frequency OR a band input and normalize to KHz
-				Frequency::Khz(freq)
+		map_opt(
+			terminated(take_while1(|c: char| c.is_ascii_alphanumeric() || c == '.'), not(not_space)),
+			|token: &str| {
+				FREQUENCY_DESIGNATORS.iter()
+					.find(|(_, designator, _)| *designator == token)
+					.map(|(band, _, _)| Frequency::Band(*band))
 			}
+		),
+		map_opt(
+			recognize(pair(digit1, opt(pair(char('.'), digit1)))),
+			parse_khz
 		)
 	))(input)
 }
 
-/*fn cabrillo_signal_report(input: &str) -> IResult<&str, SignalReport> {
+/// Read a frequency in kHz, which may have up to three decimal places.
+fn parse_khz(khz: &str) -> Option<Frequency> {
+	let (whole, fraction) = khz.split_once('.').unwrap_or((khz, ""));
+
+	if fraction.len() > 3 {
+		return None;
+	}
+
+	let whole = whole.parse::<u64>().ok()?.checked_mul(1000)?;
+	let fraction = match fraction {
+		"" => 0,
+		fraction => format!("{:0<3}", fraction).parse::<u64>().ok()?
+	};
+
+	Some(Frequency::Hz(whole + fraction))
+}
+
+/// Characters which may not follow a frequency designator.
+fn not_space(input: &str) -> IResult<&str, char> {
+	nom::character::complete::satisfy(|c: char| !c.is_whitespace())(input)
+}
+
+/// A digit of a signal report, allowing the cut number N for 9 used on CW.
+fn signal_digit<'a>(digits: &'static str) -> impl FnMut(&'a str) -> IResult<&'a str, u8> {
+	map_opt(
+		one_of(digits),
+		|digit: char| match digit {
+			'N' => Some(9),
+			digit => digit.to_digit(10).map(|digit| digit as u8)
+		}
+	)
+}
+
+/// Parse a signal report such as "599", "5NN", or "59". The tone is zero when
+/// it is not given, as on phone.
+pub fn cabrillo_signal_report(input: &str) -> IResult<&str, SignalReport> {
 	map(
 		tuple((
-			map_opt(
-				one_of("12345")),
-				|readability: char| readability.to_digit(10)
-			)),
-			map_opt(
-				one_of("123456789")),
-				|strength: char| strength.to_digit(10)
-			)),
-			alt((
-				map_opt(
-					one_of("123456789")),
-					|tone: char| tone.to_digit(10)
-				)),
-				value(0, eof)
-			))
-		))),
-		|rst: (u32, u32, u32)| SignalReport(rst.0 as u8, rst.1 as u8, rst.2 as u8)
+			signal_digit("12345"),
+			signal_digit("123456789N"),
+			opt(signal_digit("123456789N"))
+		)),
+		|rst: (u8, u8, Option<u8>)| SignalReport(rst.0, rst.1, rst.2.unwrap_or(0))
 	)(input)
-}*/
+}
 
-fn cabrillo_operators(input: &str) -> IResult<&str, Vec<String>> {
+fn cabrillo_operators(input: &str) -> IResult<&str, Vec<Callsign>> {
 	fold_many1(
 		terminated(
-			cabrillo_callsign,
+			map(cabrillo_callsign, Callsign::from),
 			opt(
-				terminated(
-					tag(","),
-					space0
-				)
+				alt((
+					terminated(
+						tag(","),
+						space0
+					),
+					space1
+				))
 			)
 		),
 		Vec::new,
-		|mut callsigns: Vec<_>, item| {
-			callsigns.push(item.to_string());
-			callsigns
+		|mut operators: Vec<_>, item| {
+			operators.push(item);
+			operators
 		}
 	)(input)
 }
 
-fn cabrillo_qso(input: &str) -> IResult<&str, Qso> {
-	map(
+/// Whether `token` has the shape of an amateur callsign: a prefix containing a
+/// letter, a digit, and a suffix of letters, optionally with a portable
+/// designator. This tells callsigns apart from exchange fields such as "599",
+/// "5NN", "10A", or "STX".
+fn looks_like_callsign(token: &str) -> bool {
+	token.trim_start_matches('@').split('/').any(callsign_shaped)
+}
+
+/// Where the received callsign and the end of the received exchange are in the
+/// fields of a QSO line following the timestamp, or None if there is no way to
+/// split them. Anything after the received exchange is the transmitter ID.
+///
+/// With the number of sent and received exchange fields known from the schema
+/// of the contest, the line must have exactly that many, optionally followed by
+/// the transmitter ID. Otherwise the sent and received exchanges are assumed to
+/// be the same length if that puts a callsign in the right place, and failing
+/// that the received call is the first callsign after at least one sent
+/// exchange field.
+fn split_qso_fields(fields: &[&str], exchange_fields: Option<(usize, usize)>) -> Option<(usize, usize)> {
+	if let Some((sent, received)) = exchange_fields {
+		let end = sent + received + 2;
+		return (fields.len() == end || fields.len() == end + 1).then_some((sent + 1, end));
+	}
+
+	if fields.len() < 4 {
+		return None;
+	}
+
+	// a single digit after equal length exchanges is the transmitter ID
+	let last = fields[fields.len() - 1];
+	let symmetric = match fields.len() % 2 {
+		0 => Some(fields.len() / 2),
+		_ if last.len() == 1 && last.chars().all(|c| c.is_ascii_digit()) => Some(fields.len() / 2),
+		_ => None
+	};
+
+	symmetric
+		.filter(|&index| looks_like_callsign(fields[index]))
+		.or_else(|| (2..fields.len() - 1).find(|&index| looks_like_callsign(fields[index])))
+		.or_else(|| symmetric.filter(|_| fields.len().is_multiple_of(2)))
+		.map(|index| match symmetric {
+			Some(middle) if middle == index => (index, 2 * index),
+			_ => (index, fields.len())
+		})
+}
+
+type QsoFields<'a> = (&'a str, String, &'a str, String, Option<u8>);
+
+/// Parse the calls, exchanges, and transmitter ID of a QSO line, which may have
+/// any number of exchange fields. See `split_qso_fields()`.
+fn cabrillo_qso_exchanges(exchange_fields: Option<(usize, usize)>) -> impl Fn(&str) -> IResult<&str, QsoFields> {
+	move |input: &str| {
+		let (rest, fields) = terminated(
+			separated_list1(space1, take_till1(char::is_whitespace)),
+			space0
+		)(input)?;
+
+		let (index, end) = split_qso_fields(&fields, exchange_fields)
+			.ok_or_else(|| nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Count)))?;
+
+		for call in [fields[0], fields[index]] {
+			all_consuming(cabrillo_callsign)(call)?;
+		}
+
+		let transmitter_id = match fields.get(end) {
+			Some(field) => Some(all_consuming(map_res(digit1, str::parse::<u8>))(field)?.1),
+			None => None
+		};
+
+		Ok((rest, (
+			fields[0],
+			fields[1..index].join(" "),
+			fields[index],
+			fields[index + 1..end].join(" "),
+			transmitter_id
+		)))
+	}
+}
+
+fn cabrillo_qso(exchange_fields: Option<(usize, usize)>) -> impl Fn(&str) -> IResult<&str, Qso> {
+	move |input: &str| map(
 		preceded(
 			space0,
 			tuple((
@@ -292,85 +735,56 @@ fn cabrillo_qso(input: &str) -> IResult<&str, Qso> {
 					space1
 				),
 				terminated(
-					cabrillo_mode,      // Mode
+					cabrillo_qso_mode,  // Mode
 					space1
 				),
 				terminated(
 					cabrillo_datetime,  // QSO timestamp
 					space1,
 				),
-				terminated(
-					cabrillo_callsign,  // Sent call
-					space1
-				),
-				terminated(             // Sent exchange
-					alt((
-						map(
-							separated_pair(
-								alphanumeric1,
-								space1,
-								recognize(
-									tuple((
-										not(cabrillo_callsign),
-										alphanumeric1
-									))
-								)
-							),
-							|pair: (&str, &str)| format!("{} {}", pair.0, pair.1)
-						),
-						map(alphanumeric1, |i: &str| i.to_string())
-					)),
-					space1
-				),
-				terminated(
-					cabrillo_callsign,  // Rcvd call
-					space1
-				),
-				terminated(             // Recvd exchange
-					alt((
-						map(
-							separated_pair(
-								alphanumeric1,
-								space1,
-								recognize(
-									tuple((
-										not(cabrillo_callsign),
-										alphanumeric1
-									))
-								)
-							),
-							|pair: (&str, &str)| format!("{} {}", pair.0, pair.1)
-						),
-						map(alphanumeric1, |i: &str| i.to_string())
-					)),
-					space0
-				)
+				cabrillo_qso_exchanges(exchange_fields)
 			)),
 		),
-		|data: (Frequency, Mode, NaiveDateTime, &str, String, &str, String)| {
+		|data: (Frequency, (Mode, Option<DigitalMode>), DateTime, QsoFields)| {
 			Qso {
 				frequency: data.0,
-				mode: data.1,
+				mode: data.1.0,
+				submode: data.1.1,
 				datetime: data.2,
-				call_sent: data.3.to_string(),
-				exch_sent: data.4,
-				call_recvd: data.5.to_string(),
-				exch_recvd: data.6,
-				transmitter_id: false
+				call_sent: Callsign::from(data.3.0),
+				exch_sent: data.3.1,
+				call_recvd: Callsign::from(data.3.2),
+				exch_recvd: data.3.3,
+				transmitter_id: data.3.4,
+				typed_sent: None,
+				typed_recvd: None,
+				line: None,
+				source_text: None
 			}
 		}
 	)(input)
 }
 
-fn cabrillo_log_start<'a>(input: &'a str, log: &'a mut CabrilloLog) -> IResult<&'a str, ()> {
+/// Versions other than 2.0 and 3.0 are kept as `CabrilloVersion::Other`, and
+/// reported as a warning when a log is parsed.
+fn cabrillo_version(input: &str) -> IResult<&str, CabrilloVersion> {
 	map(
-		alt((tag("2.0"), tag("3.0"))),
-		|version: &str| {
-			log.version = version.parse::<f32>().unwrap()
+		take_till1(char::is_whitespace),
+		|token: &str| match token {
+			"2.0"   => CabrilloVersion::V2,
+			"3.0"   => CabrilloVersion::V3,
+			version => CabrilloVersion::Other(version.to_string())
 		}
 	)(input)
 }
 
+fn cabrillo_log_start<'a>(input: &'a str, log: &'a mut CabrilloLog) -> IResult<&'a str, ()> {
+	map(
+		cabrillo_version,
+		|version: CabrilloVersion| log.version = version
+	)(input)
+}
+
 fn cabrillo_log_callsign<'a>(input: &'a str, log: &'a mut CabrilloLog) -> IResult<&'a str, ()> {
 	map(
 		cabrillo_callsign,
@@ -395,39 +809,7 @@ fn cabrillo_log_category_assisted<'a>(input: &'a str, log: &'a mut CabrilloLog)
 
 fn cabrillo_log_category_band<'a>(input: &'a str, log: &'a mut CabrilloLog) -> IResult<&'a str, ()> {
 	map(
-		alt((
-			alt((
-				value(Band::All      , tag("ALL")),
-				value(Band::Band160M , tag("160M")),
-				value(Band::Band80M  , tag("80M")),
-				value(Band::Band40M  , tag("40M")),
-				value(Band::Band20M  , tag("20M")),
-				value(Band::Band15M  , tag("15M")),
-				value(Band::Band10M  , tag("10M")),
-				value(Band::Band6M   , tag("6M")),
-				value(Band::Band4M   , tag("4M")),
-				value(Band::Band2M   , tag("2M")),
-				value(Band::Band222  , tag("222")),
-				value(Band::Band432  , tag("432")),
-				value(Band::Band902  , tag("902")),
-				value(Band::Band1_2G , tag("1.2G")),
-				value(Band::Band2_3G , tag("2.3G")),
-				value(Band::Band3_4G , tag("3.4G")),
-				value(Band::Band5_7G , tag("5.7G")),
-				value(Band::Band10G  , tag("10G")),
-				value(Band::Band24G  , tag("24G")),
-				value(Band::Band47G  , tag("47G")),
-			)),
-			alt((
-				value(Band::Band75G  , tag("75G")),
-				value(Band::Band123G , tag("123G")),
-				value(Band::Band134G , tag("134G")),
-				value(Band::Band241G , tag("241G")),
-				value(Band::Light    , tag("LIGHT")),
-				value(Band::Vhf3Band , tag("VHF-3-BAND")),
-				value(Band::VhfFmOnly, tag("VHF-FM-ONLY"))
-			))
-		)),
+		cabrillo_band,
 		|band: Band| log.category_band = Some(band)
 	)(input)
 }
@@ -439,78 +821,66 @@ fn cabrillo_log_category_mode<'a>(input: &'a str, log: &'a mut CabrilloLog) -> I
 	)(input)
 }
 
-fn cabrillo_log_category_operator<'a>(input: &'a str, log: &'a mut CabrilloLog) -> IResult<&'a str, ()> {
+/// The Cabrillo 2.0 CATEGORY tag, e.g. `SINGLE-OP ALL HIGH CW`, which holds the
+/// operator category followed by the band, power, and mode.
+fn cabrillo_log_category_v2<'a>(input: &'a str, log: &'a mut CabrilloLog) -> IResult<&'a str, ()> {
 	map(
-		alt((
-			value(OperatorCategory::SingleOp, tag("SINGLE-OP")),
-			value(OperatorCategory::MultiOp , tag("MULTI-OP")),
-			value(OperatorCategory::CheckLog, tag("CHECKLOG"))
+		tuple((
+			cabrillo_v2_operator_category,
+			opt(preceded(space1, cabrillo_band)),
+			opt(preceded(space1, cabrillo_power_category)),
+			opt(preceded(space1, cabrillo_mode))
 		)),
+		|(op, band, power, mode)| {
+			log.category_operator = op.operator;
+			log.category_assisted = op.assisted.or(log.category_assisted);
+			log.category_station = op.station.or(log.category_station);
+			log.category_transmitter = op.transmitter.or(log.category_transmitter);
+			log.category_band = band;
+			log.category_power = power;
+			log.category_mode = mode;
+		}
+	)(input)
+}
+
+fn cabrillo_log_category_operator<'a>(input: &'a str, log: &'a mut CabrilloLog) -> IResult<&'a str, ()> {
+	map(
+		cabrillo_operator_category,
 		|op: OperatorCategory| log.category_operator = Some(op)
 	)(input)
 }
 
 fn cabrillo_log_category_power<'a>(input: &'a str, log: &'a mut CabrilloLog) -> IResult<&'a str, ()> {
 	map(
-		alt((
-			value(PowerCategory::High, tag("HIGH")),
-			value(PowerCategory::Low , tag("LOW")),
-			value(PowerCategory::Qrp , tag("QRP"))
-		)),
+		cabrillo_power_category,
 		|power: PowerCategory| log.category_power = Some(power)
 	)(input)
 }
 
 fn cabrillo_log_category_station<'a>(input: &'a str, log: &'a mut CabrilloLog) -> IResult<&'a str, ()> {
 	map(
-		alt((
-			value(StationCategory::Fixed         , tag("FIXED")),
-			value(StationCategory::Mobile        , tag("MOBILE")),
-			value(StationCategory::Portable      , tag("PORTABLE")),
-			value(StationCategory::Rover         , tag("ROVER")),
-			value(StationCategory::RoverLimited  , tag("ROVER-LIMITED")),
-			value(StationCategory::RoverUnlimited, tag("ROVER-UNLIMITED")),
-			value(StationCategory::Expedition    , tag("EXPEDITION")),
-			value(StationCategory::Hq            , tag("HQ")),
-			value(StationCategory::School        , tag("SCHOOL"))
-		)),
+		cabrillo_station_category,
 		|st: StationCategory| log.category_station = Some(st)
 	)(input)
 }
 
 fn cabrillo_log_category_time<'a>(input: &'a str, log: &'a mut CabrilloLog) -> IResult<&'a str, ()> {
 	map(
-		alt((
-			value(TimeCategory::Hours6 , tag("6-HOURS")),
-			value(TimeCategory::Hours12, tag("12-HOURS")),
-			value(TimeCategory::Hours24, tag("24-HOURS"))
-		)),
+		cabrillo_time_category,
 		|time: TimeCategory| log.category_time = Some(time)
 	)(input)
 }
 
 fn cabrillo_log_category_xmitter<'a>(input: &'a str, log: &'a mut CabrilloLog) -> IResult<&'a str, ()> {
 	map(
-		alt((
-			value(TransmitterCategory::One      , tag("ONE")),
-			value(TransmitterCategory::Two      , tag("TWO")),
-			value(TransmitterCategory::Limited  , tag("LIMITED")),
-			value(TransmitterCategory::Unlimited, tag("UNLIMITED")),
-			value(TransmitterCategory::Swl      , tag("SWL"))
-		)),
+		cabrillo_xmitter_category,
 		|xmitter: TransmitterCategory| log.category_transmitter = Some(xmitter)
 	)(input)
 }
 
 fn cabrillo_log_category_overlay<'a>(input: &'a str, log: &'a mut CabrilloLog) -> IResult<&'a str, ()> {
 	map(
-		alt((
-			value(OverlayCategory::Classic   , tag("CLASSIC")),
-			value(OverlayCategory::Rookie    , tag("ROOKIE")),
-			value(OverlayCategory::TbWires   , tag("TB-WIRES")),
-			value(OverlayCategory::NoviceTech, tag("NOVICE-TECH")),
-			value(OverlayCategory::Over50    , tag("OVER-50"))
-		)),
+		cabrillo_overlay_category,
 		|overlay: OverlayCategory| log.category_overlay = Some(overlay)
 	)(input)
 }
@@ -547,15 +917,18 @@ fn cabrillo_log_created_by<'a>(input: &'a str, log: &'a mut CabrilloLog) -> IRes
 
 fn cabrillo_log_email<'a>(input: &'a str, log: &'a mut CabrilloLog) -> IResult<&'a str, ()> {
 	map(
-		cabrillo_email,
+		terminated(
+			cabrillo_email,
+			pair(space0, eof)
+		),
 		|email: &str| log.email = Some(email.to_string())
 	)(input)
 }
 
 fn cabrillo_log_grid_locator<'a>(input: &'a str, log: &'a mut CabrilloLog) -> IResult<&'a str, ()> {
 	map(
-		cabrillo_grid_locator,
-		|grid_square: &str| log.grid_locator = Some(grid_square.to_string())
+		cabrillo_grid_square,
+		|grid_square: GridSquare| log.grid_locator = Some(grid_square)
 	)(input)
 }
 
@@ -564,28 +937,27 @@ fn cabrillo_log_location<'a>(input: &'a str, log: &'a mut CabrilloLog) -> IResul
 	Ok(("", ()))
 }
 
+fn cabrillo_log_arrl_section<'a>(input: &'a str, log: &'a mut CabrilloLog) -> IResult<&'a str, ()> {
+	map(
+		preceded(space0, cabrillo_section),
+		|section: Section| log.arrl_section = Some(section)
+	)(input)
+}
+
 fn cabrillo_log_name<'a>(input: &'a str, log: &'a mut CabrilloLog) -> IResult<&'a str, ()> {
 	log.name = Some(input.trim().to_string());
 	Ok(("", ()))
 }
 
 fn cabrillo_log_addr_fragment<'a>(input: &'a str, log: &'a mut CabrilloLog) -> IResult<&'a str, ()> {
-	let value = input.trim();
-
-	if let Some(ref mut address) = log.address {
-		address.push('\n');
-		address.push_str(value);
-	} else {
-		log.address = Some(value.to_string());
-	}
-	
+	log.push_address_line(input);
 	Ok(("", ()))
 }
 
 fn cabrillo_log_operators<'a>(input: &'a str, log: &'a mut CabrilloLog) -> IResult<&'a str, ()> {
 	map(
 		cabrillo_operators,
-		|ops: Vec<String>| log.operators.extend(ops)
+		|ops: Vec<Callsign>| log.operators.extend(ops)
 	)(input)
 }
 
@@ -597,30 +969,35 @@ fn cabrillo_log_offtime<'a>(input: &'a str, log: &'a mut CabrilloLog) -> IResult
 }
 
 fn cabrillo_log_soapbox<'a>(input: &'a str, log: &'a mut CabrilloLog) -> IResult<&'a str, ()> {
-	let value = input.trim();
+	log.push_soapbox_line(input);
+	Ok(("", ()))
+}
 
-	if let Some(ref mut soapbox) = log.soapbox {
-		soapbox.push('\n');
-		soapbox.push_str(value);
-	} else {
-		log.soapbox = Some(value.to_string());
+/// Parse a QSO or X-QSO line using the schema of the contest, if `schemas` has
+/// one, to split and type its exchanges.
+fn cabrillo_log_qso_with<'a>(input: &'a str, log: &'a mut CabrilloLog, schemas: &SchemaRegistry, ignored: bool) -> IResult<&'a str, ()> {
+	let schema = schemas.for_log(log);
+	let (rest, mut qso) = cabrillo_qso(schema.map(|schema| (schema.sent().len(), schema.received().len())))(input)?;
+
+	if let Some(schema) = schema {
+		qso.typed_sent = qso.exchange_sent_as(schema.sent()).ok();
+		qso.typed_recvd = qso.exchange_received_as(schema.received()).ok();
 	}
-	
-	Ok(("", ()))
+
+	match ignored {
+		false => log.entries.push(qso),
+		true => log.ignored_entries.push(qso)
+	}
+
+	Ok((rest, ()))
 }
 
 fn cabrillo_log_qso<'a>(input: &'a str, log: &'a mut CabrilloLog) -> IResult<&'a str, ()> {
-	map(
-		cabrillo_qso,
-		|qso: Qso| log.entries.push(qso)
-	)(input)
+	cabrillo_log_qso_with(input, log, &BUILTIN_SCHEMAS, false)
 }
 
 fn cabrillo_ignore_qso<'a>(input: &'a str, log: &'a mut CabrilloLog) -> IResult<&'a str, ()> {
-	map(
-		cabrillo_qso,
-		|qso: Qso| log.ignored_entries.push(qso)
-	)(input)
+	cabrillo_log_qso_with(input, log, &BUILTIN_SCHEMAS, true)
 }
 
 fn cabrillo_log_debug<'a>(_input: &'a str, log: &'a mut CabrilloLog) -> IResult<&'a str, ()> {
@@ -632,10 +1009,12 @@ fn cabrillo_log_end<'a>(_input: &'a str, _log: &'a mut CabrilloLog) -> IResult<&
 	Ok(("", ()))
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum CabrilloErrorKind {
 	IoError(String),
 	ParseError(String),
+	/// A tag in the wrong place, such as a QSO after END-OF-LOG.
+	StructureError(String),
 	Other(String)
 }
 
@@ -644,15 +1023,29 @@ impl Display for CabrilloErrorKind {
 		match self {
 			CabrilloErrorKind::IoError(error) => write!(f, "I/O Error: {}", error),
 			CabrilloErrorKind::ParseError(error) => write!(f, "Parse Error: {}", error),
+			CabrilloErrorKind::StructureError(error) => write!(f, "Structure Error: {}", error),
 			CabrilloErrorKind::Other(error) => write!(f, "Unknown Error: {}", error)
 		}
 	}
 }
 
+impl CabrilloErrorKind {
+	/// The message of a parse error on its own, or the whole error otherwise,
+	/// for errors reported as warnings or findings instead.
+	pub(crate) fn into_message(self) -> String {
+		match self {
+			CabrilloErrorKind::ParseError(message) => message,
+			error => error.to_string()
+		}
+	}
+}
+
 #[derive(Debug, Clone)]
 pub struct CabrilloError {
 	tag: String,
 	line: usize,
+	column: Option<usize>,
+	raw: Option<String>,
 	kind: CabrilloErrorKind
 }
 
@@ -661,10 +1054,24 @@ impl CabrilloError {
 		Self {
 			tag: tag.to_string(),
 			line,
+			column: None,
+			raw: None,
 			kind
 		}
 	}
 
+	/// Set the byte offset within the line where the error was found.
+	pub fn with_column(mut self, column: usize) -> Self {
+		self.column = Some(column);
+		self
+	}
+
+	/// Set the text of the line the error was found on.
+	pub fn with_raw(mut self, raw: &str) -> Self {
+		self.raw = Some(raw.to_string());
+		self
+	}
+
 	pub fn tag(&self) -> &String {
 		&self.tag
 	}
@@ -673,6 +1080,18 @@ impl CabrilloError {
 		self.line
 	}
 
+	/// The text of the line which was rejected, if the error came from parsing
+	/// a line.
+	pub fn raw(&self) -> &Option<String> {
+		&self.raw
+	}
+
+	/// Byte offset within the line where parsing failed, if known. Like the
+	/// line number, this starts at zero.
+	pub fn column(&self) -> Option<usize> {
+		self.column
+	}
+
 	pub fn kind(&self) -> &CabrilloErrorKind {
 		&self.kind
 	}
@@ -680,7 +1099,12 @@ impl CabrilloError {
 
 impl Display for CabrilloError {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		write!(f, "{} in tag '{}' on line {}", self.kind, self.tag, self.line)
+		write!(f, "{} in tag '{}' on line {}", self.kind, self.tag, self.line)?;
+
+		match self.column {
+			Some(column) => write!(f, ", column {}", column),
+			None => Ok(())
+		}
 	}
 }
 
@@ -688,34 +1112,132 @@ impl Error for CabrilloError {}
 
 pub type CabrilloResult<T> = std::result::Result<T, CabrilloError>;
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+/// Version of the Cabrillo specification a log is written in. Logs claiming
+/// any other version are written using the structure of 3.0.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum CabrilloVersion {
+	V2,
+	#[default]
+	V3,
+	Other(String)
+}
+
+impl CabrilloVersion {
+	/// The version as a number, or None if an unknown version is not numeric.
+	pub fn as_f32(&self) -> Option<f32> {
+		match self {
+			CabrilloVersion::V2 => Some(2.0),
+			CabrilloVersion::V3 => Some(3.0),
+			CabrilloVersion::Other(version) => version.parse().ok()
+		}
+	}
+}
+
+impl Display for CabrilloVersion {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			CabrilloVersion::V2 => write!(f, "2.0"),
+			CabrilloVersion::V3 => write!(f, "3.0"),
+			CabrilloVersion::Other(version) => write!(f, "{}", version)
+		}
+	}
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Frequency {
-	Khz(u32),
+	/// An exact frequency, in Hz. QSO lines give it in kHz, which some loggers
+	/// write with a fractional part for narrow digital segments.
+	Hz(u64),
+	/// A band designator such as `144` or `1.2G`, which VHF and higher logs give
+	/// instead of an exact frequency.
+	Band(Band),
 	Light
 }
 
+/// Designators used in the frequency field of QSOs on 50 MHz and higher, and
+/// the frequency in KHz each one names. The specification calls the 122 GHz
+/// band 122G in QSOs but 123G in CATEGORY-BAND, and loggers write both, so both
+/// are read. The first designator of a band is the one written.
+const FREQUENCY_DESIGNATORS: [(Band, &str, u32); 18] = [
+	(Band::Band6M  , "50"  , 50000),
+	(Band::Band4M  , "70"  , 70000),
+	(Band::Band2M  , "144" , 144000),
+	(Band::Band222 , "222" , 222000),
+	(Band::Band432 , "432" , 432000),
+	(Band::Band902 , "902" , 902000),
+	(Band::Band1_2G, "1.2G", 1200000),
+	(Band::Band2_3G, "2.3G", 2300000),
+	(Band::Band3_4G, "3.4G", 3400000),
+	(Band::Band5_7G, "5.7G", 5700000),
+	(Band::Band10G , "10G" , 10000000),
+	(Band::Band24G , "24G" , 24000000),
+	(Band::Band47G , "47G" , 47000000),
+	(Band::Band75G , "75G" , 75000000),
+	(Band::Band123G, "122G", 122000000),
+	(Band::Band123G, "123G", 122000000),
+	(Band::Band134G, "134G", 134000000),
+	(Band::Band241G, "241G", 241000000)
+];
+
+fn band_designator(band: &Band) -> Option<&'static (Band, &'static str, u32)> {
+	FREQUENCY_DESIGNATORS.iter().find(|(designator_band, _, _)| designator_band == band)
+}
+
 impl Frequency {
-	/// Convert the inner frequency from KHz to MHz. If the frequency is considered 
-	/// Light, then None will be returned.
-	pub fn as_mhz(&self) -> Option<f32> {
+	pub fn from_hz(hz: u64) -> Self {
+		Frequency::Hz(hz)
+	}
+
+	pub fn from_khz(khz: u32) -> Self {
+		Frequency::Hz(khz as u64 * 1000)
+	}
+
+	/// A frequency in MHz, rounded to the nearest Hz.
+	pub fn from_mhz(mhz: f64) -> Self {
+		Frequency::Hz((mhz * 1000000.0).round() as u64)
+	}
+
+	/// The frequency in Hz. For a band designator this is the frequency it
+	/// names, e.g. 144000000 for `144`. If the frequency is considered Light,
+	/// then None will be returned.
+	pub fn as_hz(&self) -> Option<u64> {
 		match self {
-			Frequency::Khz(frequency) => {
-				let as_mhz: f32 = *frequency as f32 / 1000.0;
-				Some(as_mhz)
-			},
+			Frequency::Hz(hz) => Some(*hz),
+			Frequency::Band(band) => band_designator(band).map(|(_, _, khz)| *khz as u64 * 1000),
 			Frequency::Light => None
 		}
 	}
 
-	/// Convert the inner frequency from KHz to GHz. If the frequency is considered
+	/// The frequency in KHz, rounded down. For a band designator this is the
+	/// frequency it names, e.g. 144000 for `144`. If the frequency is considered
+	/// Light, then None will be returned.
+	pub fn as_khz(&self) -> Option<u32> {
+		self.as_hz().map(|hz| (hz / 1000) as u32)
+	}
+
+	/// Convert the inner frequency to MHz. If the frequency is considered
+	/// Light, then None will be returned.
+	pub fn as_mhz(&self) -> Option<f32> {
+		self.as_hz().map(|hz| (hz as f64 / 1000000.0) as f32)
+	}
+
+	/// Convert the inner frequency to GHz. If the frequency is considered
 	/// Light, then None will be returned.
 	pub fn as_ghz(&self) -> Option<f32> {
+		self.as_hz().map(|hz| (hz as f64 / 1000000000.0) as f32)
+	}
+
+	/// The token written in the frequency field of a QSO line: whole kHz, or
+	/// kHz with as many decimal places as needed.
+	pub(crate) fn token(&self) -> String {
 		match self {
-			Frequency::Khz(frequency) => {
-				let as_ghz: f32 = *frequency as f32 / 1000000.0;
-				Some(as_ghz)
-			},
-			Frequency::Light => None
+			Frequency::Hz(hz) if hz % 1000 == 0 => (hz / 1000).to_string(),
+			Frequency::Hz(hz) => format!("{}.{:03}", hz / 1000, hz % 1000).trim_end_matches('0').to_string(),
+			Frequency::Band(band) => band_designator(band)
+				.map(|(_, designator, _)| designator.to_string())
+				.unwrap_or_else(|| band.to_string()),
+			Frequency::Light => "LIGHT".to_string()
 		}
 	}
 
@@ -724,18 +1246,21 @@ impl Frequency {
 	}
 }
 
-impl ToString for Frequency {
-	fn to_string(&self) -> String {
+impl Display for Frequency {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		match self {
-			Frequency::Khz(freq) => format!("{} KHz", freq),
-			Frequency::Light => "LIGHT".to_string()
+			Frequency::Hz(_) => write!(f, "{} KHz", self.token()),
+			Frequency::Band(_) => write!(f, "{}", self.token()),
+			Frequency::Light => write!(f, "LIGHT")
 		}
 	}
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Band {
 	All,
+	Band2200M,
+	Band630M,
 	Band160M,
 	Band80M,
 	Band40M,
@@ -764,48 +1289,115 @@ pub enum Band {
 	VhfFmOnly
 }
 
+/// Edges of each band in KHz, inclusive.
+const BAND_EDGES: [(Band, u32, u32); 25] = [
+	(Band::Band2200M, 135, 138),
+	(Band::Band630M, 472, 479),
+	(Band::Band160M, 1800, 2000),
+	(Band::Band80M, 3500, 4000),
+	(Band::Band40M, 7000, 7300),
+	(Band::Band20M, 14000, 14350),
+	(Band::Band15M, 21000, 21450),
+	(Band::Band10M, 28000, 29700),
+	(Band::Band6M, 50000, 54000),
+	(Band::Band4M, 70000, 70500),
+	(Band::Band2M, 144000, 148000),
+	(Band::Band222, 219000, 225000),
+	(Band::Band432, 420000, 450000),
+	(Band::Band902, 902000, 928000),
+	(Band::Band1_2G, 1240000, 1300000),
+	(Band::Band2_3G, 2390000, 2450000),
+	(Band::Band3_4G, 3300000, 3500000),
+	(Band::Band5_7G, 5650000, 5925000),
+	(Band::Band10G, 10000000, 10500000),
+	(Band::Band24G, 24000000, 24250000),
+	(Band::Band47G, 47000000, 47200000),
+	(Band::Band75G, 76000000, 81000000),
+	(Band::Band123G, 122250000, 123000000),
+	(Band::Band134G, 134000000, 141000000),
+	(Band::Band241G, 241000000, 250000000)
+];
+
+/// Frequencies from 300 GHz up are considered Light.
+const LIGHT_KHZ: u32 = 300000000;
+
+impl Band {
+	/// The edges of the band in KHz, inclusive. Light has no upper edge and
+	/// `ALL`, `VHF-3-BAND`, and `VHF-FM-ONLY` are not a single range, so these
+	/// return None.
+	pub fn frequency_range(&self) -> Option<RangeInclusive<u32>> {
+		BAND_EDGES.iter()
+			.find(|(band, _, _)| band == self)
+			.map(|(_, low, high)| *low..=*high)
+	}
+
+	/// Whether `frequency` falls within this band. A band designator is only
+	/// within its own band, and `ALL` contains every frequency.
+	pub fn contains(&self, frequency: &Frequency) -> bool {
+		match self {
+			Band::All => true,
+			_ => Band::try_from(*frequency).is_ok_and(|band| band == *self)
+		}
+	}
+}
+
 impl TryFrom<Frequency> for Band {
 	type Error = CabrilloErrorKind;
 
 	fn try_from(other: Frequency) -> Result<Self, Self::Error> {
 		match other {
-			Frequency::Khz(freq) => {
-				match freq {
-					_ if (1800..=2000).contains(&freq) => Ok(Band::Band160M),
-					_ if (3500..=4000).contains(&freq) => Ok(Band::Band80M),
-					_ if (7000..=7300).contains(&freq) => Ok(Band::Band40M),
-					_ if (14000..=14350).contains(&freq) => Ok(Band::Band20M),
-					_ if (21000..=21450).contains(&freq) => Ok(Band::Band15M),
-					_ if (28000..=29700).contains(&freq) => Ok(Band::Band10M),
-					_ if (50000..=54000).contains(&freq) => Ok(Band::Band6M),
-					_ if (70000..=70500).contains(&freq) => Ok(Band::Band4M),
-					_ if (144000..=148000).contains(&freq) => Ok(Band::Band2M),
-					_ if (219000..=225000).contains(&freq) => Ok(Band::Band222),
-					_ if (420000..=450000).contains(&freq) => Ok(Band::Band432),
-					_ if (902000..=928000).contains(&freq) => Ok(Band::Band902),
-					_ if (1240000..=1300000).contains(&freq) => Ok(Band::Band1_2G),
-					_ if (2390000..=2450000).contains(&freq) => Ok(Band::Band2_3G),
-					_ if (3300000..=3500000).contains(&freq) => Ok(Band::Band3_4G),
-					_ if (5650000..=5925000).contains(&freq) => Ok(Band::Band5_7G),
-					_ if (10000000..=10500000).contains(&freq) => Ok(Band::Band10G),
-					_ if (24000000..=24250000).contains(&freq) => Ok(Band::Band24G),
-					_ if (47000000..=47200000).contains(&freq) => Ok(Band::Band47G),
-					_ if (76000000..=81000000).contains(&freq) => Ok(Band::Band75G),
-					_ if (122250000..=123000000).contains(&freq) => Ok(Band::Band123G),
-					_ if (134000000..=141000000).contains(&freq) => Ok(Band::Band134G),
-					_ if (241000000..=250000000).contains(&freq) => Ok(Band::Band241G),
-					_ if freq >= 300000000 => Ok(Band::Light),
-					_ => Err(
-						CabrilloErrorKind::ParseError(format!("The value '{}' does not fall within a valid amateur band", other.to_string()))
-					)
-				}
+			Frequency::Hz(hz) => {
+				BAND_EDGES.iter()
+					.find(|(_, low, high)| (*low as u64 * 1000..=*high as u64 * 1000).contains(&hz))
+					.map(|(band, _, _)| *band)
+					.or(if hz >= LIGHT_KHZ as u64 * 1000 { Some(Band::Light) } else { None })
+					.ok_or_else(|| {
+						CabrilloErrorKind::ParseError(format!("The value '{}' does not fall within a valid amateur band", other))
+					})
 			},
+			Frequency::Band(band) => Ok(band),
 			Frequency::Light => Ok(Band::Light)
 		}
 	}
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+impl Display for Band {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Band::All       => write!(f, "ALL"),
+			Band::Band2200M => write!(f, "2200M"),
+			Band::Band630M  => write!(f, "630M"),
+			Band::Band160M  => write!(f, "160M"),
+			Band::Band80M   => write!(f, "80M"),
+			Band::Band40M   => write!(f, "40M"),
+			Band::Band20M   => write!(f, "20M"),
+			Band::Band15M   => write!(f, "15M"),
+			Band::Band10M   => write!(f, "10M"),
+			Band::Band6M    => write!(f, "6M"),
+			Band::Band4M    => write!(f, "4M"),
+			Band::Band2M    => write!(f, "2M"),
+			Band::Band222   => write!(f, "222"),
+			Band::Band432   => write!(f, "432"),
+			Band::Band902   => write!(f, "902"),
+			Band::Band1_2G  => write!(f, "1.2G"),
+			Band::Band2_3G  => write!(f, "2.3G"),
+			Band::Band3_4G  => write!(f, "3.4G"),
+			Band::Band5_7G  => write!(f, "5.7G"),
+			Band::Band10G   => write!(f, "10G"),
+			Band::Band24G   => write!(f, "24G"),
+			Band::Band47G   => write!(f, "47G"),
+			Band::Band75G   => write!(f, "75G"),
+			Band::Band123G  => write!(f, "123G"),
+			Band::Band134G  => write!(f, "134G"),
+			Band::Band241G  => write!(f, "241G"),
+			Band::Light     => write!(f, "LIGHT"),
+			Band::Vhf3Band  => write!(f, "VHF-3-BAND"),
+			Band::VhfFmOnly => write!(f, "VHF-FM-ONLY")
+		}
+	}
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Mode {
 	Cw,
 	Phone,
@@ -815,12 +1407,65 @@ pub enum Mode {
 	Mixed
 }
 
-/*
-/// A tuple type representing the 3 parts of a signal report (readability, strength, and tone). If the tone
+/// Modes are displayed using the CATEGORY-MODE tokens. QSO lines use the
+/// abbreviated forms (PH, RY, DG) instead.
+impl Display for Mode {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Mode::Cw      => write!(f, "CW"),
+			Mode::Phone   => write!(f, "SSB"),
+			Mode::Fm      => write!(f, "FM"),
+			Mode::Rtty    => write!(f, "RTTY"),
+			Mode::Digital => write!(f, "DIGI"),
+			Mode::Mixed   => write!(f, "MIXED")
+		}
+	}
+}
+
+/// The digital mode used for a QSO, when its mode column names one instead of
+/// DG.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DigitalMode {
+	Ft8,
+	Ft4,
+	Psk31,
+	Psk63,
+	Jt65,
+	Jt9,
+	Js8,
+	Msk144,
+	Olivia
+}
+
+impl Display for DigitalMode {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			DigitalMode::Ft8    => write!(f, "FT8"),
+			DigitalMode::Ft4    => write!(f, "FT4"),
+			DigitalMode::Psk31  => write!(f, "PSK31"),
+			DigitalMode::Psk63  => write!(f, "PSK63"),
+			DigitalMode::Jt65   => write!(f, "JT65"),
+			DigitalMode::Jt9    => write!(f, "JT9"),
+			DigitalMode::Js8    => write!(f, "JS8"),
+			DigitalMode::Msk144 => write!(f, "MSK144"),
+			DigitalMode::Olivia => write!(f, "OLIVIA")
+		}
+	}
+}
+
+/// A tuple type representing the 3 parts of a signal report (readability, strength, and tone). The tone
 /// will always be zero if it is not provided.
-#[derive(Debug, Copy, Clone, PartialEq)]
-pub struct SignalReport(u8, u8, u8);
-*/
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SignalReport(pub u8, pub u8, pub u8);
+
+impl Display for SignalReport {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.2 {
+			0 => write!(f, "{}{}", self.0, self.1),
+			tone => write!(f, "{}{}{}", self.0, self.1, tone)
+		}
+	}
+}
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum OperatorCategory {
@@ -829,6 +1474,16 @@ pub enum OperatorCategory {
 	CheckLog
 }
 
+impl Display for OperatorCategory {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			OperatorCategory::SingleOp => write!(f, "SINGLE-OP"),
+			OperatorCategory::MultiOp  => write!(f, "MULTI-OP"),
+			OperatorCategory::CheckLog => write!(f, "CHECKLOG")
+		}
+	}
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum PowerCategory {
 	High,
@@ -836,6 +1491,16 @@ pub enum PowerCategory {
 	Qrp
 }
 
+impl Display for PowerCategory {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			PowerCategory::High => write!(f, "HIGH"),
+			PowerCategory::Low  => write!(f, "LOW"),
+			PowerCategory::Qrp  => write!(f, "QRP")
+		}
+	}
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum StationCategory {
 	Fixed,
@@ -846,7 +1511,30 @@ pub enum StationCategory {
 	RoverUnlimited,
 	Expedition,
 	Hq,
-	School
+	School,
+	Distributed,
+	Explorer,
+	/// Not in the specification, but used by some sponsors.
+	Remote
+}
+
+impl Display for StationCategory {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			StationCategory::Fixed          => write!(f, "FIXED"),
+			StationCategory::Mobile         => write!(f, "MOBILE"),
+			StationCategory::Portable       => write!(f, "PORTABLE"),
+			StationCategory::Rover          => write!(f, "ROVER"),
+			StationCategory::RoverLimited   => write!(f, "ROVER-LIMITED"),
+			StationCategory::RoverUnlimited => write!(f, "ROVER-UNLIMITED"),
+			StationCategory::Expedition     => write!(f, "EXPEDITION"),
+			StationCategory::Hq             => write!(f, "HQ"),
+			StationCategory::School         => write!(f, "SCHOOL"),
+			StationCategory::Distributed    => write!(f, "DISTRIBUTED"),
+			StationCategory::Explorer       => write!(f, "EXPLORER"),
+			StationCategory::Remote         => write!(f, "REMOTE")
+		}
+	}
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -856,6 +1544,16 @@ pub enum TimeCategory {
 	Hours24
 }
 
+impl Display for TimeCategory {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			TimeCategory::Hours6  => write!(f, "6-HOURS"),
+			TimeCategory::Hours12 => write!(f, "12-HOURS"),
+			TimeCategory::Hours24 => write!(f, "24-HOURS")
+		}
+	}
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum TransmitterCategory {
 	One,
@@ -865,30 +1563,125 @@ pub enum TransmitterCategory {
 	Swl
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+impl Display for TransmitterCategory {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			TransmitterCategory::One       => write!(f, "ONE"),
+			TransmitterCategory::Two       => write!(f, "TWO"),
+			TransmitterCategory::Limited   => write!(f, "LIMITED"),
+			TransmitterCategory::Unlimited => write!(f, "UNLIMITED"),
+			TransmitterCategory::Swl       => write!(f, "SWL")
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum OverlayCategory {
 	Classic,
 	Rookie,
 	TbWires,
 	NoviceTech,
-	Over50
+	Over50,
+	Youth,
+	Yl,
+	FirstTimer,
+	/// An overlay defined by the contest sponsor.
+	Other(String)
 }
 
+impl Display for OverlayCategory {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			OverlayCategory::Classic    => write!(f, "CLASSIC"),
+			OverlayCategory::Rookie     => write!(f, "ROOKIE"),
+			OverlayCategory::TbWires    => write!(f, "TB-WIRES"),
+			OverlayCategory::NoviceTech => write!(f, "NOVICE-TECH"),
+			OverlayCategory::Over50     => write!(f, "OVER-50"),
+			OverlayCategory::Youth      => write!(f, "YOUTH"),
+			OverlayCategory::Yl         => write!(f, "YL"),
+			OverlayCategory::FirstTimer => write!(f, "FIRST-TIMER"),
+			OverlayCategory::Other(overlay) => write!(f, "{}", overlay)
+		}
+	}
+}
+
+impl_from_str!(Band, cabrillo_band);
+impl_from_str!(CabrilloVersion, cabrillo_version);
+impl_from_str!(Mode, cabrillo_mode);
+impl_from_str!(DigitalMode, cabrillo_digital_mode);
+impl_from_str!(SignalReport, cabrillo_signal_report);
+impl_from_str!(OperatorCategory, cabrillo_operator_category);
+impl_from_str!(PowerCategory, cabrillo_power_category);
+impl_from_str!(StationCategory, cabrillo_station_category);
+impl_from_str!(TimeCategory, cabrillo_time_category);
+impl_from_str!(TransmitterCategory, cabrillo_xmitter_category);
+impl_from_str!(OverlayCategory, cabrillo_overlay_category);
+impl_from_str!(GridSquare, cabrillo_grid_square);
+
 /// A QSO is a contact made between two stations. This type holds the relevant metadata
 /// for each contact in the log.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone)]
 pub struct Qso {
 	frequency: Frequency,
 	mode: Mode,
-	datetime: NaiveDateTime,
-	call_sent: String,
+	submode: Option<DigitalMode>,
+	#[cfg_attr(feature = "serde", serde(with = "serde_impl::iso_datetime"))]
+	datetime: DateTime,
+	call_sent: Callsign,
+	#[cfg_attr(feature = "serde", serde(rename = "exchange_sent"))]
 	exch_sent: String,
-	call_recvd: String,
+	#[cfg_attr(feature = "serde", serde(rename = "call_received"))]
+	call_recvd: Callsign,
+	#[cfg_attr(feature = "serde", serde(rename = "exchange_received"))]
 	exch_recvd: String,
-	transmitter_id: bool
+	transmitter_id: Option<u8>,
+	#[cfg_attr(feature = "serde", serde(skip))]
+	typed_sent: Option<Exchange>,
+	#[cfg_attr(feature = "serde", serde(skip))]
+	typed_recvd: Option<Exchange>,
+	#[cfg_attr(feature = "serde", serde(skip))]
+	line: Option<usize>,
+	#[cfg_attr(feature = "serde", serde(skip))]
+	source_text: Option<String>
 }
 
 impl Qso {
+	/// Create a new QSO entry without a transmitter ID, which can be set with
+	/// `with_transmitter_id()`.
+	pub fn new<S: Into<String>>(frequency: Frequency, mode: Mode, datetime: DateTime,
+		call_sent: S, exch_sent: S, call_recvd: S, exch_recvd: S) -> Self
+	{
+		Self {
+			frequency,
+			mode,
+			submode: None,
+			datetime,
+			call_sent: Callsign::from(call_sent.into()),
+			exch_sent: exch_sent.into(),
+			call_recvd: Callsign::from(call_recvd.into()),
+			exch_recvd: exch_recvd.into(),
+			transmitter_id: None,
+			typed_sent: None,
+			typed_recvd: None,
+			line: None,
+			source_text: None
+		}
+	}
+
+	pub fn with_transmitter_id(mut self, transmitter_id: u8) -> Self {
+		self.transmitter_id = Some(transmitter_id);
+		self
+	}
+
+	/// Record which digital mode was used. This also sets the mode to
+	/// `Mode::Digital`.
+	pub fn with_submode(mut self, submode: DigitalMode) -> Self {
+		self.mode = Mode::Digital;
+		self.submode = Some(submode);
+		self
+	}
+
 	pub fn frequency(&self) -> &Frequency {
 		&self.frequency
 	}
@@ -897,12 +1690,28 @@ impl Qso {
 		&self.mode
 	}
 
-	pub fn datetime(&self) -> &NaiveDateTime {
+	/// The band the QSO was made on, or None if its frequency is outside the
+	/// amateur bands.
+	pub fn band(&self) -> Option<Band> {
+		Band::try_from(self.frequency).ok()
+	}
+
+	/// A message if the frequency of this QSO is outside the amateur bands.
+	pub(crate) fn check_band(&self) -> Option<String> {
+		self.band().is_none().then(|| format!("Frequency {} is not within an amateur band", self.frequency))
+	}
+
+	pub fn datetime(&self) -> &DateTime {
 		&self.datetime
 	}
 
+	/// Time of the QSO in UTC, which Cabrillo logs always use.
+	pub fn datetime_utc(&self) -> UtcDateTime {
+		datetime::to_utc(&self.datetime)
+	}
+
 	/// Callsign sent during QSO.
-	pub fn call_sent(&self) -> &String {
+	pub fn call_sent(&self) -> &Callsign {
 		&self.call_sent
 	}
 
@@ -912,7 +1721,7 @@ impl Qso {
 	}
 
 	/// Callsign received from other station.
-	pub fn call_received(&self) -> &String {
+	pub fn call_received(&self) -> &Callsign {
 		&self.call_recvd
 	}
 
@@ -921,35 +1730,553 @@ impl Qso {
 		&self.exch_recvd
 	}
 
-	pub fn transmitter_id(&self) -> bool {
-		self.transmitter_id
+	/// The signal report sent, for contests whose exchange starts with one.
+	/// This is the first field of the sent exchange if it reads as a report.
+	pub fn rst_sent(&self) -> Option<SignalReport> {
+		self.exch_sent.split_whitespace().next()?.parse().ok()
+	}
+
+	/// The signal report received, for contests whose exchange starts with one.
+	pub fn rst_received(&self) -> Option<SignalReport> {
+		self.exch_recvd.split_whitespace().next()?.parse().ok()
+	}
+
+	/// The sent exchange read using the schema of the contest, if the log was
+	/// parsed with one and the exchange matches it.
+	pub fn typed_exchange_sent(&self) -> &Option<Exchange> {
+		&self.typed_sent
+	}
+
+	/// The received exchange read using the schema of the contest, if the log
+	/// was parsed with one and the exchange matches it.
+	pub fn typed_exchange_received(&self) -> &Option<Exchange> {
+		&self.typed_recvd
+	}
+
+	/// Line of the log the QSO was read from, counting from zero like
+	/// `CabrilloError::line()`, or None if it was not read from a log.
+	pub fn line(&self) -> &Option<usize> {
+		&self.line
+	}
+
+	/// The original text of the line the QSO was read from, if the log was
+	/// parsed with `ParseOptions::keep_qso_text`.
+	pub fn source_text(&self) -> &Option<String> {
+		&self.source_text
+	}
+
+	/// What makes two QSOs duplicates in most contests: the band, the mode, and
+	/// the callsign worked, ignoring case.
+	pub fn dupe_key(&self) -> (Option<Band>, Mode, String) {
+		(self.band(), self.mode, self.call_recvd.as_str().to_ascii_uppercase())
+	}
+
+	/// Which transmitter made the QSO, in multi-transmitter logs. This is the
+	/// optional last column of a QSO line, usually 0 or 1, and None if the line
+	/// did not have one.
+	pub fn transmitter_id(&self) -> &Option<u8> {
+		&self.transmitter_id
+	}
+
+	/// The digital mode used, if the QSO line named one such as FT8 in place
+	/// of DG. It is written back the same way.
+	pub fn submode(&self) -> &Option<DigitalMode> {
+		&self.submode
 	}
 }
 
+/// QSOs are equal if every field of their lines is, ignoring where they were
+/// read from and the typed exchanges read from them.
+impl PartialEq for Qso {
+	fn eq(&self, other: &Self) -> bool {
+		self.frequency == other.frequency
+			&& self.mode == other.mode
+			&& self.submode == other.submode
+			&& self.datetime == other.datetime
+			&& self.call_sent == other.call_sent
+			&& self.exch_sent == other.exch_sent
+			&& self.call_recvd == other.call_recvd
+			&& self.exch_recvd == other.exch_recvd
+			&& self.transmitter_id == other.transmitter_id
+	}
+}
+
+impl Eq for Qso {}
+
+impl Hash for Qso {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.frequency.hash(state);
+		self.mode.hash(state);
+		self.submode.hash(state);
+		self.datetime.hash(state);
+		self.call_sent.hash(state);
+		self.exch_sent.hash(state);
+		self.call_recvd.hash(state);
+		self.exch_recvd.hash(state);
+		self.transmitter_id.hash(state);
+	}
+}
+
+/// A callsign from the OPERATORS tag, with the host station marked by a
+/// leading '@'.
+#[deprecated(note = "use Callsign")]
+pub type Operator = Callsign;
+
 // NOTE: actually I don't believe this spec provides a way to determine *which* of the
 // operators was off duty during this Offtime.
 
 /// This type represents a period in time where an operator in this log was 
 /// no longer operating.
-#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Offtime {
-	begin: NaiveDateTime,
-	end: NaiveDateTime
+	#[cfg_attr(feature = "serde", serde(with = "serde_impl::iso_datetime"))]
+	begin: DateTime,
+	#[cfg_attr(feature = "serde", serde(with = "serde_impl::iso_datetime"))]
+	end: DateTime
 }
 
 impl Offtime {
-	pub fn begin(&self) -> &NaiveDateTime {
+	pub fn new(begin: DateTime, end: DateTime) -> Self {
+		Self { begin, end }
+	}
+
+	pub fn begin(&self) -> &DateTime {
 		&self.begin
 	}
 
-	pub fn end(&self) -> &NaiveDateTime {
+	pub fn end(&self) -> &DateTime {
 		&self.end
 	}
+
+	/// Start of this offtime in UTC.
+	pub fn begin_utc(&self) -> UtcDateTime {
+		datetime::to_utc(&self.begin)
+	}
+
+	/// End of this offtime in UTC.
+	pub fn end_utc(&self) -> UtcDateTime {
+		datetime::to_utc(&self.end)
+	}
+
+	/// Length of this offtime, which is negative if it ends before it begins.
+	pub fn duration(&self) -> Duration {
+		self.end - self.begin
+	}
+
+	/// Whether this offtime and `other` share any period of time. Offtimes which
+	/// only touch, with one ending as the other begins, do not overlap.
+	pub fn overlaps(&self, other: &Offtime) -> bool {
+		self.begin < other.end && other.begin < self.end
+	}
+
+	/// A message if this offtime ends before it begins.
+	pub(crate) fn check_order(&self) -> Option<String> {
+		(self.begin > self.end).then(|| "Offtime ends before it begins".to_string())
+	}
+
+	/// A message if this offtime overlaps any of the `earlier` ones.
+	pub(crate) fn check_overlap(&self, earlier: &[Offtime]) -> Option<String> {
+		earlier.iter().any(|other| other.overlaps(self)).then(|| "Offtime overlaps an earlier offtime".to_string())
+	}
+}
+
+/// A line from the original input of a log, recorded to allow the log to be
+/// written back out with its original ordering and formatting.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceLine {
+	tag: String,
+	raw: String
+}
+
+impl SourceLine {
+	/// The tag of this line, or an empty string for blank lines.
+	pub fn tag(&self) -> &String {
+		&self.tag
+	}
+
+	/// The original text of this line.
+	pub fn raw(&self) -> &String {
+		&self.raw
+	}
+}
+
+/// Byte order mark written by some Windows editors at the start of a file.
+const UTF8_BOM: &[u8] = b"\xef\xbb\xbf";
+
+/// Character encoding of a log being parsed.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum Encoding {
+	/// Lines which are not valid UTF-8 are errors.
+	#[default]
+	Utf8Strict,
+	/// Invalid UTF-8 sequences are replaced with U+FFFD.
+	Utf8Lossy,
+	/// Every byte is a character of ISO 8859-1, as written by many older loggers.
+	Latin1
 }
 
+impl Encoding {
+	fn decode<'a>(&self, bytes: &'a [u8]) -> Result<Cow<'a, str>, str::Utf8Error> {
+		match self {
+			Encoding::Utf8Strict => str::from_utf8(bytes).map(Cow::Borrowed),
+			Encoding::Utf8Lossy => Ok(String::from_utf8_lossy(bytes)),
+			Encoding::Latin1 => Ok(Cow::Owned(bytes.iter().map(|&byte| byte as char).collect()))
+		}
+	}
+}
+
+/// Longest line allowed by the Cabrillo specification, in characters.
+pub const MAX_LINE_LENGTH: usize = 75;
+
+/// How lines after END-OF-LOG are handled.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum AfterEndOfLog {
+	/// Parse them like any other line.
+	#[default]
+	Parse,
+	/// Leave them out of the log.
+	Ignore,
+	/// Keep them as text in `CabrilloLog::trailing_text()`.
+	Collect,
+	/// Fail with a `StructureError` on the first line which is not blank.
+	Error
+}
+
+/// How repeated header tags are handled. Tags which may appear on several
+/// lines, like `ADDRESS`, `OPERATORS`, `OFFTIME`, `SOAPBOX`, and QSOs, are
+/// never duplicates, and neither are unrecognized tags, which are all kept in
+/// `CabrilloLog::other_tags()`.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum DuplicateTags {
+	/// The last value replaces earlier ones.
+	#[default]
+	LastWins,
+	/// Later values are ignored.
+	FirstWins,
+	/// The last value replaces earlier ones, with a warning in the
+	/// `ValidationReport`.
+	Warn,
+	/// Fail with a `StructureError`.
+	Error
+}
+
+/// Tags which may appear more than once in a log.
+const REPEATABLE_TAGS: [&str; 6] = ["ADDRESS", "OPERATORS", "OFFTIME", "SOAPBOX", "QSO", "X-QSO"];
+
+/// Options controlling how a log is parsed. The default is strict, where the
+/// first malformed line aborts parsing.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ParseOptions {
+	/// Keep the values of known header tags which cannot be parsed, such as
+	/// `CATEGORY-BAND: 11M`, in `other_tags` instead of failing. QSO and X-QSO
+	/// lines must still parse.
+	pub tolerate_unknown_values: bool,
+	/// Skip lines which are not of the form `TAG: value`.
+	pub skip_untagged_lines: bool,
+	/// Leave out QSO and header lines which fail to parse and continue with the
+	/// rest of the log. Skipped lines are listed in the `ValidationReport`.
+	pub skip_bad_lines: bool,
+	/// Record the original lines so that the log can be written back out with
+	/// its formatting intact.
+	pub preserve_source: bool,
+	/// Accept tags and values in any case, such as `Callsign: w1aw`, or `cw` and
+	/// `10g` as the mode and frequency of a QSO. Values of free-text tags like
+	/// `NAME` keep their case.
+	pub ignore_case: bool,
+	/// Accept tags without a space after the colon, like `CALLSIGN:W1AW`, or
+	/// with a tab or several spaces.
+	pub relaxed_separator: bool,
+	/// How the bytes of the log are decoded into text.
+	pub encoding: Encoding,
+	/// Require START-OF-LOG to be the first tag and END-OF-LOG the last, failing
+	/// with a `StructureError` otherwise. Without this, a log missing either
+	/// marker is accepted.
+	pub require_envelope: bool,
+	/// What to do with lines after END-OF-LOG.
+	pub after_end_of_log: AfterEndOfLog,
+	/// Warn about lines longer than this many characters. Some log checking
+	/// robots reject lines longer than `MAX_LINE_LENGTH`.
+	pub max_line_length: Option<usize>,
+	/// What to do when a tag which holds a single value, such as `CALLSIGN`,
+	/// appears more than once.
+	pub duplicate_tags: DuplicateTags,
+	/// Accept OFFTIME periods which overlap each other, with a warning, instead
+	/// of failing.
+	pub allow_overlapping_offtimes: bool,
+	/// Accept times in QSO and OFFTIME lines written as `HHMMSS`, `HH:MM`, or
+	/// `HH:MM:SS`, truncating them to minutes.
+	pub lenient_times: bool,
+	/// Exchange layouts of contests, used to split the exchanges of QSO lines
+	/// and read them as a typed `Exchange`. Defaults to the built in schemas.
+	pub schemas: SchemaRegistry,
+	/// Keep the original text of each QSO line, available from
+	/// `Qso::source_text()`.
+	pub keep_qso_text: bool,
+	/// Parsers for additional tags, added with `register_tag()`.
+	pub tag_handlers: TagHandlers,
+	/// How closely the callsigns of CALLSIGN, OPERATORS, and QSO lines must
+	/// follow the usual form.
+	pub callsigns: CallsignStrictness,
+	/// How many characters the GRID-LOCATOR of the log must have.
+	pub grid_precision: GridPrecision
+}
+
+impl ParseOptions {
+	/// Fail on the first malformed line. This is the default.
+	pub fn strict() -> Self {
+		Self::default()
+	}
+
+	/// Accept logs with unrecognized header values, stray lines, tags or values
+	/// in lowercase, tags without a space after the colon, overlapping offtimes,
+	/// times with seconds or a colon, and callsigns of any shape.
+	pub fn lenient() -> Self {
+		Self {
+			tolerate_unknown_values: true,
+			skip_untagged_lines: true,
+			ignore_case: true,
+			relaxed_separator: true,
+			allow_overlapping_offtimes: true,
+			lenient_times: true,
+			callsigns: CallsignStrictness::Loose,
+			..Self::default()
+		}
+	}
+
+	fn preserving() -> Self {
+		Self {
+			preserve_source: true,
+			..Self::default()
+		}
+	}
+}
+
+fn is_qso_tag(tag: &str) -> bool {
+	tag == "QSO" || tag == "X-QSO"
+}
+
+/// Tags whose values are free text, and keep their case when parsing with
+/// `ParseOptions::ignore_case`.
+const FREE_TEXT_TAGS: [&str; 11] = [
+	"ADDRESS", "ADDRESS-CITY", "ADDRESS-STATE-PROVINCE", "ADDRESS-POSTALCODE",
+	"ADDRESS-COUNTRY", "CLUB", "CREATED-BY", "EMAIL", "GRID-LOCATOR", "NAME", "SOAPBOX"
+];
+
+/// Uppercase the tag of a line, and its value unless it is free text. Only
+/// ASCII letters are changed, so byte offsets into the line stay the same.
+fn uppercase_line(line: &str) -> String {
+	match line.split_once(':') {
+		Some((tag, value)) => {
+			let tag = tag.to_ascii_uppercase();
+			let value = if FREE_TEXT_TAGS.contains(&tag.trim()) || !TAGS.contains_key(tag.trim()) {
+				value.to_string()
+			} else {
+				value.to_ascii_uppercase()
+			};
+
+			format!("{}:{}", tag, value)
+		},
+		None => line.to_ascii_uppercase()
+	}
+}
+
+fn is_date(token: &str) -> bool {
+	token.len() == 10 && token.char_indices().all(|(idx, c)| match idx {
+		4 | 7 => c == '-',
+		_ => c.is_ascii_digit()
+	})
+}
+
+/// The hours and minutes of a time written as `HHMMSS`, `HH:MM`, or `HH:MM:SS`.
+fn time_to_minutes(token: &str) -> Option<String> {
+	let digits: String = token.chars().filter(|&c| c != ':').collect();
+	let colons = token.len() - digits.len();
+
+	let valid = digits.chars().all(|c| c.is_ascii_digit()) && match (digits.len(), colons) {
+		(6, 0) => true,
+		(4, 1) => token.as_bytes()[2] == b':',
+		(6, 2) => token.as_bytes()[2] == b':' && token.as_bytes()[5] == b':',
+		_ => false
+	};
+
+	valid.then(|| digits[..4].to_string())
+}
+
+/// Rewrite times following a date in QSO and OFFTIME lines to `HHMM`, for
+/// `ParseOptions::lenient_times`. Times are padded with spaces so that the
+/// columns of later fields do not move.
+fn normalize_times(line: &str) -> Cow<'_, str> {
+	let tag = line.split_once(':').map(|(tag, _)| tag.trim());
+
+	if !matches!(tag, Some("QSO" | "X-QSO" | "OFFTIME")) {
+		return Cow::Borrowed(line);
+	}
+
+	let mut normalized = line.to_string();
+	let mut previous = "";
+	let mut rest = line.trim_start();
+
+	while !rest.is_empty() {
+		let offset = line.len() - rest.len();
+		let (token, after) = rest.split_at(rest.find(char::is_whitespace).unwrap_or(rest.len()));
+
+		if is_date(previous) {
+			if let Some(time) = time_to_minutes(token) {
+				normalized.replace_range(offset..offset + token.len(), &format!("{:<width$}", time, width = token.len()));
+			}
+		}
+
+		previous = token;
+		rest = after.trim_start();
+	}
+
+	Cow::Owned(normalized)
+}
+
+/// State kept while parsing the lines of a log.
+struct ParseState<'a> {
+	options: &'a ParseOptions,
+	report: ValidationReport,
+	/// Number of lines read so far.
+	lines: usize,
+	started: bool,
+	ended: bool,
+	seen_tags: HashSet<String>
+}
+
+impl<'a> ParseState<'a> {
+	fn new(options: &'a ParseOptions) -> Self {
+		Self {
+			options,
+			report: ValidationReport::new(),
+			lines: 0,
+			started: false,
+			ended: false,
+			seen_tags: HashSet::new()
+		}
+	}
+
+	/// Check whether `tag` has been seen before, returning false if this value
+	/// should be ignored.
+	fn check_duplicate(&mut self, line_no: usize, tag: &str) -> CabrilloResult<bool> {
+		// every occurrence of an unrecognized tag is kept in `CabrilloLog::other_tags()`
+		if REPEATABLE_TAGS.contains(&tag) || !TAGS.contains_key(tag) || self.seen_tags.insert(tag.to_string()) {
+			return Ok(true);
+		}
+
+		match self.options.duplicate_tags {
+			DuplicateTags::LastWins => Ok(true),
+			DuplicateTags::FirstWins => Ok(false),
+			DuplicateTags::Warn => {
+				self.report.warn(tag, line_no, "Duplicate tag replaces an earlier value".into());
+				Ok(true)
+			},
+			DuplicateTags::Error => Err(
+				CabrilloError::new(tag, line_no, CabrilloErrorKind::StructureError("Duplicate tag".into()))
+			)
+		}
+	}
+
+	/// Check that `tag` is inside the START-OF-LOG and END-OF-LOG envelope. A
+	/// missing START-OF-LOG is only reported on the first tag.
+	fn check_envelope(&mut self, line_no: usize, tag: &str) -> CabrilloResult<()> {
+		if !self.options.require_envelope {
+			return Ok(());
+		}
+
+		let error = |message: &str| {
+			Err(CabrilloError::new(tag, line_no, CabrilloErrorKind::StructureError(message.to_string())))
+		};
+
+		if self.ended {
+			return error("Tag after END-OF-LOG");
+		}
+
+		match (std::mem::replace(&mut self.started, true), tag) {
+			(false, "START-OF-LOG") => Ok(()),
+			(false, _) => error("Log does not begin with START-OF-LOG"),
+			(true, "START-OF-LOG") => error("START-OF-LOG is not the first tag"),
+			(true, _) => Ok(())
+		}
+	}
+
+	/// Warn if `line` is longer than the maximum line length, if one is set.
+	fn check_line_length(&mut self, line_no: usize, tag: &str, line: &str) {
+		let Some(max) = self.options.max_line_length else { return };
+		let length = line.chars().count();
+
+		if length > max {
+			self.report.warn(tag, line_no, format!("Line is {} characters long, more than {}", length, max));
+		}
+	}
+
+	/// Check that the log ended with END-OF-LOG, once all lines have been read.
+	fn finish(&mut self) -> CabrilloResult<()> {
+		if !self.options.require_envelope || self.ended {
+			return Ok(());
+		}
+
+		let error = CabrilloError::new("END-OF-LOG", self.lines,
+			CabrilloErrorKind::StructureError("Missing END-OF-LOG".into()));
+
+		if self.options.skip_bad_lines {
+			self.report.skip(error, "");
+			Ok(())
+		} else {
+			Err(error)
+		}
+	}
+}
+
+/// Check the last offtime of a log against the earlier ones. An offtime which
+/// ends before it begins is reported as a warning, and one which overlaps an
+/// earlier offtime fails unless the options allow it.
+fn check_offtime(offtimes: &mut Vec<Offtime>, line_no: usize, state: &mut ParseState) -> CabrilloResult<()> {
+	let Some((offtime, earlier)) = offtimes.split_last() else { return Ok(()) };
+
+	if let Some(message) = offtime.check_order() {
+		state.report.warn("OFFTIME", line_no, message);
+	}
+
+	if let Some(message) = offtime.check_overlap(earlier) {
+		if !state.options.allow_overlapping_offtimes {
+			offtimes.pop();
+			return Err(CabrilloError::new("OFFTIME", line_no, CabrilloErrorKind::ParseError(message)));
+		}
+
+		state.report.warn("OFFTIME", line_no, message);
+	}
+
+	Ok(())
+}
+
+/// Report QSOs which parse but look wrong: frequencies outside the amateur
+/// bands, and exchanges which do not match the schema of the contest or, if
+/// there is none, whose sent and received halves differ in length.
+fn check_qso(qso: Option<&Qso>, line_no: usize, tag: &str, schema: Option<&dyn ContestSchema>, report: &mut ValidationReport) {
+	let Some(qso) = qso else { return };
+
+	if let Some(message) = qso.check_band() {
+		report.warn(tag, line_no, message);
+	}
+
+	let messages = match schema {
+		Some(schema) => qso.check_exchanges(schema.sent(), schema.received()),
+		None => qso.check_exchange_lengths().into_iter().collect()
+	};
+
+	for message in messages {
+		report.warn(tag, line_no, message);
+	}
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, rename_all = "SCREAMING-KEBAB-CASE"))]
 #[derive(Debug, Default, Clone)]
 pub struct CabrilloLog {
-	version: f32,
+	#[cfg_attr(feature = "serde", serde(rename = "START-OF-LOG"))]
+	version: CabrilloVersion,
 	callsign: Option<String>,
 	contest: Option<String>,
 	category_assisted: Option<bool>,
@@ -966,48 +2293,173 @@ pub struct CabrilloLog {
 	club: Option<String>,
 	created_by: Option<String>,
 	email: Option<String>,
-	grid_locator: Option<String>,
+	grid_locator: Option<GridSquare>,
 	location: Option<String>,
+	arrl_section: Option<Section>,
 	name: Option<String>,
 	address: Option<String>,
-	operators: Vec<String>,
+	operators: Vec<Callsign>,
+	#[cfg_attr(feature = "serde", serde(rename = "OFFTIME"))]
 	offtimes: Vec<Offtime>,
-	soapbox: Option<String>,
-	other_tags: HashMap<String, String>,
+	#[cfg_attr(feature = "serde", serde(with = "serde_impl::joined_lines"))]
+	soapbox: Vec<String>,
+	other_tags: OtherTags,
+	#[cfg_attr(feature = "serde", serde(rename = "QSO"))]
 	entries: Vec<Qso>,
+	#[cfg_attr(feature = "serde", serde(rename = "X-QSO"))]
 	ignored_entries: Vec<Qso>,
-	debug: bool
+	debug: bool,
+	#[cfg_attr(feature = "serde", serde(skip))]
+	source: Option<Vec<SourceLine>>,
+	#[cfg_attr(feature = "serde", serde(skip))]
+	trailing_text: Vec<String>,
+	#[cfg_attr(feature = "serde", serde(skip))]
+	custom_tags: HashMap<String, CustomValue>,
+	#[cfg_attr(feature = "serde", serde(skip))]
+	extensions: Vec<Extension>
 }
 
 impl CabrilloLog {
 	pub fn new() -> Self {
 		Self {
-			version: 3.0,
+			version: CabrilloVersion::V3,
 			..Default::default()
 		}
 	}
 
 	pub fn from_buffer(buf: &[u8]) -> CabrilloResult<Self> {
-		let mut new_log = Self::new();
+		Self::from_buffer_with(buf, &ParseOptions::default())
+	}
 
-		for (line_no, line) in buf.split(|c| c == &b'\n').enumerate() {
-			let line = str::from_utf8(line)
-				.map_err(|err| {
-					CabrilloError::new("", line_no, 
-						CabrilloErrorKind::IoError(
-							format!("{}", err)))
-				})?;
+	/// Parse a log while recording the original lines, so that writing it back
+	/// out reproduces the input except for fields which have been modified.
+	pub fn from_buffer_preserving(buf: &[u8]) -> CabrilloResult<Self> {
+		Self::from_buffer_with(buf, &ParseOptions::preserving())
+	}
 
-			new_log.parse_line(line_no, line)?;
+	/// Parse a log using the given options.
+	pub fn from_buffer_with(buf: &[u8], options: &ParseOptions) -> CabrilloResult<Self> {
+		Self::from_buffer_validated(buf, options).map(|(log, _)| log)
+	}
+
+	/// Parse a log using the given options, returning it along with a report of
+	/// the non-fatal problems which were found.
+	pub fn from_buffer_validated(buf: &[u8], options: &ParseOptions) -> CabrilloResult<(Self, ValidationReport)> {
+		let mut state = ParseState::new(options);
+		let mut log = Self::for_options(options);
+		log.read_buffer(buf, &mut state)?;
+		Ok((log, state.report))
+	}
+
+	/// Parse as much of a log as possible, collecting every error in the file
+	/// instead of stopping at the first. Lines which fail to parse are left out
+	/// of the log. The `skip_bad_lines` option is always enabled.
+	pub fn from_buffer_partial(buf: &[u8], options: &ParseOptions) -> PartialParse {
+		let options = ParseOptions { skip_bad_lines: true, ..options.clone() };
+		let mut state = ParseState::new(&options);
+		let mut log = Self::for_options(&options);
+		let fatal = log.read_buffer(buf, &mut state).err();
+		PartialParse::new(log, state.report, fatal)
+	}
+
+	fn for_options(options: &ParseOptions) -> Self {
+		Self {
+			source: if options.preserve_source { Some(Vec::new()) } else { None },
+			..Self::new()
 		}
+	}
+
+	fn read_buffer(&mut self, buf: &[u8], state: &mut ParseState) -> CabrilloResult<()> {
+		let buf = buf.strip_suffix(b"\n").unwrap_or(buf);
 
-		Ok(new_log)
+		for (line_no, line) in buf.split(|c| c == &b'\n').enumerate() {
+			self.read_line(line_no, line, state)?;
+		}
+
+		state.finish()
+	}
+
+	/// Decode and parse one line of input, without its line feed.
+	fn read_line(&mut self, line_no: usize, line: &[u8], state: &mut ParseState) -> CabrilloResult<()> {
+		state.lines = line_no + 1;
+
+		let line = line.strip_suffix(b"\r").unwrap_or(line);
+		let line = match line_no {
+			0 => line.strip_prefix(UTF8_BOM).unwrap_or(line),
+			_ => line
+		};
+		let line = match state.options.encoding.decode(line) {
+			Ok(line) => line,
+			Err(err) => {
+				let raw = String::from_utf8_lossy(line);
+				let error = CabrilloError::new("", line_no, 
+					CabrilloErrorKind::IoError(
+						format!("{}", err)))
+					.with_raw(&raw);
+
+				if !state.options.skip_bad_lines {
+					return Err(error);
+				}
+
+				state.report.skip(error, &raw);
+				return Ok(());
+			}
+		};
+
+		self.parse_line_recovering(line_no, &line, state)
 	}
 	
+	/// Read and parse the log file at `path`.
+	pub fn from_path<P: AsRef<Path>>(path: P) -> CabrilloResult<Self> {
+		let file = File::open(path)
+			.map_err(|err| {
+				CabrilloError::new("", 0,
+					CabrilloErrorKind::IoError(err.to_string()))
+			})?;
+
+		Self::from_reader(&mut BufReader::new(file))
+	}
+
 	pub fn from_reader<R: BufRead>(reader: &mut R) -> CabrilloResult<Self> {
-		let mut new_log = Self::new();
+		Self::from_reader_with(reader, &ParseOptions::default())
+	}
+
+	/// Like `from_buffer_preserving()`, but reading from a `BufRead`.
+	pub fn from_reader_preserving<R: BufRead>(reader: &mut R) -> CabrilloResult<Self> {
+		Self::from_reader_with(reader, &ParseOptions::preserving())
+	}
+
+	/// Like `from_buffer_with()`, but reading from a `BufRead`.
+	pub fn from_reader_with<R: BufRead>(reader: &mut R, options: &ParseOptions) -> CabrilloResult<Self> {
+		Self::from_reader_validated(reader, options).map(|(log, _)| log)
+	}
+
+	/// Like `from_buffer_validated()`, but reading from a `BufRead`.
+	pub fn from_reader_validated<R: BufRead>(reader: &mut R, options: &ParseOptions) -> CabrilloResult<(Self, ValidationReport)> {
+		let mut state = ParseState::new(options);
+		let mut log = Self::for_options(options);
+		log.read_lines(reader, &mut state, |_, _| Ok(()))?;
+		Ok((log, state.report))
+	}
 
-		for (line_no, line) in reader.lines().enumerate() {
+	/// Like `from_buffer_partial()`, but reading from a `BufRead`. An I/O error
+	/// stops parsing, and is included in the errors.
+	pub fn from_reader_partial<R: BufRead>(reader: &mut R, options: &ParseOptions) -> PartialParse {
+		let options = ParseOptions { skip_bad_lines: true, ..options.clone() };
+		let mut state = ParseState::new(&options);
+		let mut log = Self::for_options(&options);
+		let fatal = log.read_lines(reader, &mut state, |_, _| Ok(())).err();
+		PartialParse::new(log, state.report, fatal)
+	}
+
+	/// Parse each line of `reader`, calling `after_line` with the log and line
+	/// number once the line has been parsed.
+	fn read_lines<R, F>(&mut self, reader: &mut R, state: &mut ParseState, mut after_line: F) -> CabrilloResult<()>
+	where
+		R: BufRead,
+		F: FnMut(&mut Self, usize) -> CabrilloResult<()>
+	{
+		for (line_no, line) in reader.split(b'\n').enumerate() {
 			let line = line
 				.map_err(|err| {
 					CabrilloError::new("", line_no, 
@@ -1017,57 +2469,216 @@ impl CabrilloLog {
 							.unwrap_or_else(|| "Unknown I/O error".into())))
 				})?;
 
-			new_log.parse_line(line_no, &line)?;
+			self.read_line(line_no, &line, state)?;
+			after_line(self, line_no)?;
 		}
 
-		Ok(new_log)
+		state.finish()
+	}
+
+	/// Parse a line, recording it as skipped instead of failing if the options
+	/// allow bad lines to be skipped.
+	fn parse_line_recovering(&mut self, line_no: usize, line: &str, state: &mut ParseState) -> CabrilloResult<()> {
+		match self.parse_line(line_no, line, state).map_err(|error| error.with_raw(line)) {
+			Err(error) if state.options.skip_bad_lines => {
+				state.report.skip(error, line);
+				Ok(())
+			},
+			result => result
+		}
 	}
 
-	fn parse_line(&mut self, line_no: usize, line: &str) -> CabrilloResult<()> {
-		if line.is_empty() {
+	fn parse_line(&mut self, line_no: usize, line: &str, state: &mut ParseState) -> CabrilloResult<()> {
+		if state.ended {
+			match state.options.after_end_of_log {
+				AfterEndOfLog::Parse => (),
+				AfterEndOfLog::Ignore => {
+					self.record_source("", line);
+					return Ok(());
+				},
+				AfterEndOfLog::Collect => {
+					self.record_source("", line);
+					self.trailing_text.push(line.to_string());
+					return Ok(());
+				},
+				AfterEndOfLog::Error if line.trim().is_empty() => (),
+				AfterEndOfLog::Error => {
+					return Err(
+						CabrilloError::new("", line_no,
+							CabrilloErrorKind::StructureError("Content after END-OF-LOG".into()))
+					);
+				}
+			}
+		}
+
+		// trailing whitespace is not significant in any tag
+		let trimmed = line.trim_end();
+
+		if trimmed.is_empty() {
+			self.record_source("", line);
 			return Ok(());
 		}
 
-		match cabrillo_tag(line) {
+		let uppercased;
+		let parsed = if state.options.ignore_case {
+			uppercased = uppercase_line(trimmed);
+			uppercased.as_str()
+		} else {
+			trimmed
+		};
+
+		let normalized;
+		let parsed = if state.options.lenient_times {
+			normalized = normalize_times(parsed);
+			normalized.as_ref()
+		} else {
+			parsed
+		};
+
+		let result = if state.options.relaxed_separator {
+			cabrillo_tag_relaxed(parsed).or_else(|_| cabrillo_tag(parsed))
+		} else {
+			cabrillo_tag(parsed)
+		};
+
+		match result {
 			Ok((_, (tag, value))) => {
-				self.parse_tag(line_no, tag, value)?;
+				self.record_source(tag, line);
+				state.check_envelope(line_no, tag)?;
+				state.check_line_length(line_no, tag, line);
+
+				if !state.check_duplicate(line_no, tag)? {
+					return Ok(());
+				}
+
+				if tag == "END-OF-LOG" {
+					state.ended = true;
+				}
+
+				let column = parsed.len() - value.len();
+				let result = match state.options.tag_handlers.parse(tag, value.trim()) {
+					Some(Ok(custom)) => {
+						self.custom_tags.insert(tag.to_string(), custom);
+						self.keep_other_tag(tag, value);
+						self.record_extension(line_no, tag, value);
+						Ok(())
+					},
+					Some(Err(message)) => Err(
+						CabrilloError::new(tag, line_no, CabrilloErrorKind::ParseError(message))
+							.with_column(column)
+					),
+					None if is_qso_tag(tag) => self.parse_qso(line_no, tag, value, column, line, state.options),
+					None => match CabrilloLog::check_tag_callsigns(tag, value, state.options.callsigns)
+						.and_then(|_| CabrilloLog::check_tag_grid(tag, value, state.options.grid_precision)) {
+						Ok(()) => self.parse_tag(line_no, tag, value, column),
+						Err(message) => Err(
+							CabrilloError::new(tag, line_no, CabrilloErrorKind::ParseError(message))
+								.with_column(column)
+						)
+					}
+				};
+
+				match result {
+					// an address which looks wrong may still be the one the entrant reads
+					Err(_) if state.options.tolerate_unknown_values && tag == "EMAIL" => {
+						state.report.warn(tag, line_no, format!("Invalid e-mail address '{}'", value.trim()));
+						self.email = Some(value.trim().to_string());
+					},
+					Err(_) if state.options.tolerate_unknown_values && !is_qso_tag(tag) => {
+						state.report.warn(tag, line_no, format!("Unrecognized value '{}'", value.trim()));
+						self.keep_other_tag(tag, value);
+					},
+					result => result?
+				}
+
+				match tag {
+					"QSO" => check_qso(self.entries.last(), line_no, tag,
+						state.options.schemas.for_log(self), &mut state.report),
+					"X-QSO" => check_qso(self.ignored_entries.last(), line_no, tag,
+						state.options.schemas.for_log(self), &mut state.report),
+					"OFFTIME" => check_offtime(&mut self.offtimes, line_no, state)?,
+					"START-OF-LOG" => {
+						if let CabrilloVersion::Other(version) = &self.version {
+							state.report.warn(tag, line_no, format!("Unsupported version '{}'", version));
+						}
+					},
+					_ => ()
+				}
+			},
+			Err(_) if state.options.skip_untagged_lines => {
+				state.report.warn("", line_no, "Skipped line without a tag".into());
+				state.check_line_length(line_no, "", line);
+				self.record_source("", line);
 			},
 			Err(error) => {
-				return Err(
-					CabrilloError::new("", line_no, 
-						CabrilloErrorKind::ParseError(error.to_string()))
-				);
+				let column = error_column(parsed, &error);
+				let error = CabrilloError::new("", line_no, 
+					CabrilloErrorKind::ParseError(error.to_string()));
+
+				return Err(match column {
+					Some(column) => error.with_column(column),
+					None => error
+				});
 			}
 		}
 
-		Ok(())
+		Ok(())
+	}
+
+	fn record_source(&mut self, tag: &str, line: &str) {
+		if let Some(ref mut source) = self.source {
+			source.push(SourceLine {
+				tag: tag.to_string(),
+				raw: line.to_string()
+			});
+		}
 	}
 
-	fn parse_tag(&mut self, line_no: usize, tag: &str, value: &str) -> CabrilloResult<()> {
+	/// Parse the value of a tag, which starts at byte `column` of its line.
+	fn parse_tag(&mut self, line_no: usize, tag: &str, value: &str, column: usize) -> CabrilloResult<()> {
  		match TAGS.get(tag) {
  			Some(parser) => {
  				parser(value, self)
- 					.map_err(|error| {
- 						CabrilloError::new(
- 							tag, 
- 							line_no, 
-							CabrilloErrorKind::ParseError(
-								error.to_string()
-							)
-						)
-					})?;
+ 					.map_err(|error| tag_error(tag, line_no, value, column, &error))?;
  			},
  			None => {
-				self.other_tags.insert(tag.to_string(), value.to_string());
+				self.keep_other_tag(tag, value);
+				self.record_extension(line_no, tag, value);
  			}
  		}
 		
 		Ok(())
 	}
 
+	/// Parse a QSO or X-QSO line using the contest schemas of `options`, and
+	/// record where it came from.
+	fn parse_qso(&mut self, line_no: usize, tag: &str, value: &str, column: usize, line: &str, options: &ParseOptions) -> CabrilloResult<()> {
+		let ignored = tag == "X-QSO";
+		cabrillo_log_qso_with(value, self, &options.schemas, ignored)
+			.map_err(|error| tag_error(tag, line_no, value, column, &error))?;
+		self.check_qso_callsigns(ignored, options.callsigns)
+			.map_err(|message| CabrilloError::new(tag, line_no, CabrilloErrorKind::ParseError(message)).with_column(column))?;
+
+		let entries = if ignored { &mut self.ignored_entries } else { &mut self.entries };
+
+		if let Some(qso) = entries.last_mut() {
+			qso.line = Some(line_no);
+			qso.source_text = options.keep_qso_text.then(|| line.to_string());
+		}
+
+		Ok(())
+	}
+
 	/// Version of the Cabrillo format this log uses.
+	pub fn cabrillo_version(&self) -> &CabrilloVersion {
+		&self.version
+	}
+
+	/// Version of the Cabrillo format this log uses, as a number. This is 0.0
+	/// for a version which is not numeric.
+	#[deprecated(note = "use cabrillo_version()")]
 	pub fn version(&self) -> f32 {
-		self.version
+		self.version.as_f32().unwrap_or_default()
 	}
 
 	/// The callsign used during the contest.
@@ -1096,6 +2707,13 @@ impl CabrilloLog {
 		&self.category_operator
 	}
 
+	/// Whether the log was sent in as a checklog, to help check other logs
+	/// rather than to compete. Checklogs are not scored, so their claimed
+	/// score is neither required nor checked.
+	pub fn is_checklog(&self) -> bool {
+		self.category_operator == Some(OperatorCategory::CheckLog)
+	}
+
 	pub fn category_power(&self) -> &Option<PowerCategory> {
 		&self.category_power
 	}
@@ -1143,7 +2761,7 @@ impl CabrilloLog {
 	}
 
 	/// The Maidenhead Grid Square where the station was operating from.
-	pub fn grid_locator(&self) -> &Option<String> {
+	pub fn grid_locator(&self) -> &Option<GridSquare> {
 		&self.grid_locator
 	}
 
@@ -1152,6 +2770,12 @@ impl CabrilloLog {
 		&self.location
 	}
 
+	/// The ARRL or RAC section the station was operating from, required by
+	/// Cabrillo 2.0 and included in many ARRL contest logs.
+	pub fn arrl_section(&self) -> &Option<Section> {
+		&self.arrl_section
+	}
+
 	/// Name of the contact person submitting this log.
 	pub fn name(&self) -> &Option<String> {
 		&self.name
@@ -1162,25 +2786,94 @@ impl CabrilloLog {
 		&self.address
 	}
 
-	/// List of operators callsigns in this log. The host station may be indicated
-	/// by an '@' character in front of their callsign.
-	pub fn operators(&self) -> &Vec<String> {
+	/// Append a line to the mailing address, as an ADDRESS tag does.
+	pub(crate) fn push_address_line(&mut self, line: &str) {
+		let line = line.trim();
+
+		match self.address {
+			Some(ref mut address) => {
+				address.push('\n');
+				address.push_str(line);
+			},
+			None => self.address = Some(line.to_string())
+		}
+	}
+
+	/// List of operators in this log, including the host station if it was
+	/// marked with an '@' in front of its callsign.
+	pub fn operators(&self) -> &Vec<Callsign> {
 		&self.operators
 	}
 
+	/// The operators as written in the OPERATORS tag, with the host station
+	/// marked.
+	pub(crate) fn joined_operators(&self, separator: &str) -> String {
+		self.operators.iter()
+			.map(Callsign::to_string)
+			.collect::<Vec<_>>()
+			.join(separator)
+	}
+
+	/// Callsign of the host station, if the OPERATORS tag names one.
+	pub fn host_station(&self) -> Option<&str> {
+		self.operators.iter()
+			.find(|operator| operator.is_host())
+			.map(Callsign::as_str)
+	}
+
 	/// List of time ranges where breaks were taken.
 	pub fn offtimes(&self) -> &Vec<Offtime> {
 		&self.offtimes
 	}
 
-	/// All of the comments from this log.
-	pub fn soapbox(&self) -> &Option<String> {
+	/// The offtimes in order, with those which overlap or follow on from one
+	/// another combined into a single break. Offtimes which end before they
+	/// begin are left out.
+	pub fn merged_offtimes(&self) -> Vec<Offtime> {
+		let mut offtimes: Vec<Offtime> = self.offtimes.iter()
+			.filter(|offtime| offtime.begin <= offtime.end)
+			.cloned()
+			.collect();
+		offtimes.sort_by_key(|offtime| offtime.begin);
+
+		let mut merged: Vec<Offtime> = Vec::with_capacity(offtimes.len());
+
+		for offtime in offtimes {
+			match merged.last_mut() {
+				Some(last) if offtime.begin <= last.end => last.end = last.end.max(offtime.end),
+				_ => merged.push(offtime)
+			}
+		}
+
+		merged
+	}
+
+	/// Total time off, from `merged_offtimes()`, so that time covered by more
+	/// than one offtime is only counted once.
+	pub fn total_offtime(&self) -> Duration {
+		self.merged_offtimes().iter()
+			.map(Offtime::duration)
+			.fold(Duration::default(), |total, duration| total + duration)
+	}
+
+	/// All of the comments from this log, one entry per SOAPBOX line.
+	pub fn soapbox(&self) -> &Vec<String> {
 		&self.soapbox
 	}
 
-	/// A key-value map of all unrecognized tags in this log. Some contests use custom
-	/// or non-standard tags. Those tags will be found in this map.
-	pub fn other_tags(&self) -> &HashMap<String, String> {
+	/// Append a line of comments, as a SOAPBOX tag does.
+	pub(crate) fn push_soapbox_line(&mut self, line: &str) {
+		self.soapbox.push(line.trim().to_string());
+	}
+
+	/// The comments from this log joined with '\n', or None if there are none.
+	pub fn soapbox_text(&self) -> Option<String> {
+		if self.soapbox.is_empty() { None } else { Some(self.soapbox.join("\n")) }
+	}
+
+	/// All unrecognized tags in this log, in order. Some contests use custom or
+	/// non-standard tags. Those tags will be found here.
+	pub fn other_tags(&self) -> &OtherTags {
 		&self.other_tags
 	}
 
@@ -1198,6 +2891,160 @@ impl CabrilloLog {
 	pub fn debug(&self) -> bool {
 		self.debug
 	}
+
+	/// The original lines of the log, in order, if it was parsed with
+	/// `from_buffer_preserving()`, `from_reader_preserving()`, or with
+	/// `ParseOptions::preserve_source` set.
+	pub fn source_lines(&self) -> Option<&Vec<SourceLine>> {
+		self.source.as_ref()
+	}
+
+	/// Lines found after END-OF-LOG, such as signatures added by mail clients,
+	/// if the log was parsed with `AfterEndOfLog::Collect`.
+	pub fn trailing_text(&self) -> &Vec<String> {
+		&self.trailing_text
+	}
+
+	pub fn set_version(&mut self, version: CabrilloVersion) {
+		self.version = version;
+	}
+
+	pub fn set_callsign(&mut self, callsign: Option<String>) {
+		self.callsign = callsign;
+	}
+
+	pub fn set_contest(&mut self, contest: Option<String>) {
+		self.contest = contest;
+	}
+
+	pub fn set_category_assisted(&mut self, category_assisted: Option<bool>) {
+		self.category_assisted = category_assisted;
+	}
+
+	pub fn set_category_band(&mut self, category_band: Option<Band>) {
+		self.category_band = category_band;
+	}
+
+	pub fn set_category_mode(&mut self, category_mode: Option<Mode>) {
+		self.category_mode = category_mode;
+	}
+
+	pub fn set_category_operator(&mut self, category_operator: Option<OperatorCategory>) {
+		self.category_operator = category_operator;
+	}
+
+	pub fn set_category_power(&mut self, category_power: Option<PowerCategory>) {
+		self.category_power = category_power;
+	}
+
+	pub fn set_category_station(&mut self, category_station: Option<StationCategory>) {
+		self.category_station = category_station;
+	}
+
+	pub fn set_category_time(&mut self, category_time: Option<TimeCategory>) {
+		self.category_time = category_time;
+	}
+
+	pub fn set_category_transmitter(&mut self, category_transmitter: Option<TransmitterCategory>) {
+		self.category_transmitter = category_transmitter;
+	}
+
+	pub fn set_category_overlay(&mut self, category_overlay: Option<OverlayCategory>) {
+		self.category_overlay = category_overlay;
+	}
+
+	pub fn set_certificate(&mut self, certificate: Option<bool>) {
+		self.certificate = certificate;
+	}
+
+	pub fn set_claimed_score(&mut self, claimed_score: Option<u32>) {
+		self.claimed_score = claimed_score;
+	}
+
+	pub fn set_club(&mut self, club: Option<String>) {
+		self.club = club;
+	}
+
+	pub fn set_created_by(&mut self, created_by: Option<String>) {
+		self.created_by = created_by;
+	}
+
+	pub fn set_email(&mut self, email: Option<String>) {
+		self.email = email;
+	}
+
+	pub fn set_grid_locator(&mut self, grid_locator: Option<GridSquare>) {
+		self.grid_locator = grid_locator;
+	}
+
+	pub fn set_location(&mut self, location: Option<String>) {
+		self.location = location;
+	}
+
+	pub fn set_arrl_section(&mut self, section: Option<Section>) {
+		self.arrl_section = section;
+	}
+
+	pub fn set_name(&mut self, name: Option<String>) {
+		self.name = name;
+	}
+
+	/// Set the mailing address. Multiple lines are separated by '\n'.
+	pub fn set_address(&mut self, address: Option<String>) {
+		self.address = address;
+	}
+
+	pub fn set_operators(&mut self, operators: Vec<Callsign>) {
+		self.operators = operators;
+	}
+
+	pub fn set_offtimes(&mut self, offtimes: Vec<Offtime>) {
+		self.offtimes = offtimes;
+	}
+
+	/// Set the comments for this log, one entry per SOAPBOX line.
+	pub fn set_soapbox(&mut self, soapbox: Vec<String>) {
+		self.soapbox = soapbox;
+	}
+
+	pub fn set_debug(&mut self, debug: bool) {
+		self.debug = debug;
+	}
+
+	/// Append a QSO to the end of the log.
+	pub fn add_entry(&mut self, qso: Qso) {
+		self.entries.push(qso);
+	}
+
+	/// Remove the QSO at `index`, returning it if it exists.
+	pub fn remove_entry(&mut self, index: usize) -> Option<Qso> {
+		if index < self.entries.len() {
+			Some(self.entries.remove(index))
+		} else {
+			None
+		}
+	}
+
+	/// Keep only the QSOs for which `predicate` returns true.
+	pub fn retain_entries<F: FnMut(&Qso) -> bool>(&mut self, predicate: F) {
+		self.entries.retain(predicate);
+	}
+
+	/// Set the value of an unrecognized or non-standard tag, replacing any
+	/// earlier values.
+	pub fn set_other_tag(&mut self, tag: &str, value: &str) {
+		self.other_tags.set(tag, value);
+	}
+
+	/// Add another value of an unrecognized or non-standard tag, after any
+	/// earlier ones.
+	pub fn add_other_tag(&mut self, tag: &str, value: &str) {
+		self.other_tags.push(tag, value);
+	}
+
+	pub fn remove_other_tag(&mut self, tag: &str) -> Option<String> {
+		self.other_tags.remove(tag)
+	}
 }
 
 #[cfg(test)]
@@ -1216,11 +3063,12 @@ mod tests {
 			"test_data/cqww_vhf.txt",
 			"test_data/cqwpx.txt",
 			"test_data/cqwpx_rtty.txt",
-			"test_data/ncj_naqp.txt"
+			"test_data/ncj_naqp.txt",
+			"test_data/afs_phone.txt"
 		]
 			.iter()
 			.for_each(|path| {
-				let buf = fs::read(&path).unwrap();
+				let buf = fs::read(path).unwrap();
 				let log = CabrilloLog::from_buffer(&buf);
 
 				if let Err(ref error) = log {
@@ -1238,18 +3086,724 @@ mod tests {
 		let _log = CabrilloLog::from_reader(&mut data_reader).unwrap();
 	}
 
+	#[test]
+	fn crlf_and_trailing_whitespace() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\r\n",
+			"CALLSIGN: W1AW  \r\n",
+			"CATEGORY-POWER: LOW\t\r\n",
+			"   \r\n",
+			"QSO: 14025 CW 2023-01-14 1805 W1AW 599 CT K1AB 599 MA \r\n",
+			"END-OF-LOG:\r\n"
+		);
+
+		let log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+		assert_eq!(log.callsign(), &Some("W1AW".to_string()));
+		assert_eq!(log.category_power(), &Some(PowerCategory::Low));
+		assert_eq!(log.entries()[0].exchange_received(), "599 MA");
+
+		let log = CabrilloLog::from_reader(&mut buf.as_bytes()).unwrap();
+		assert_eq!(log.entries()[0].exchange_received(), "599 MA");
+	}
+
+	#[test]
+	fn byte_order_mark() {
+		let buf = b"\xef\xbb\xbfSTART-OF-LOG: 3.0\r\nCALLSIGN: W1AW\r\nEND-OF-LOG:\r\n";
+
+		let log = CabrilloLog::from_buffer(buf).unwrap();
+		assert_eq!(log.callsign(), &Some("W1AW".to_string()));
+
+		let log = CabrilloLog::from_reader(&mut buf.as_slice()).unwrap();
+		assert_eq!(log.cabrillo_version(), &CabrilloVersion::V3);
+	}
+
+	#[test]
+	fn unknown_version() {
+		let buf = b"START-OF-LOG: 3.1\nCALLSIGN: W1AW\nEND-OF-LOG:\n";
+		let (log, report) = CabrilloLog::from_buffer_validated(buf, &ParseOptions::default()).unwrap();
+		assert_eq!(log.cabrillo_version(), &CabrilloVersion::Other("3.1".to_string()));
+		assert_eq!(log.cabrillo_version().as_f32(), Some(3.1));
+		assert_eq!(report.warnings()[0].tag(), "START-OF-LOG");
+		assert!(log.to_string().starts_with("START-OF-LOG: 3.1\n"));
+
+		#[allow(deprecated)]
+		let version = CabrilloLog::from_buffer(b"START-OF-LOG: 2.0\nEND-OF-LOG:").unwrap().version();
+		assert_eq!(version, 2.0);
+	}
+
+	#[test]
+	fn tab_delimited_qso() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"QSO: 14025\tCW\t2023-01-14\t1805\tW1AW\t599\tCT \tK1AB\t599\t\tMA\n",
+			"END-OF-LOG:"
+		);
+
+		let log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+		let qso = &log.entries()[0];
+		assert_eq!(datetime::format(qso.datetime(), "%Y-%m-%d %H%M"), "2023-01-14 1805");
+		assert_eq!(qso.exchange_sent(), "599 CT");
+		assert_eq!(qso.call_received(), "K1AB");
+		assert_eq!(qso.exchange_received(), "599 MA");
+	}
+
+	#[test]
+	fn variable_length_exchange() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"CONTEST: ARRL-SS-CW\n",
+			"QSO: 21042 CW 1997-11-01 2102 N5KO          1 B 74 STX K9ZO          2 A 69 IL\n",
+			"QSO: 21042 CW 1997-11-01 2103 N5KO          2 B 74 STX W1AW          3 Q 9A1A CT    1\n",
+			"END-OF-LOG:"
+		);
+
+		let log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+		assert_eq!(log.entries()[0].exchange_sent(), "1 B 74 STX");
+		assert_eq!(log.entries()[0].call_received(), "K9ZO");
+		assert_eq!(log.entries()[0].exchange_received(), "2 A 69 IL");
+		// the field count for the contest wins over anything which looks like a call
+		assert_eq!(log.entries()[1].call_received(), "W1AW");
+		assert_eq!(log.entries()[1].exchange_received(), "3 Q 9A1A CT");
+		assert_eq!(log.entries()[1].transmitter_id(), &Some(1));
+
+		let error = CabrilloLog::from_buffer(b"CONTEST: ARRL-SS-SSB\nQSO: 21042 PH 1997-11-01 2102 N5KO 1 B 74 K9ZO 2 A 69").unwrap_err();
+		assert_eq!((error.tag().as_str(), error.line()), ("QSO", 1));
+
+		let (_, qso) = cabrillo_qso(None)("14025 CW 2023-01-14 1805 W1AW 5NN 10A CT K1AB 5NN 3A").unwrap();
+		assert_eq!(qso.exchange_sent(), "5NN 10A CT");
+		assert_eq!(qso.call_received(), "K1AB");
+		assert_eq!(qso.exchange_received(), "5NN 3A");
+
+		let (_, qso) = cabrillo_qso(None)("3799 PH 2000-10-26 0711 AA1ZZZ 59 05 K9QZO 59 04 0").unwrap();
+		assert_eq!(qso.exchange_received(), "59 04");
+		assert_eq!(qso.transmitter_id(), &Some(0));
+
+		assert!(cabrillo_qso(None)("14025 CW 2023-01-14 1805 W1AW 599 K1AB").is_err());
+	}
+
+	#[test]
+	fn new_from_path() {
+		let log = CabrilloLog::from_path("test_data/cqwpx.txt").unwrap();
+		assert_eq!(log.contest(), &Some("CQ-WPX-CW".to_string()));
+
+		let result = CabrilloLog::from_path("test_data/does_not_exist.txt");
+		assert!(matches!(result.unwrap_err().kind(), CabrilloErrorKind::IoError(_)));
+	}
+
 	#[test]
 	fn frequency() {
-		assert_eq!(Frequency::Khz(146520).as_mhz(), Some(146.520));
-		assert_eq!(Frequency::Khz(2400000).as_ghz(), Some(2.4));
+		assert_eq!(Frequency::from_khz(146520).as_mhz(), Some(146.520));
+		assert_eq!(Frequency::from_khz(2400000).as_ghz(), Some(2.4));
+		assert_eq!(Frequency::from_mhz(14.0745), Frequency::Hz(14074500));
+		assert_eq!(Frequency::from_hz(14074500).as_khz(), Some(14074));
+		assert_eq!(Frequency::from_hz(14074500).to_string(), "14074.5 KHz");
+		assert_eq!(Frequency::Band(Band::Band2M).as_hz(), Some(144000000));
 		assert_eq!(Frequency::Light.as_mhz(), None);
 		assert_eq!(Frequency::Light.as_ghz(), None);
 	}
 
 	#[test]
 	fn parse_frequency() {
-		assert_eq!(cabrillo_frequency("144"), Ok(("", Frequency::Khz(144000)))); // 144 MHz input
-		assert_eq!(cabrillo_frequency("14280"), Ok(("", Frequency::Khz(14280)))); // 14280 KHz input
+		assert_eq!(cabrillo_frequency("144"), Ok(("", Frequency::Band(Band::Band2M)))); // 144 MHz input
+		assert_eq!(cabrillo_frequency("14280"), Ok(("", Frequency::from_khz(14280)))); // 14280 KHz input
+		assert_eq!(cabrillo_frequency("1440 CW"), Ok((" CW", Frequency::from_khz(1440))));
+		assert_eq!(cabrillo_frequency("14074.25 DG"), Ok((" DG", Frequency::Hz(14074250))));
+		assert!(cabrillo_frequency("14074.2505").is_err());
+		assert_eq!(Frequency::Hz(14074250).token(), "14074.25");
+		assert_eq!(cabrillo_frequency("1.2G CW"), Ok((" CW", Frequency::Band(Band::Band1_2G))));
+		assert_eq!(cabrillo_frequency("123G"), Ok(("", Frequency::Band(Band::Band123G))));
+		assert_eq!(cabrillo_frequency("122G"), Ok(("", Frequency::Band(Band::Band123G))));
+		assert_eq!(Frequency::Band(Band::Band123G).token(), "122G");
+		assert_eq!(cabrillo_frequency("LIGHT"), Ok(("", Frequency::Light)));
+		assert!(CabrilloLog::from_buffer(b"QSO: 1.5G CW 2023-01-14 1805 W1AW 599 CT K1AB 599 MA").is_err());
+
+		assert_eq!(Band::try_from(Frequency::Band(Band::Band75G)), Ok(Band::Band75G));
+		assert_eq!(Frequency::Band(Band::Band2M).as_mhz(), Some(144.0));
+		assert_eq!(Frequency::Band(Band::Band1_2G).token(), "1.2G");
+	}
+
+	#[test]
+	fn band_edges() {
+		assert_eq!(Band::Band20M.frequency_range(), Some(14000..=14350));
+		assert_eq!(Band::Light.frequency_range(), None);
+		assert_eq!(Band::try_from(Frequency::Hz(137500)), Ok(Band::Band2200M));
+		assert_eq!(Band::try_from(Frequency::from_khz(475)), Ok(Band::Band630M));
+		assert_eq!(cabrillo_band("630M"), Ok(("", Band::Band630M)));
+		assert_eq!(cabrillo_band("2200M"), Ok(("", Band::Band2200M)));
+		assert_eq!(Band::Band2200M.to_string(), "2200M");
+		assert!(Band::Band20M.contains(&Frequency::from_khz(14350)));
+		assert!(!Band::Band20M.contains(&Frequency::Hz(14350001)));
+		assert!(Band::Band2M.contains(&Frequency::Band(Band::Band2M)));
+		assert!(Band::Light.contains(&Frequency::from_khz(400000000)));
+		assert!(Band::All.contains(&Frequency::from_khz(7025)));
+		assert!(Band::try_from(Frequency::from_khz(5000)).is_err());
+	}
+
+	#[test]
+	fn display_tokens() {
+		assert_eq!(Band::VhfFmOnly.to_string(), "VHF-FM-ONLY");
+		assert_eq!(Band::Band1_2G.to_string(), "1.2G");
+		assert_eq!(Mode::Phone.to_string(), "SSB");
+		assert_eq!(OperatorCategory::SingleOp.to_string(), "SINGLE-OP");
+		assert_eq!(StationCategory::RoverUnlimited.to_string(), "ROVER-UNLIMITED");
+		assert_eq!(TimeCategory::Hours12.to_string(), "12-HOURS");
+		assert_eq!(TransmitterCategory::Swl.to_string(), "SWL");
+		assert_eq!(OverlayCategory::TbWires.to_string(), "TB-WIRES");
+	}
+
+	#[test]
+	fn from_str_tokens() {
+		assert_eq!("VHF-FM-ONLY".parse::<Band>(), Ok(Band::VhfFmOnly));
+		assert_eq!("PH".parse::<Mode>(), Ok(Mode::Phone));
+		assert_eq!("SSB".parse::<Mode>(), Ok(Mode::Phone));
+		assert_eq!(StationCategory::try_from("ROVER-LIMITED"), Ok(StationCategory::RoverLimited));
+		assert_eq!(StationCategory::try_from("DISTRIBUTED"), Ok(StationCategory::Distributed));
+		assert_eq!(StationCategory::try_from("EXPLORER"), Ok(StationCategory::Explorer));
+		assert_eq!(StationCategory::Remote.to_string(), "REMOTE");
+		assert_eq!(OverlayCategory::try_from("OVER-50"), Ok(OverlayCategory::Over50));
+		assert_eq!(OverlayCategory::try_from("FIRST-TIMER"), Ok(OverlayCategory::FirstTimer));
+		assert_eq!(OverlayCategory::try_from("WIRES-ONLY"), Ok(OverlayCategory::Other("WIRES-ONLY".to_string())));
+		assert_eq!(OverlayCategory::Other("WIRES-ONLY".to_string()).to_string(), "WIRES-ONLY");
+		assert!("SINGLE-OPERATOR".parse::<OperatorCategory>().is_err());
+		assert!("".parse::<PowerCategory>().is_err());
+
+		[Band::All, Band::Band2M, Band::Band222, Band::Band1_2G, Band::Light]
+			.iter()
+			.for_each(|band| assert_eq!(band.to_string().parse::<Band>(), Ok(*band)));
+	}
+
+	#[test]
+	fn new_qso() {
+		let datetime = datetime::parse("2021-11-27 0000", "%Y-%m-%d %H%M").unwrap();
+		let qso = Qso::new(Frequency::from_khz(14025), Mode::Cw, datetime, "W1AW", "599 05", "K1ABC", "599 05")
+			.with_transmitter_id(1);
+
+		assert_eq!(qso.call_received(), "K1ABC");
+		assert_eq!(qso.exchange_sent(), "599 05");
+		assert_eq!(qso.transmitter_id(), &Some(1));
+		assert_eq!(qso.band(), Some(Band::Band20M));
+		assert_eq!(Qso::new(Frequency::from_khz(12000), Mode::Cw, datetime, "W1AW", "599", "K1ABC", "599").band(), None);
+
+		let (_, parsed) = cabrillo_qso(None)("14025 CW 2021-11-27 0000 W1AW 599 05 K1ABC 599 05").unwrap();
+		assert_eq!(parsed.call_sent(), qso.call_sent());
+		assert_eq!(parsed.exchange_received(), qso.exchange_received());
+	}
+
+	#[test]
+	fn duplicate_qsos() {
+		let log = CabrilloLog::from_path("test_data/cqww.txt").unwrap();
+		let unique: HashSet<&Qso> = log.entries().iter().collect();
+		assert_eq!(unique.len(), log.entries().len());
+
+		let qso = log.entries()[0].clone();
+		assert_eq!(qso, log.entries()[0]);
+		assert!(unique.contains(&qso));
+
+		let datetime = datetime::parse("2000-10-26 0800", "%Y-%m-%d %H%M").unwrap();
+		let dupe = Qso::new(Frequency::from_khz(3501), Mode::Phone, datetime, "AA1ZZZ", "59 05", "k9qzo", "59 04");
+		assert_ne!(dupe, qso);
+		assert_eq!(dupe.dupe_key(), qso.dupe_key());
+		assert_eq!(qso.dupe_key(), (Some(Band::Band80M), Mode::Phone, "K9QZO".to_string()));
+
+		let keys: HashSet<_> = log.entries().iter().map(Qso::dupe_key).collect();
+		assert!(keys.contains(&dupe.dupe_key()));
+	}
+
+	#[test]
+	fn qso_source_line() {
+		let buf = std::fs::read("test_data/cqww.txt").unwrap();
+		let log = CabrilloLog::from_buffer(&buf).unwrap();
+		let qso = &log.entries()[0];
+		assert_eq!(qso.line(), &Some(24));
+		assert_eq!(qso.source_text(), &None);
+
+		let options = ParseOptions { keep_qso_text: true, ..ParseOptions::default() };
+		let log = CabrilloLog::from_buffer_with(&buf, &options).unwrap();
+		let qso = &log.entries()[1];
+		let line = std::str::from_utf8(&buf).unwrap().lines().nth(qso.line().unwrap()).unwrap();
+		assert_eq!(qso.source_text().as_deref(), Some(line));
+		assert!(line.contains("P29AS"));
+
+		assert_eq!(log.entries()[0], CabrilloLog::from_buffer(&buf).unwrap().entries()[0]);
+	}
+
+	#[test]
+	fn digital_submodes() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"QSO: 14074 FT8 2023-06-24 1200 K1AB FN42 W9XYZ EN52\n",
+			"QSO: 14080 DG 2023-06-24 1201 K1AB FN42 N5KO DM13\n",
+			"END-OF-LOG:\n"
+		);
+
+		let log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+		assert_eq!(log.entries()[0].mode(), &Mode::Digital);
+		assert_eq!(log.entries()[0].submode(), &Some(DigitalMode::Ft8));
+		assert_eq!(log.entries()[1].submode(), &None);
+
+		let written = log.to_string();
+		assert!(written.contains("QSO: 14074 FT8 2023-06-24 1200"));
+		assert!(written.contains("QSO: 14080 DG 2023-06-24 1201"));
+
+		let qso = log.entries()[1].clone().with_submode(DigitalMode::Ft4);
+		assert_ne!(&qso, &log.entries()[1]);
+		assert_eq!(DigitalMode::try_from("MSK144"), Ok(DigitalMode::Msk144));
+	}
+
+	#[test]
+	fn host_station() {
+		let log = CabrilloLog::from_buffer(b"START-OF-LOG: 3.0\nOPERATORS: K1AB, W1XYZ @N1MM\nEND-OF-LOG:").unwrap();
+		assert_eq!(log.operators().len(), 3);
+		assert_eq!(log.operators()[0].as_str(), "K1AB");
+		assert!(!log.operators()[0].is_host());
+		assert!(log.operators()[2].is_host());
+		assert_eq!(log.host_station(), Some("N1MM"));
+		assert!(log.to_string().contains("OPERATORS: K1AB W1XYZ @N1MM\n"));
+
+		let log = CabrilloLog::from_path("test_data/cqww.txt").unwrap();
+		assert_eq!(log.host_station(), None);
+	}
+
+	#[test]
+	fn set_header_fields() {
+		let buf = fs::read("test_data/cqww.txt").unwrap();
+		let mut log = CabrilloLog::from_buffer(&buf).unwrap();
+
+		log.set_callsign(Some("AA1ZZ".to_string()));
+		log.set_claimed_score(Some(1000));
+		log.set_grid_locator(None);
+		log.set_other_tag("X-SPONSOR-FIELD", "123");
+
+		let reparsed = CabrilloLog::from_buffer(log.to_string().as_bytes()).unwrap();
+		assert_eq!(reparsed.callsign(), &Some("AA1ZZ".to_string()));
+		assert_eq!(reparsed.claimed_score(), &Some(1000));
+		assert_eq!(reparsed.grid_locator(), &None);
+		assert_eq!(reparsed.other_tags().get("X-SPONSOR-FIELD"), Some(&"123".to_string()));
+		assert_eq!(reparsed.soapbox().len(), 2);
+		assert_eq!(
+			reparsed.soapbox_text().as_deref(),
+			Some("Put your comments here.\nUse multiple lines if needed.")
+		);
+
+		log.set_soapbox(Vec::new());
+		let reparsed = CabrilloLog::from_buffer(log.to_string().as_bytes()).unwrap();
+		assert_eq!(reparsed.soapbox_text(), None);
+	}
+
+	#[test]
+	fn mutate_entries() {
+		let buf = fs::read("test_data/rdxc.txt").unwrap();
+		let mut log = CabrilloLog::from_buffer(&buf).unwrap();
+		let count = log.entries().len();
+
+		let removed = log.remove_entry(0).unwrap();
+		assert_eq!(log.entries().len(), count - 1);
+		assert!(log.remove_entry(count).is_none());
+
+		let call = removed.call_received().clone();
+		log.add_entry(removed);
+		assert_eq!(log.entries().len(), count);
+		assert_eq!(log.entries().last().unwrap().call_received(), &call);
+
+		log.retain_entries(|qso| qso.mode() == &Mode::Cw);
+		assert!(log.entries().iter().all(|qso| qso.mode() == &Mode::Cw));
+	}
+
+	#[test]
+	fn parse_lenient() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"CALLSIGN: W1AW\n",
+			"CATEGORY-BAND: 11M\n",
+			"this line has no tag\n",
+			"QSO: 14025 CW 2023-01-14 1805 W1AW 599 CT K1AB 599 MA\n",
+			"END-OF-LOG:"
+		);
+
+		let result = CabrilloLog::from_buffer(buf.as_bytes());
+		assert_eq!(result.unwrap_err().tag(), "CATEGORY-BAND");
+
+		let mut reader = BufReader::new(buf.as_bytes());
+		let log = CabrilloLog::from_reader_with(&mut reader, &ParseOptions::lenient()).unwrap();
+		assert_eq!(log.category_band(), &None);
+		assert_eq!(log.other_tags().get("CATEGORY-BAND"), Some(&"11M".to_string()));
+		assert_eq!(log.entries().len(), 1);
+
+		let options = ParseOptions {
+			tolerate_unknown_values: true,
+			..ParseOptions::strict()
+		};
+		let result = CabrilloLog::from_buffer_with(buf.as_bytes(), &options);
+		assert_eq!(result.unwrap_err().line(), 3);
+	}
+
+	#[test]
+	fn parse_ignore_case() {
+		let buf = concat!(
+			"start-of-log: 3.0\n",
+			"Callsign: w1aw\n",
+			"Category-Mode: cw\n",
+			"Name: Hiram Percy Maxim\n",
+			"x-custom: Mixed Case\n",
+			"qso: 14025 cw 2023-01-14 1805 w1aw 599 ct k1ab 599 ma\n",
+			"end-of-log:"
+		);
+
+		assert!(CabrilloLog::from_buffer(buf.as_bytes()).is_err());
+
+		let options = ParseOptions {
+			ignore_case: true,
+			..ParseOptions::strict()
+		};
+		let log = CabrilloLog::from_buffer_with(buf.as_bytes(), &options).unwrap();
+		assert_eq!(log.callsign(), &Some("W1AW".to_string()));
+		assert_eq!(log.category_mode(), &Some(Mode::Cw));
+		assert_eq!(log.name(), &Some("Hiram Percy Maxim".to_string()));
+		assert_eq!(log.other_tags().get("X-CUSTOM"), Some(&"Mixed Case".to_string()));
+		assert_eq!(log.entries()[0].call_received(), "K1AB");
+	}
+
+	#[test]
+	fn parse_lowercase_tokens() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"CATEGORY-BAND: 20m\n",
+			"CATEGORY-MODE: mixed\n",
+			"QSO: 14200 ph 2023-01-14 1805 W1AW 59 CT K1AB 59 MA\n",
+			"QSO: 10g dg 2023-01-14 1806 W1AW 59 CT K1CD 59 MA\n",
+			"X-QSO: light cw 2023-01-14 1807 W1AW 599 CT K1EF 599 MA\n",
+			"END-OF-LOG:"
+		);
+
+		assert_eq!(CabrilloLog::from_buffer(buf.as_bytes()).unwrap_err().line(), 1);
+
+		let log = CabrilloLog::from_buffer_with(buf.as_bytes(), &ParseOptions::lenient()).unwrap();
+		assert_eq!(log.category_band(), &Some(Band::Band20M));
+		assert_eq!(log.category_mode(), &Some(Mode::Mixed));
+
+		let entries: Vec<(Frequency, Mode)> = log.entries().iter()
+			.chain(log.ignored_entries())
+			.map(|qso| (*qso.frequency(), *qso.mode()))
+			.collect();
+		assert_eq!(entries, vec![
+			(Frequency::from_khz(14200), Mode::Phone),
+			(Frequency::Band(Band::Band10G), Mode::Digital),
+			(Frequency::Light, Mode::Cw)
+		]);
+	}
+
+	#[test]
+	fn parse_relaxed_separator() {
+		let buf = concat!(
+			"START-OF-LOG:3.0\n",
+			"CALLSIGN:W1AW\n",
+			"CATEGORY-POWER:\tLOW\n",
+			"QSO:  14025 CW 2023-01-14 1805 W1AW 599 CT K1AB 599 MA\n",
+			"END-OF-LOG:"
+		);
+
+		assert_eq!(CabrilloLog::from_buffer(buf.as_bytes()).unwrap_err().line(), 0);
+
+		let options = ParseOptions {
+			relaxed_separator: true,
+			..ParseOptions::strict()
+		};
+		let log = CabrilloLog::from_buffer_with(buf.as_bytes(), &options).unwrap();
+		assert_eq!(log.callsign(), &Some("W1AW".to_string()));
+		assert_eq!(log.category_power(), &Some(PowerCategory::Low));
+		assert_eq!(log.entries().len(), 1);
+	}
+
+	#[test]
+	fn parse_encoding() {
+		let buf = b"START-OF-LOG: 3.0\nNAME: Jos\xe9 Mar\xeda\nEND-OF-LOG:";
+
+		assert!(CabrilloLog::from_buffer(buf).is_err());
+
+		let options = ParseOptions {
+			encoding: Encoding::Latin1,
+			..ParseOptions::strict()
+		};
+		let log = CabrilloLog::from_buffer_with(buf, &options).unwrap();
+		assert_eq!(log.name(), &Some("Jos\u{e9} Mar\u{ed}a".to_string()));
+
+		let options = ParseOptions {
+			encoding: Encoding::Utf8Lossy,
+			..ParseOptions::strict()
+		};
+		let log = CabrilloLog::from_reader_with(&mut buf.as_slice(), &options).unwrap();
+		assert_eq!(log.name(), &Some("Jos\u{fffd} Mar\u{fffd}a".to_string()));
+	}
+
+	#[test]
+	fn parse_envelope() {
+		let options = ParseOptions {
+			require_envelope: true,
+			..ParseOptions::strict()
+		};
+		let parse = |buf: &str| CabrilloLog::from_buffer_with(buf.as_bytes(), &options);
+		let is_structure_error = |error: &CabrilloError| matches!(error.kind(), CabrilloErrorKind::StructureError(_));
+
+		assert!(CabrilloLog::from_buffer(b"CALLSIGN: W1AW").is_ok());
+		assert!(parse("START-OF-LOG: 3.0\nCALLSIGN: W1AW\nEND-OF-LOG:\n").is_ok());
+
+		let error = parse("CALLSIGN: W1AW\nEND-OF-LOG:").unwrap_err();
+		assert!(is_structure_error(&error));
+		assert_eq!((error.tag().as_str(), error.line()), ("CALLSIGN", 0));
+
+		let error = parse("START-OF-LOG: 3.0\nCALLSIGN: W1AW\n").unwrap_err();
+		assert_eq!((error.tag().as_str(), error.line()), ("END-OF-LOG", 2));
+
+		let error = parse("START-OF-LOG: 3.0\nEND-OF-LOG:\nQSO: 14025 CW 2023-01-14 1805 W1AW 599 CT K1AB 599 MA").unwrap_err();
+		assert!(is_structure_error(&error));
+		assert_eq!((error.tag().as_str(), error.line()), ("QSO", 2));
+
+		let partial = CabrilloLog::from_buffer_partial(b"CALLSIGN: W1AW\nSTART-OF-LOG: 3.0", &options);
+		assert_eq!(partial.errors().len(), 3);
+	}
+
+	#[test]
+	fn parse_after_end_of_log() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"CALLSIGN: W1AW\n",
+			"END-OF-LOG:\n",
+			"\n",
+			"-- \n",
+			"Sent from my phone"
+		);
+		let options = |after_end_of_log| ParseOptions {
+			after_end_of_log,
+			..ParseOptions::strict()
+		};
+
+		assert_eq!(CabrilloLog::from_buffer(buf.as_bytes()).unwrap_err().line(), 4);
+
+		let log = CabrilloLog::from_buffer_with(buf.as_bytes(), &options(AfterEndOfLog::Ignore)).unwrap();
+		assert_eq!(log.callsign(), &Some("W1AW".to_string()));
+		assert!(log.trailing_text().is_empty());
+
+		let log = CabrilloLog::from_buffer_with(buf.as_bytes(), &options(AfterEndOfLog::Collect)).unwrap();
+		assert_eq!(log.trailing_text(), &vec!["", "-- ", "Sent from my phone"]);
+
+		let error = CabrilloLog::from_buffer_with(buf.as_bytes(), &options(AfterEndOfLog::Error)).unwrap_err();
+		assert!(matches!(error.kind(), CabrilloErrorKind::StructureError(_)));
+		assert_eq!(error.line(), 4);
+	}
+
+	#[test]
+	fn parse_validated() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"CATEGORY-BAND: 11M\n",
+			"QSO: 27025 CW 2023-01-14 1805 W1AW 599 CT K1AB 599 MA\n",
+			"QSO: 14025 CW 2023-01-14 1806 W1AW 599 CT K1CD 599\n",
+			"END-OF-LOG:"
+		);
+
+		let (log, report) = CabrilloLog::from_buffer_validated(buf.as_bytes(), &ParseOptions::lenient()).unwrap();
+		assert_eq!(log.entries().len(), 2);
+
+		let warnings: Vec<(&str, usize)> = report.warnings().iter()
+			.map(|warning| (warning.tag().as_str(), warning.line()))
+			.collect();
+		assert_eq!(warnings, vec![("CATEGORY-BAND", 1), ("QSO", 2), ("QSO", 3)]);
+
+		let (_, report) = CabrilloLog::from_buffer_validated(b"CALLSIGN: W1AW", &ParseOptions::strict()).unwrap();
+		assert!(report.is_empty());
+	}
+
+	#[test]
+	fn parse_line_length() {
+		let buf = fs::read("test_data/rdxc.txt").unwrap();
+		let (_, report) = CabrilloLog::from_buffer_validated(&buf, &ParseOptions::strict()).unwrap();
+		assert!(report.warnings().iter().all(|warning| !warning.message().starts_with("Line is")));
+
+		let options = ParseOptions {
+			max_line_length: Some(MAX_LINE_LENGTH),
+			..ParseOptions::strict()
+		};
+		let (_, report) = CabrilloLog::from_buffer_validated(&buf, &options).unwrap();
+		let long_lines: Vec<(&str, usize)> = report.warnings().iter()
+			.filter(|warning| warning.message() == "Line is 76 characters long, more than 75")
+			.map(|warning| (warning.tag().as_str(), warning.line()))
+			.collect();
+		assert_eq!(long_lines.len(), 4);
+		assert!(long_lines.iter().all(|(tag, _)| *tag == "QSO"));
+	}
+
+	#[test]
+	fn parse_duplicate_tags() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"CALLSIGN: W1AW\n",
+			"OPERATORS: W1AW\n",
+			"CALLSIGN: K1AB\n",
+			"OPERATORS: K1CD\n",
+			"END-OF-LOG:"
+		);
+		let parse = |duplicate_tags| {
+			let options = ParseOptions {
+				duplicate_tags,
+				..ParseOptions::strict()
+			};
+			CabrilloLog::from_buffer_validated(buf.as_bytes(), &options)
+		};
+
+		let (log, report) = parse(DuplicateTags::LastWins).unwrap();
+		assert_eq!(log.callsign(), &Some("K1AB".to_string()));
+		assert_eq!(log.operators().len(), 2);
+		assert!(report.is_empty());
+
+		let (log, _) = parse(DuplicateTags::FirstWins).unwrap();
+		assert_eq!(log.callsign(), &Some("W1AW".to_string()));
+		assert_eq!(log.operators().len(), 2);
+
+		let (log, report) = parse(DuplicateTags::Warn).unwrap();
+		assert_eq!(log.callsign(), &Some("K1AB".to_string()));
+		let warnings: Vec<(&str, usize)> = report.warnings().iter()
+			.map(|warning| (warning.tag().as_str(), warning.line()))
+			.collect();
+		assert_eq!(warnings, vec![("CALLSIGN", 3)]);
+
+		let error = parse(DuplicateTags::Error).unwrap_err();
+		assert!(matches!(error.kind(), CabrilloErrorKind::StructureError(_)));
+		assert_eq!(error.line(), 3);
+	}
+
+	#[test]
+	fn parse_offtimes() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"OFFTIME: 2023-01-14 1800 2023-01-14 1930\n",
+			"OFFTIME: 2023-01-15 0200 2023-01-15 0100\n",
+			"OFFTIME: 2023-01-14 1930 2023-01-14 2000\n",
+			"OFFTIME: 2023-01-14 1900 2023-01-14 2100\n",
+			"END-OF-LOG:"
+		);
+
+		let error = CabrilloLog::from_buffer(buf.as_bytes()).unwrap_err();
+		assert_eq!((error.tag().as_str(), error.line()), ("OFFTIME", 4));
+
+		let (log, report) = CabrilloLog::from_buffer_validated(buf.as_bytes(), &ParseOptions::lenient()).unwrap();
+		let warnings: Vec<(usize, &str)> = report.warnings().iter()
+			.map(|warning| (warning.line(), warning.message().as_str()))
+			.collect();
+		assert_eq!(warnings, vec![
+			(2, "Offtime ends before it begins"),
+			(4, "Offtime overlaps an earlier offtime")
+		]);
+
+		assert_eq!(log.offtimes()[0].duration(), Duration::minutes(90));
+		assert_eq!(log.offtimes()[1].duration(), Duration::minutes(-60));
+		assert!(!log.offtimes()[0].overlaps(&log.offtimes()[2]));
+		assert_eq!(log.total_offtime(), Duration::minutes(180));
+
+		let merged = log.merged_offtimes();
+		assert_eq!(merged.len(), 1);
+		assert_eq!(datetime::format(merged[0].begin(), "%H%M"), "1800");
+		assert_eq!(datetime::format(merged[0].end(), "%H%M"), "2100");
+		assert_eq!(datetime::timestamp(&merged[0].begin_utc()), 1673719200);
+	}
+
+	#[test]
+	fn parse_lenient_times() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"OFFTIME: 2023-01-14 18:00 2023-01-14 19:30:15\n",
+			"QSO: 14025 CW 2023-01-14 180559 W1AW 599 CT K1AB 599 MA\n",
+			"QSO: 14025 CW 2023-01-14 18:06 W1AW 599 CT K1AB 599 MA\n",
+			"END-OF-LOG:"
+		);
+
+		let error = CabrilloLog::from_buffer(buf.as_bytes()).unwrap_err();
+		assert_eq!(error.line(), 1);
+
+		let options = ParseOptions { lenient_times: true, ..ParseOptions::strict() };
+		let log = CabrilloLog::from_buffer_with(buf.as_bytes(), &options).unwrap();
+		assert_eq!(log.offtimes()[0].duration(), Duration::minutes(90));
+		assert_eq!(datetime::format(log.entries()[0].datetime(), "%H%M%S"), "180500");
+		assert_eq!(datetime::format(log.entries()[1].datetime(), "%H%M"), "1806");
+		assert_eq!(log.entries()[1].call_sent(), "W1AW");
+		assert_eq!(datetime::timestamp(&log.entries()[1].datetime_utc()) % 86400, 18 * 3600 + 6 * 60);
+
+		let error = CabrilloLog::from_buffer_with(b"QSO: 14025 CW 2023-01-14 18:6 W1AW 599 CT K1AB 599 MA", &options).unwrap_err();
+		assert_eq!(error.column(), Some(25));
+
+		for time in ["12:3456:", "12:345:6"] {
+			let line = format!("QSO: 14025 CW 2023-01-14 {} W1AW 599 CT K1AB 599 MA", time);
+			assert!(CabrilloLog::from_buffer_with(line.as_bytes(), &options).is_err());
+		}
+	}
+
+	#[test]
+	fn parse_skip_bad_lines() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"CATEGORY-POWER: LOUD\n",
+			"QSO: 14025 CW 2023-01-14 1805 W1AW 599 CT K1AB 599 CT\n",
+			"QSO: 14025 CW 2023-01-14 W1AW 599 CT K1CD 599 CT\n",
+			"QSO: 14025 CW 2023-01-14 1807 W1AW 599 CT K1EF 599 CT\n",
+			"END-OF-LOG:"
+		);
+
+		assert_eq!(CabrilloLog::from_buffer(buf.as_bytes()).unwrap_err().line(), 1);
+
+		let options = ParseOptions {
+			skip_bad_lines: true,
+			..ParseOptions::default()
+		};
+		let (log, report) = CabrilloLog::from_buffer_validated(buf.as_bytes(), &options).unwrap();
+		assert_eq!(log.entries().len(), 2);
+		assert_eq!(log.category_power(), &None);
+
+		let skipped: Vec<(usize, &str)> = report.skipped_lines().iter()
+			.map(|skipped| (skipped.line(), skipped.raw().as_str()))
+			.collect();
+		assert_eq!(skipped, vec![
+			(1, "CATEGORY-POWER: LOUD"),
+			(3, "QSO: 14025 CW 2023-01-14 W1AW 599 CT K1CD 599 CT")
+		]);
+	}
+
+	#[test]
+	fn parse_partial() {
+		let mut buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"CATEGORY-POWER: LOUD\n",
+			"QSO: 14025 CW 2023-01-14 1805 W1AW 599 CT K1AB 599 CT\n",
+			"QSO: 14025 CW 2023-01-14 W1AW 599 CT K1CD 599 CT\n"
+		).as_bytes().to_vec();
+		buf.extend_from_slice(b"SOAPBOX: \xff\n");
+		buf.extend_from_slice(b"END-OF-LOG:");
+
+		let partial = CabrilloLog::from_buffer_partial(&buf, &ParseOptions::default());
+		assert!(!partial.is_ok());
+		assert_eq!(partial.log().entries().len(), 1);
+
+		let lines: Vec<usize> = partial.errors().iter().map(|error| error.line()).collect();
+		assert_eq!(lines, vec![1, 3, 4]);
+		assert_eq!(CabrilloLog::from_reader_partial(&mut buf.as_slice(), &ParseOptions::default()).errors().len(), 3);
+
+		let partial = CabrilloLog::from_path("test_data/cqww.txt")
+			.map(|log| log.to_string())
+			.map(|log| CabrilloLog::from_buffer_partial(log.as_bytes(), &ParseOptions::default()))
+			.unwrap();
+		assert!(partial.into_result().is_ok());
+	}
+
+	#[test]
+	fn error_location() {
+		let error = CabrilloLog::from_buffer(b"START-OF-LOG: 3.0\nCATEGORY-POWER: LOUD").unwrap_err();
+		assert_eq!((error.line(), error.column()), (1, Some(16)));
+		assert!(error.to_string().ends_with("on line 1, column 16"));
+		assert_eq!(error.raw().as_deref(), Some("CATEGORY-POWER: LOUD"));
+
+		let error = CabrilloLog::from_buffer(b"START-OF-LOG: 3.0\nQSO: 14025 CW 2023-01-14 W1AW 599 CT").unwrap_err();
+		assert_eq!(error.column(), Some(25));
+
+		let error = CabrilloLog::from_buffer(b"START-OF-LOG: 3.0\nNOT A TAG").unwrap_err();
+		assert_eq!(error.column(), Some(0));
 	}
 
 	#[test]
@@ -1258,6 +3812,28 @@ mod tests {
 		assert_eq!(result, Ok(("\n", ("VERSION", "2.0"))));
 	}
 
+	#[test]
+	fn parse_category_v2() {
+		let log = CabrilloLog::from_buffer(b"START-OF-LOG: 2.0\nCATEGORY: SINGLE-OP-ASSISTED 20M LOW CW\nEND-OF-LOG:").unwrap();
+		assert_eq!(log.category_operator(), &Some(OperatorCategory::SingleOp));
+		assert_eq!(log.category_assisted(), &Some(true));
+		assert_eq!(log.category_band(), &Some(Band::Band20M));
+		assert_eq!(log.category_power(), &Some(PowerCategory::Low));
+		assert_eq!(log.category_mode(), &Some(Mode::Cw));
+		assert!(log.other_tags().is_empty());
+
+		let log = CabrilloLog::from_buffer(b"CATEGORY: MULTI-TWO ALL HIGH").unwrap();
+		assert_eq!(log.category_operator(), &Some(OperatorCategory::MultiOp));
+		assert_eq!(log.category_transmitter(), &Some(TransmitterCategory::Two));
+		assert_eq!(log.category_mode(), &None);
+
+		let log = CabrilloLog::from_buffer(b"CATEGORY: ROVER").unwrap();
+		assert_eq!(log.category_operator(), &None);
+		assert_eq!(log.category_station(), &Some(StationCategory::Rover));
+
+		assert!(CabrilloLog::from_buffer(b"CATEGORY: SINGLE OP").is_err());
+	}
+
 	#[test]
 	fn parse_email() {
 		let result = cabrillo_email("name@test.com");
@@ -1268,6 +3844,20 @@ mod tests {
 
 		let result = cabrillo_email("893u4f9834.com");
 		assert!(result.is_err());
+
+		for valid in ["first.last+contest@mail.example.com", "k1ab@my-isp.co.uk", "w1aw@arrl.photography", "o'brien@xn--bcher-kva.xn--p1ai"] {
+			assert_eq!(cabrillo_email(valid), Ok(("", valid)));
+		}
+
+		for invalid in [".name@test.com", "name..x@test.com", "name@-test.com", "name@test-.com", "name@test.c", "name@test.c0m", "a@b@test.com"] {
+			assert!(cabrillo_email(invalid).is_err(), "{}", invalid);
+		}
+
+		assert!(CabrilloLog::from_buffer(b"EMAIL: name@test.com junk").is_err());
+
+		let (log, report) = CabrilloLog::from_buffer_validated(b"EMAIL: name at test.com", &ParseOptions::lenient()).unwrap();
+		assert_eq!(log.email(), &Some("name at test.com".to_string()));
+		assert_eq!(report.warnings()[0].message(), "Invalid e-mail address 'name at test.com'");
 	}
 
 	#[test]
@@ -1285,6 +3875,12 @@ mod tests {
 		assert!(result.is_err());
 
 		let result = cabrillo_grid_locator("FN20id00xx");
+		assert_eq!(result, Ok(("", "FN20id00xx")));
+
+		let result = cabrillo_grid_locator("fn20ID");
+		assert_eq!(result, Ok(("", "fn20ID")));
+
+		let result = cabrillo_grid_locator("FN20id00xx00");
 		assert!(result.is_err());
 	}
 
@@ -1297,7 +3893,7 @@ mod tests {
 			});
 	}
 
-	/*#[test]
+	#[test]
 	fn parse_signal_report() {
 		let rst = cabrillo_signal_report("599");
 		assert_eq!(rst, Ok(("", SignalReport(5, 9, 9))));
@@ -1311,5 +3907,17 @@ mod tests {
 				let rst = cabrillo_signal_report(signal);
 				assert!(rst.is_err());
 			});
-	}*/
+
+		assert_eq!("5NN".parse::<SignalReport>(), Ok(SignalReport(5, 9, 9)));
+		assert!("5999".parse::<SignalReport>().is_err());
+		assert_eq!(SignalReport(5, 9, 9).to_string(), "599");
+		assert_eq!(SignalReport(5, 7, 0).to_string(), "57");
+
+		let (_, qso) = cabrillo_qso(None)("14025 CW 2023-01-14 1805 W1AW 5NN CT K1AB 579 MA").unwrap();
+		assert_eq!(qso.rst_sent(), Some(SignalReport(5, 9, 9)));
+		assert_eq!(qso.rst_received(), Some(SignalReport(5, 7, 9)));
+
+		let (_, qso) = cabrillo_qso(None)("14025 CW 2023-01-14 1805 W1AW 001 CT K1AB 599 MA").unwrap();
+		assert_eq!(qso.rst_sent(), None);
+	}
 }
@@ -0,0 +1,137 @@
+use crate::*;
+
+/// Number of close matches suggested for an unknown location.
+pub(crate) const MAX_SUGGESTIONS: usize = 3;
+
+/// Number of single character insertions, deletions, and substitutions which
+/// turn `a` into `b`, ignoring case.
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+	let a: Vec<char> = a.chars().map(|c| c.to_ascii_uppercase()).collect();
+	let b: Vec<char> = b.chars().map(|c| c.to_ascii_uppercase()).collect();
+	let mut row: Vec<usize> = (0..=b.len()).collect();
+
+	for (i, ca) in a.iter().enumerate() {
+		let mut diagonal = row[0];
+		row[0] = i + 1;
+
+		for (j, cb) in b.iter().enumerate() {
+			let substitution = diagonal + usize::from(ca != cb);
+			diagonal = row[j + 1];
+			row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+		}
+	}
+
+	row[b.len()]
+}
+
+/// Values from `candidates` within a few edits of `value`, closest first.
+pub(crate) fn close_matches<'a, I: IntoIterator<Item = &'a str>>(value: &str, candidates: I) -> Vec<String> {
+	let mut matches: Vec<(usize, &str)> = candidates.into_iter()
+		.map(|candidate| (edit_distance(value, candidate), candidate))
+		.filter(|(distance, candidate)| *distance <= 2 && *distance < candidate.len())
+		.collect();
+
+	matches.sort_by_key(|(distance, _)| *distance);
+	matches.into_iter().take(MAX_SUGGESTIONS).map(|(_, candidate)| candidate.to_string()).collect()
+}
+
+/// The values a sponsor accepts for the LOCATION tag. As a `Rule`, reports a
+/// LOCATION which is not one of them, suggesting close matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LocationList {
+	/// ARRL and RAC sections, or DX for stations outside the US and Canada,
+	/// as in the ARRL contests.
+	Sections,
+	/// CQ zones 1 to 40, as in the CQ WW contests.
+	CqZones,
+	/// Any other list, such as the counties of a state QSO party.
+	Custom(Vec<String>)
+}
+
+impl LocationList {
+	/// A list of the given values, which are matched in any case.
+	pub fn custom<I: IntoIterator<Item = S>, S: Into<String>>(values: I) -> Self {
+		LocationList::Custom(values.into_iter().map(|value| value.into().to_ascii_uppercase()).collect())
+	}
+
+	fn values(&self) -> Vec<String> {
+		match self {
+			LocationList::Sections => Section::all()
+				.map(|section| section.to_string())
+				.chain(std::iter::once("DX".to_string()))
+				.collect(),
+			LocationList::CqZones => (1..=40).map(|zone: u8| zone.to_string()).collect(),
+			LocationList::Custom(values) => values.clone()
+		}
+	}
+
+	/// Whether `location` is in the list.
+	pub fn contains(&self, location: &str) -> bool {
+		let location = location.trim();
+
+		match self {
+			LocationList::CqZones => location.parse::<u8>().is_ok_and(|zone| (1..=40).contains(&zone)),
+			_ => self.values().iter().any(|value| value.eq_ignore_ascii_case(location))
+		}
+	}
+
+	/// Values of the list close to `location`, closest first, for a location
+	/// which is not in the list.
+	pub fn suggest(&self, location: &str) -> Vec<String> {
+		let values = self.values();
+		close_matches(location.trim(), values.iter().map(String::as_str))
+	}
+}
+
+impl Rule for LocationList {
+	fn name(&self) -> &str {
+		"location"
+	}
+
+	fn check(&self, log: &CabrilloLog, report: &mut ValidationReport) {
+		let Some(location) = &log.location else { return };
+
+		if self.contains(location) {
+			return;
+		}
+
+		let mut message = format!("Unknown location '{}'", location);
+		let suggestions = self.suggest(location);
+
+		if !suggestions.is_empty() {
+			message.push_str(&format!(", did you mean {}?", suggestions.join(" or ")));
+		}
+
+		report.add(Finding::new(self.name(), Severity::Warning, "LOCATION", message).with_line(log.tag_line("LOCATION", 0)));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+	use super::edit_distance;
+
+	#[test]
+	fn location_list() {
+		assert_eq!(edit_distance("EMA", "ema"), 0);
+		assert_eq!(edit_distance("WMA", "EMA"), 1);
+		assert_eq!(edit_distance("kitten", "sitting"), 3);
+
+		assert!(LocationList::Sections.contains("wma"));
+		assert!(LocationList::Sections.contains("DX"));
+		assert!(!LocationList::Sections.contains("XYZ"));
+		assert!(LocationList::CqZones.contains("05"));
+		assert!(!LocationList::CqZones.contains("41"));
+		assert_eq!(LocationList::Sections.suggest("ENAX"), vec!["EMA", "ENY", "EPA"]);
+
+		let counties = LocationList::custom(["Middlesex", "Essex", "Suffolk"]);
+		assert!(counties.contains("ESSEX"));
+		assert_eq!(counties.suggest("Sufolk"), vec!["SUFFOLK"]);
+
+		let log = CabrilloLog::from_buffer(b"START-OF-LOG: 3.0\nLOCATION: Esex\nEND-OF-LOG:\n").unwrap();
+		let mut rules = RuleSet::empty();
+		rules.register(counties);
+		let report = log.validate(&rules);
+		assert_eq!(report.findings()[0].message(), "Unknown location 'Esex', did you mean ESSEX?");
+	}
+}
@@ -0,0 +1,182 @@
+use crate::*;
+
+/// What makes a multiplier for `CabrilloLog::multipliers()`, for contests
+/// which only need to count unique values per band and mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MultiplierKey<'a> {
+	/// A field of the received exchange, such as `ExchangeFieldKind::State`.
+	ExchangeField(ExchangeFieldKind),
+	/// The two letter field of the received grid, such as `FN`.
+	GridField,
+	/// The four character square of the received grid, such as `FN31`.
+	GridSquare,
+	/// The WPX prefix of the callsign worked.
+	Prefix,
+	/// The zone of the received exchange.
+	Zone,
+	/// The country of the callsign worked, named by its primary prefix.
+	Country(&'a CountryTable)
+}
+
+impl MultiplierKey<'_> {
+	/// The first field of `kind` in the received exchange of `qso`: from its
+	/// typed exchange if it has one, or else the first token which reads as
+	/// one. Signal reports at the start of an untyped exchange are skipped, so
+	/// that a phone report such as 59 is not read as a zone, but the last token
+	/// is always kept.
+	fn exchange_field(qso: &Qso, kind: ExchangeFieldKind) -> Option<String> {
+		if let Some(exchange) = &qso.typed_recvd {
+			return exchange.get(kind).map(|field| field.to_string());
+		}
+
+		let tokens: Vec<&str> = qso.exch_recvd.split_whitespace().collect();
+		let reports = match kind {
+			ExchangeFieldKind::Rst => 0,
+			_ => tokens[..tokens.len().saturating_sub(1)].iter()
+				.take_while(|token| ExchangeField::parse(ExchangeFieldKind::Rst, token).is_ok())
+				.count()
+		};
+
+		tokens[reports..].iter()
+			.find_map(|token| ExchangeField::parse(kind, token).ok())
+			.map(|field| field.to_string())
+	}
+
+	/// The multiplier `qso` counts toward, if any.
+	pub(crate) fn value(&self, qso: &Qso) -> Option<String> {
+		match self {
+			MultiplierKey::ExchangeField(kind) => Self::exchange_field(qso, *kind),
+			MultiplierKey::GridField => Self::exchange_field(qso, ExchangeFieldKind::Grid)
+				.map(|grid| grid[..2].to_ascii_uppercase()),
+			MultiplierKey::GridSquare => Self::exchange_field(qso, ExchangeFieldKind::Grid)
+				.map(|grid| grid[..4].to_ascii_uppercase()),
+			MultiplierKey::Prefix => Some(qso.call_recvd.wpx_prefix()),
+			MultiplierKey::Zone => Self::exchange_field(qso, ExchangeFieldKind::Zone),
+			MultiplierKey::Country(countries) => countries.lookup(&qso.call_recvd).map(|country| country.prefix().clone())
+		}
+	}
+}
+
+/// Unique multipliers of a log for each band and mode worked, returned by
+/// `CabrilloLog::multipliers()`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Multipliers(Vec<(Option<Band>, Mode, Vec<String>)>);
+
+impl Multipliers {
+	/// Number of multipliers for each band and mode, in the order each was
+	/// first worked.
+	pub fn counts(&self) -> Vec<(Option<Band>, Mode, usize)> {
+		self.0.iter().map(|(band, mode, values)| (*band, *mode, values.len())).collect()
+	}
+
+	/// The multipliers worked on `band` in `mode`, in the order they were
+	/// first worked.
+	pub fn values(&self, band: Option<Band>, mode: Mode) -> &[String] {
+		self.0.iter()
+			.find(|(other_band, other_mode, _)| *other_band == band && *other_mode == mode)
+			.map(|(_, _, values)| values.as_slice())
+			.unwrap_or(&[])
+	}
+
+	pub fn count(&self, band: Option<Band>, mode: Mode) -> usize {
+		self.values(band, mode).len()
+	}
+
+	/// Multipliers counted once per band and mode, added up.
+	pub fn total(&self) -> usize {
+		self.0.iter().map(|(_, _, values)| values.len()).sum()
+	}
+
+	/// Multipliers counted once in the whole log.
+	pub fn distinct(&self) -> usize {
+		self.0.iter().flat_map(|(_, _, values)| values).collect::<HashSet<&String>>().len()
+	}
+}
+
+impl CabrilloLog {
+	/// Count the unique values of `by` in the QSOs of this log, for each band
+	/// and mode. QSOs without a value, such as those whose exchange has no
+	/// field of the kind asked for, are left out.
+	pub fn multipliers(&self, by: MultiplierKey) -> Multipliers {
+		let mut multipliers = Multipliers::default();
+
+		for qso in &self.entries {
+			let Some(value) = by.value(qso) else { continue };
+			let band = qso.band();
+
+			let values = match multipliers.0.iter().position(|(other_band, mode, _)| *other_band == band && *mode == qso.mode) {
+				Some(idx) => &mut multipliers.0[idx].2,
+				None => {
+					multipliers.0.push((band, qso.mode, Vec::new()));
+					&mut multipliers.0.last_mut().unwrap().2
+				}
+			};
+
+			if !values.contains(&value) {
+				values.push(value);
+			}
+		}
+
+		multipliers
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn multipliers() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"QSO: 14025 CW 2023-01-14 0000 W1AW 599 CT K1AB 599 MA\n",
+			"QSO: 14025 CW 2023-01-14 0010 W1AW 599 CT VE3XX 599 ON\n",
+			"QSO: 14025 CW 2023-01-14 0020 W1AW 599 CT K1AC 599 MA\n",
+			"QSO: 7025 CW 2023-01-14 0030 W1AW 599 CT K1AB 599 MA\n",
+			"QSO: 7200 PH 2023-01-14 0040 W1AW 59 CT DL1ABC 59 DX\n",
+			"END-OF-LOG:"
+		);
+
+		let log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+		let states = log.multipliers(MultiplierKey::ExchangeField(ExchangeFieldKind::State));
+		assert_eq!(states.counts(), vec![
+			(Some(Band::Band20M), Mode::Cw, 2),
+			(Some(Band::Band40M), Mode::Cw, 1),
+			(Some(Band::Band40M), Mode::Phone, 1)
+		]);
+		assert_eq!(states.values(Some(Band::Band20M), Mode::Cw), ["MA", "ON"]);
+		assert_eq!((states.total(), states.distinct()), (4, 3));
+
+		let prefixes = log.multipliers(MultiplierKey::Prefix);
+		assert_eq!(prefixes.values(Some(Band::Band20M), Mode::Cw), ["K1", "VE3"]);
+
+		let countries = CountryTable::from_path("test_data/cty.dat").unwrap();
+		assert_eq!(log.multipliers(MultiplierKey::Country(&countries)).distinct(), 3);
+
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"CONTEST: CQ-VHF\n",
+			"QSO: 50 PH 2023-07-15 1800 W1AW FN31 K1AB fn42\n",
+			"QSO: 50 PH 2023-07-15 1810 W1AW FN31 K1AC FN42pr\n",
+			"QSO: 50 PH 2023-07-15 1820 W1AW FN31 K1AD FN31\n",
+			"END-OF-LOG:"
+		);
+
+		let log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+		assert_eq!(log.multipliers(MultiplierKey::GridSquare).total(), 2);
+		assert_eq!(log.multipliers(MultiplierKey::GridField).total(), 1);
+
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"QSO: 14200 PH 2023-11-25 0000 W1AW 59 05 DL1ABC 59 14\n",
+			"QSO: 14200 PH 2023-11-25 0010 W1AW 59 05 K1AB 59 05\n",
+			"QSO: 14025 CW 2023-11-25 0020 W1AW 599 05 JA1XYZ 599 25\n",
+			"END-OF-LOG:"
+		);
+
+		let log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+		let zones = log.multipliers(MultiplierKey::Zone);
+		assert_eq!(zones.values(Some(Band::Band20M), Mode::Phone), ["14", "05"]);
+		assert_eq!(zones.values(Some(Band::Band20M), Mode::Cw), ["25"]);
+	}
+}
@@ -0,0 +1,160 @@
+use crate::*;
+
+/// Trim `text`, leaving out control characters and collapsing runs of
+/// whitespace into one space.
+fn clean(text: &str) -> String {
+	text.split_whitespace()
+		.map(|word| word.chars().filter(|c| !c.is_control()).collect::<String>())
+		.filter(|word| !word.is_empty())
+		.collect::<Vec<String>>()
+		.join(" ")
+}
+
+fn clean_upper(text: &str) -> String {
+	clean(text).to_ascii_uppercase()
+}
+
+fn clean_callsign(call: &Callsign) -> Callsign {
+	let upper = clean_upper(&call.to_string());
+	Callsign::from(upper.as_str())
+}
+
+/// Options for `CabrilloLog::normalize_with()`, for steps besides those of
+/// `CabrilloLog::normalize()`. Each changes what the log claims rather than
+/// how it is written, and is off by default.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct NormalizeOptions {
+	/// Change a single mode CATEGORY-MODE to MIXED if any QSO was made in
+	/// another mode, as most sponsors would when checking the log.
+	pub downgrade_category_mode: bool
+}
+
+impl CabrilloLog {
+	/// Rewrite this log in a canonical form, so that logs from different
+	/// loggers can be compared: callsigns and exchanges in uppercase, text
+	/// trimmed and without control characters, repeated operators, offtimes,
+	/// and other tags removed, and QSOs in order of time. The original source
+	/// lines are dropped, so the log is written out in the standard layout.
+	pub fn normalize(&mut self) {
+		self.normalize_with(&NormalizeOptions::default());
+	}
+
+	/// `normalize()`, followed by the steps selected by `options`.
+	pub fn normalize_with(&mut self, options: &NormalizeOptions) {
+		self.callsign = self.callsign.as_deref().map(clean_upper);
+		self.contest = self.contest.as_deref().map(clean_upper);
+
+		for text in [&mut self.club, &mut self.created_by, &mut self.email, &mut self.location, &mut self.name] {
+			*text = text.as_deref().map(clean).filter(|text| !text.is_empty());
+		}
+
+		self.address = self.address.as_deref()
+			.map(|address| address.lines().map(clean).filter(|line| !line.is_empty()).collect::<Vec<String>>().join("\n"))
+			.filter(|address| !address.is_empty());
+		self.soapbox = self.soapbox.iter().map(|line| clean(line)).collect();
+
+		let mut operators: Vec<Callsign> = Vec::new();
+		for operator in self.operators.iter().map(clean_callsign) {
+			if !operators.contains(&operator) {
+				operators.push(operator);
+			}
+		}
+		self.operators = operators;
+
+		let mut offtimes: Vec<Offtime> = Vec::new();
+		for offtime in self.offtimes.drain(..) {
+			if !offtimes.contains(&offtime) {
+				offtimes.push(offtime);
+			}
+		}
+		self.offtimes = offtimes;
+		self.other_tags.dedup();
+
+		for qso in self.entries.iter_mut().chain(self.ignored_entries.iter_mut()) {
+			qso.call_sent = clean_callsign(&qso.call_sent);
+			qso.call_recvd = clean_callsign(&qso.call_recvd);
+			qso.exch_sent = clean_upper(&qso.exch_sent);
+			qso.exch_recvd = clean_upper(&qso.exch_recvd);
+		}
+
+		self.sort_entries();
+		self.source = None;
+
+		if options.downgrade_category_mode {
+			self.downgrade_category_mode();
+		}
+	}
+
+	/// Put the QSOs, and the X-QSOs, in order of time. QSOs made at the same
+	/// time keep their order.
+	pub fn sort_entries(&mut self) {
+		self.entries.sort_by_key(|qso| qso.datetime);
+		self.ignored_entries.sort_by_key(|qso| qso.datetime);
+	}
+
+	/// Change CATEGORY-MODE to MIXED if any QSO does not fit it, returning
+	/// whether it was changed.
+	pub fn downgrade_category_mode(&mut self) -> bool {
+		let Some(category) = self.category_mode else { return false };
+
+		if self.entries.iter().all(|qso| CategoryConsistency::mode_fits(category, qso)) {
+			return false;
+		}
+
+		self.category_mode = Some(Mode::Mixed);
+		true
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn normalize() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"CALLSIGN: w1aw\n",
+			"NAME:  Hiram  Percy\tMaxim \n",
+			"OPERATORS: k1ab K1AB w1xyz\n",
+			"X-CUSTOM: one\n",
+			"X-CUSTOM: one\n",
+			"X-CUSTOM: two\n",
+			"QSO: 14025 CW 2023-01-14 0010 w1aw 599 ct k1ac 599 ma\n",
+			"QSO: 14025 CW 2023-01-14 0000 W1AW 599   CT K1AB 599 MA\n",
+			"END-OF-LOG:"
+		);
+
+		let options = ParseOptions { ignore_case: true, preserve_source: true, ..ParseOptions::default() };
+		let mut log = CabrilloLog::from_buffer_with(buf.as_bytes(), &options).unwrap();
+		log.normalize();
+
+		assert_eq!(log.callsign(), &Some("W1AW".to_string()));
+		assert_eq!(log.name(), &Some("Hiram Percy Maxim".to_string()));
+		assert_eq!(log.operators(), &vec![Callsign::from("K1AB"), Callsign::from("W1XYZ")]);
+		assert_eq!(log.other_tags().get_all("X-CUSTOM").collect::<Vec<&String>>(), vec!["one", "two"]);
+		assert_eq!(log.entries()[0].exchange_sent(), "599 CT");
+		assert_eq!(log.entries()[1].call_received(), "K1AC");
+		assert_eq!(log.entries()[1].exchange_received(), "599 MA");
+		assert!(log.backwards_runs().is_empty());
+	}
+
+	#[test]
+	fn downgrade_category_mode() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"CATEGORY-MODE: CW\n",
+			"QSO: 14025 CW 2023-01-14 0000 W1AW 599 CT K1AB 599 MA\n",
+			"QSO: 14080 RY 2023-01-14 0010 W1AW 599 CT K1AC 599 MA\n",
+			"END-OF-LOG:"
+		);
+
+		let mut log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+		log.normalize_with(&NormalizeOptions::default());
+		assert_eq!(log.category_mode(), &Some(Mode::Cw));
+
+		log.normalize_with(&NormalizeOptions { downgrade_category_mode: true });
+		assert_eq!(log.category_mode(), &Some(Mode::Mixed));
+		assert!(!log.downgrade_category_mode());
+	}
+}
@@ -0,0 +1,135 @@
+use crate::*;
+
+/// Time on the air inferred from the QSOs of a log, returned by
+/// `CabrilloLog::operating_time()`.
+///
+/// Most contests define a break as a period of at least some minimum length
+/// without any QSOs, so the breaks here run from one QSO to the next wherever
+/// the gap between them reaches the threshold.
+#[derive(Debug, Clone)]
+pub struct OperatingTime {
+	first_qso: Option<DateTime>,
+	last_qso: Option<DateTime>,
+	breaks: Vec<Offtime>,
+	declared: Vec<Offtime>,
+	qsos_during_offtime: usize
+}
+
+impl OperatingTime {
+	pub fn first_qso(&self) -> &Option<DateTime> {
+		&self.first_qso
+	}
+
+	pub fn last_qso(&self) -> &Option<DateTime> {
+		&self.last_qso
+	}
+
+	/// Gaps between QSOs which are at least as long as the threshold, in order.
+	pub fn breaks(&self) -> &Vec<Offtime> {
+		&self.breaks
+	}
+
+	/// Total length of the inferred breaks.
+	pub fn total_break(&self) -> Duration {
+		self.breaks.iter()
+			.map(Offtime::duration)
+			.fold(Duration::default(), |total, duration| total + duration)
+	}
+
+	/// Time from the first to the last QSO, less the inferred breaks.
+	pub fn on_air(&self) -> Duration {
+		match (self.first_qso, self.last_qso) {
+			(Some(first), Some(last)) => last - first - self.total_break(),
+			_ => Duration::default()
+		}
+	}
+
+	/// The OFFTIMEs of the log, merged as by `CabrilloLog::merged_offtimes()`.
+	pub fn declared(&self) -> &Vec<Offtime> {
+		&self.declared
+	}
+
+	/// Total length of the OFFTIMEs of the log.
+	pub fn declared_offtime(&self) -> Duration {
+		self.declared.iter()
+			.map(Offtime::duration)
+			.fold(Duration::default(), |total, duration| total + duration)
+	}
+
+	/// Inferred breaks which no OFFTIME covers any part of.
+	pub fn undeclared_breaks(&self) -> Vec<&Offtime> {
+		self.breaks.iter()
+			.filter(|gap| !self.declared.iter().any(|offtime| offtime.overlaps(gap)))
+			.collect()
+	}
+
+	/// Number of QSOs made during an OFFTIME, which should be none.
+	pub fn qsos_during_offtime(&self) -> usize {
+		self.qsos_during_offtime
+	}
+}
+
+impl CabrilloLog {
+	/// Work out when this log was on the air from the times of its QSOs. A gap
+	/// of at least `gap_threshold` between two QSOs counts as a break. Ignored
+	/// (X-QSO) entries are not counted.
+	pub fn operating_time(&self, gap_threshold: Duration) -> OperatingTime {
+		let mut times: Vec<DateTime> = self.entries.iter().map(|qso| qso.datetime).collect();
+		times.sort();
+
+		let breaks = times.windows(2)
+			.filter(|pair| pair[1] - pair[0] >= gap_threshold)
+			.map(|pair| Offtime::new(pair[0], pair[1]))
+			.collect();
+
+		let declared = self.merged_offtimes();
+		let qsos_during_offtime = times.iter()
+			.filter(|time| declared.iter().any(|offtime| offtime.begin < **time && **time < offtime.end))
+			.count();
+
+		OperatingTime {
+			first_qso: times.first().copied(),
+			last_qso: times.last().copied(),
+			breaks,
+			declared,
+			qsos_during_offtime
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn operating_time() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"OFFTIME: 2023-01-14 0015 2023-01-14 0125\n",
+			"OFFTIME: 2023-01-14 0401 2023-01-14 0410\n",
+			"QSO: 14025 CW 2023-01-14 0000 W1AW 599 CT K1AB 599 MA\n",
+			"QSO: 14025 CW 2023-01-14 0010 W1AW 599 CT K1AC 599 MA\n",
+			"QSO: 14025 CW 2023-01-14 0130 W1AW 599 CT K1AD 599 MA\n",
+			"QSO: 14025 CW 2023-01-14 0140 W1AW 599 CT K1AE 599 MA\n",
+			"QSO: 14025 CW 2023-01-14 0400 W1AW 599 CT K1AF 599 MA\n",
+			"QSO: 14025 CW 2023-01-14 0405 W1AW 599 CT K1AG 599 MA\n",
+			"END-OF-LOG:"
+		);
+
+		let log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+		let time = log.operating_time(Duration::minutes(30));
+		assert_eq!(time.breaks().len(), 2);
+		assert_eq!(time.total_break(), Duration::minutes(80 + 140));
+		assert_eq!(time.on_air(), Duration::minutes(25));
+		assert_eq!(time.declared_offtime(), Duration::minutes(79));
+		assert_eq!(time.qsos_during_offtime(), 1);
+
+		let undeclared = time.undeclared_breaks();
+		assert_eq!(undeclared.len(), 1);
+		assert_eq!(undeclared[0].duration(), Duration::minutes(140));
+
+		let time = CabrilloLog::new().operating_time(Duration::minutes(30));
+		assert!(time.first_qso().is_none());
+		assert_eq!(time.on_air(), Duration::default());
+	}
+}
@@ -0,0 +1,132 @@
+use crate::*;
+
+/// Header tags which this crate does not recognize, with their values, in the
+/// order they appeared. A tag which appears more than once is kept each time,
+/// so that sponsor-specific repeated tags are written back unchanged.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct OtherTags(Vec<(String, String)>);
+
+impl OtherTags {
+	pub fn iter(&self) -> impl Iterator<Item = (&String, &String)> {
+		self.0.iter().map(|(tag, value)| (tag, value))
+	}
+
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	pub fn contains(&self, tag: &str) -> bool {
+		self.0.iter().any(|(other, _)| other == tag)
+	}
+
+	/// The last value of `tag`.
+	pub fn get(&self, tag: &str) -> Option<&String> {
+		self.0.iter()
+			.rev()
+			.find(|(other, _)| other == tag)
+			.map(|(_, value)| value)
+	}
+
+	/// Every value of `tag`, in order.
+	pub fn get_all<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = &'a String> + 'a {
+		self.0.iter()
+			.filter(move |(other, _)| other == tag)
+			.map(|(_, value)| value)
+	}
+
+	/// Each distinct tag, in the order it first appeared.
+	pub fn tags(&self) -> Vec<&String> {
+		let mut tags: Vec<&String> = Vec::new();
+
+		for (tag, _) in &self.0 {
+			if !tags.contains(&tag) {
+				tags.push(tag);
+			}
+		}
+
+		tags
+	}
+
+	/// Add a value for `tag` after any others.
+	pub fn push<T: Into<String>, V: Into<String>>(&mut self, tag: T, value: V) {
+		self.0.push((tag.into(), value.into()));
+	}
+
+	/// Replace every value of `tag` with `value`, keeping the position of the
+	/// first.
+	pub fn set(&mut self, tag: &str, value: &str) {
+		match self.0.iter().position(|(other, _)| other == tag) {
+			Some(first) => {
+				self.0[first].1 = value.to_string();
+
+				let mut idx = 0;
+				self.0.retain(|(other, _)| {
+					idx += 1;
+					idx - 1 <= first || other != tag
+				});
+			},
+			None => self.push(tag, value)
+		}
+	}
+
+	/// Remove values which repeat an earlier value of the same tag.
+	pub(crate) fn dedup(&mut self) {
+		let mut seen: HashSet<(String, String)> = HashSet::new();
+		self.0.retain(|pair| seen.insert(pair.clone()));
+	}
+
+	/// Remove every value of `tag`, returning the last.
+	pub fn remove(&mut self, tag: &str) -> Option<String> {
+		let removed = self.get(tag).cloned();
+		self.0.retain(|(other, _)| other != tag);
+		removed
+	}
+}
+
+impl CabrilloLog {
+	/// Keep the value of a tag which was not parsed. Unrecognized tags keep
+	/// every occurrence, while a standard tag with a value which could not be
+	/// read replaces any earlier one.
+	pub(crate) fn keep_other_tag(&mut self, tag: &str, value: &str) {
+		if TAGS.contains_key(tag) {
+			self.other_tags.set(tag, value);
+		} else {
+			self.other_tags.push(tag, value);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn repeated_other_tags() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"CALLSIGN: W1AW\n",
+			"SPONSOR-CLUB: YCCC\n",
+			"MEMBER: K1AR\n",
+			"MEMBER: N1MM\n",
+			"END-OF-LOG:\n"
+		);
+
+		let options = ParseOptions { duplicate_tags: DuplicateTags::Error, ..ParseOptions::default() };
+		let mut log = CabrilloLog::from_buffer_with(buf.as_bytes(), &options).unwrap();
+		let tags = log.other_tags();
+		assert_eq!(tags.len(), 3);
+		assert_eq!(tags.get("MEMBER"), Some(&"N1MM".to_string()));
+		assert_eq!(tags.get_all("MEMBER").collect::<Vec<_>>(), vec!["K1AR", "N1MM"]);
+		assert_eq!(tags.tags(), vec!["SPONSOR-CLUB", "MEMBER"]);
+		assert!(log.to_string().contains("SPONSOR-CLUB: YCCC\nMEMBER: K1AR\nMEMBER: N1MM\n"));
+
+		log.set_other_tag("MEMBER", "W1XYZ");
+		assert_eq!(log.other_tags().get_all("MEMBER").count(), 1);
+		assert_eq!(log.remove_other_tag("MEMBER"), Some("W1XYZ".to_string()));
+		assert!(!log.other_tags().contains("MEMBER"));
+	}
+}
@@ -0,0 +1,287 @@
+use std::fmt::{self, Display};
+use crate::{CabrilloError, CabrilloLog};
+
+/// A non-fatal problem found while parsing a log. The log is still accepted,
+/// but the entrant may want to correct it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationWarning {
+	tag: String,
+	line: usize,
+	message: String
+}
+
+impl ValidationWarning {
+	pub fn new(tag: &str, line: usize, message: String) -> Self {
+		Self {
+			tag: tag.to_string(),
+			line,
+			message
+		}
+	}
+
+	pub fn tag(&self) -> &String {
+		&self.tag
+	}
+
+	pub fn line(&self) -> usize {
+		self.line
+	}
+
+	pub fn message(&self) -> &String {
+		&self.message
+	}
+}
+
+impl Display for ValidationWarning {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Warning: {} in tag '{}' on line {}", self.message, self.tag, self.line)
+	}
+}
+
+/// How serious a `Finding` is. Errors are problems a sponsor would reject the
+/// log for, while warnings and notices are worth a look before submitting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+	Info,
+	Warning,
+	Error
+}
+
+impl Display for Severity {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Severity::Info    => write!(f, "Info"),
+			Severity::Warning => write!(f, "Warning"),
+			Severity::Error   => write!(f, "Error")
+		}
+	}
+}
+
+/// A problem found by a validation rule in `CabrilloLog::validate()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+	rule: String,
+	severity: Severity,
+	tag: String,
+	line: Option<usize>,
+	message: String
+}
+
+impl Finding {
+	pub fn new(rule: &str, severity: Severity, tag: &str, message: String) -> Self {
+		Self {
+			rule: rule.to_string(),
+			severity,
+			tag: tag.to_string(),
+			line: None,
+			message
+		}
+	}
+
+	pub fn with_line(mut self, line: Option<usize>) -> Self {
+		self.line = line;
+		self
+	}
+
+	/// Name of the rule which reported this finding.
+	pub fn rule(&self) -> &String {
+		&self.rule
+	}
+
+	pub fn severity(&self) -> Severity {
+		self.severity
+	}
+
+	pub fn tag(&self) -> &String {
+		&self.tag
+	}
+
+	/// Line of the log the finding refers to, if it refers to one and the log
+	/// was read with its line numbers.
+	pub fn line(&self) -> Option<usize> {
+		self.line
+	}
+
+	pub fn message(&self) -> &String {
+		&self.message
+	}
+}
+
+impl Display for Finding {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}: {} in tag '{}'", self.severity, self.message, self.tag)?;
+
+		if let Some(line) = self.line {
+			write!(f, " on line {}", line)?;
+		}
+
+		Ok(())
+	}
+}
+
+/// A line which could not be parsed and was left out of the log, when parsing
+/// with `ParseOptions::skip_bad_lines`.
+#[derive(Debug, Clone)]
+pub struct SkippedLine {
+	error: CabrilloError,
+	raw: String
+}
+
+impl SkippedLine {
+	/// The error which caused the line to be skipped.
+	pub fn error(&self) -> &CabrilloError {
+		&self.error
+	}
+
+	pub fn line(&self) -> usize {
+		self.error.line()
+	}
+
+	/// The original text of the line.
+	pub fn raw(&self) -> &String {
+		&self.raw
+	}
+}
+
+/// Warnings collected while parsing a log, returned alongside the log by
+/// `CabrilloLog::from_buffer_validated()`. Fatal problems are still returned
+/// as a `CabrilloError`, unless bad lines are being skipped.
+///
+/// The findings of validation rules are returned in a report of their own by
+/// `CabrilloLog::validate()`.
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+	warnings: Vec<ValidationWarning>,
+	skipped_lines: Vec<SkippedLine>,
+	findings: Vec<Finding>
+}
+
+impl ValidationReport {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn warnings(&self) -> &Vec<ValidationWarning> {
+		&self.warnings
+	}
+
+	/// Lines which could not be parsed and were skipped.
+	pub fn skipped_lines(&self) -> &Vec<SkippedLine> {
+		&self.skipped_lines
+	}
+
+	/// Findings of validation rules, in the order the rules ran.
+	pub fn findings(&self) -> &Vec<Finding> {
+		&self.findings
+	}
+
+	/// Findings of at least the given severity.
+	pub fn findings_at_least(&self, severity: Severity) -> impl Iterator<Item = &Finding> {
+		self.findings.iter().filter(move |finding| finding.severity >= severity)
+	}
+
+	/// Whether any finding is an error.
+	pub fn has_errors(&self) -> bool {
+		self.findings_at_least(Severity::Error).next().is_some()
+	}
+
+	/// Whether no problems were found.
+	pub fn is_empty(&self) -> bool {
+		self.warnings.is_empty() && self.skipped_lines.is_empty() && self.findings.is_empty()
+	}
+
+	/// Add a finding, for use by validation rules.
+	pub fn add(&mut self, finding: Finding) {
+		self.findings.push(finding);
+	}
+
+	pub fn extend<I: IntoIterator<Item = Finding>>(&mut self, findings: I) {
+		self.findings.extend(findings);
+	}
+
+	pub(crate) fn warn(&mut self, tag: &str, line: usize, message: String) {
+		self.warnings.push(ValidationWarning::new(tag, line, message));
+	}
+
+	pub(crate) fn skip(&mut self, error: CabrilloError, raw: &str) {
+		self.skipped_lines.push(SkippedLine {
+			error,
+			raw: raw.to_string()
+		});
+	}
+}
+
+impl Display for ValidationReport {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for skipped in &self.skipped_lines {
+			writeln!(f, "{}", skipped.error)?;
+		}
+
+		for warning in &self.warnings {
+			writeln!(f, "{}", warning)?;
+		}
+
+		for finding in &self.findings {
+			writeln!(f, "{}", finding)?;
+		}
+
+		Ok(())
+	}
+}
+
+/// The result of `CabrilloLog::from_buffer_partial()`: as much of the log as
+/// could be parsed, along with every error found in the file.
+#[derive(Debug, Clone)]
+pub struct PartialParse {
+	log: CabrilloLog,
+	errors: Vec<CabrilloError>,
+	report: ValidationReport
+}
+
+impl PartialParse {
+	pub(crate) fn new(log: CabrilloLog, report: ValidationReport, fatal: Option<CabrilloError>) -> Self {
+		let errors = report.skipped_lines.iter()
+			.map(|skipped| skipped.error.clone())
+			.chain(fatal)
+			.collect();
+
+		Self {
+			log,
+			errors,
+			report
+		}
+	}
+
+	/// The log, without the lines which failed to parse.
+	pub fn log(&self) -> &CabrilloLog {
+		&self.log
+	}
+
+	/// Every error found, in the order of the lines they were found on.
+	pub fn errors(&self) -> &Vec<CabrilloError> {
+		&self.errors
+	}
+
+	/// Warnings and skipped lines found while parsing.
+	pub fn report(&self) -> &ValidationReport {
+		&self.report
+	}
+
+	/// Whether the whole file was parsed without errors.
+	pub fn is_ok(&self) -> bool {
+		self.errors.is_empty()
+	}
+
+	pub fn into_parts(self) -> (CabrilloLog, Vec<CabrilloError>) {
+		(self.log, self.errors)
+	}
+
+	/// The log if there were no errors, or every error otherwise.
+	pub fn into_result(self) -> Result<CabrilloLog, Vec<CabrilloError>> {
+		if self.errors.is_empty() {
+			Ok(self.log)
+		} else {
+			Err(self.errors)
+		}
+	}
+}
@@ -0,0 +1,204 @@
+use std::sync::Arc;
+use crate::*;
+use ExchangeFieldKind::*;
+
+/// The layout of the exchanges of a contest, used to split QSO lines into
+/// their fields and read them as a typed `Exchange`. Implement this to
+/// describe contests which are not built in, and add it to the
+/// `SchemaRegistry` of the `ParseOptions`.
+pub trait ContestSchema: Send + Sync {
+	/// Whether this schema describes the contest named by a CONTEST tag.
+	fn matches(&self, contest: &str) -> bool;
+
+	/// Fields of the sent exchange, in order.
+	fn sent(&self) -> &[ExchangeFieldKind];
+
+	/// Fields of the received exchange, in order.
+	fn received(&self) -> &[ExchangeFieldKind];
+}
+
+/// A `ContestSchema` for a list of contests which share a layout. A contest
+/// matches if its name is in the list, or starts with a name in the list
+/// followed by a dash, so `ARRL-SS` matches `ARRL-SS-CW` and `ARRL-SS-SSB`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExchangeSchema {
+	contests: Vec<String>,
+	sent: Vec<ExchangeFieldKind>,
+	received: Vec<ExchangeFieldKind>
+}
+
+impl ExchangeSchema {
+	pub fn new(contests: &[&str], sent: &[ExchangeFieldKind], received: &[ExchangeFieldKind]) -> Self {
+		Self {
+			contests: contests.iter().map(|contest| contest.to_string()).collect(),
+			sent: sent.to_vec(),
+			received: received.to_vec()
+		}
+	}
+
+	/// A schema where both stations send the same fields.
+	pub fn symmetric(contests: &[&str], exchange: &[ExchangeFieldKind]) -> Self {
+		Self::new(contests, exchange, exchange)
+	}
+}
+
+impl ContestSchema for ExchangeSchema {
+	fn matches(&self, contest: &str) -> bool {
+		self.contests.iter().any(|name| {
+			contest.strip_prefix(name.as_str())
+				.is_some_and(|rest| rest.is_empty() || rest.starts_with('-'))
+		})
+	}
+
+	fn sent(&self) -> &[ExchangeFieldKind] {
+		&self.sent
+	}
+
+	fn received(&self) -> &[ExchangeFieldKind] {
+		&self.received
+	}
+}
+
+/// Contest schemas looked up by the CONTEST tag of a log. Schemas registered
+/// later take precedence, so a built in schema can be replaced.
+#[derive(Clone)]
+pub struct SchemaRegistry(Vec<Arc<dyn ContestSchema>>);
+
+impl SchemaRegistry {
+	/// A registry without any schemas.
+	pub fn empty() -> Self {
+		Self(Vec::new())
+	}
+
+	/// The schemas built into this crate, for CQ-WW, CQ-WPX, ARRL-SS, ARRL-FD,
+	/// NAQP, CQ-VHF, IARU-HF, and RDXC. This is the default.
+	pub fn builtin() -> Self {
+		BUILTIN_SCHEMAS.clone()
+	}
+
+	fn build_builtin() -> Self {
+		let mut registry = Self::empty();
+		registry.register(ExchangeSchema::symmetric(&["CQ-WW-CW", "CQ-WW-SSB"], &[Rst, Zone]));
+		registry.register(ExchangeSchema::symmetric(&["CQ-WW-RTTY"], &[Rst, Zone, Other]));
+		registry.register(ExchangeSchema::symmetric(&["CQ-WPX"], &[Rst, Serial]));
+		registry.register(ExchangeSchema::symmetric(&["ARRL-SS"], &[Serial, Precedence, Check, Section]));
+		registry.register(ExchangeSchema::symmetric(&["ARRL-FD"], &[Other, Section]));
+		registry.register(ExchangeSchema::symmetric(&["NAQP"], &[Name, State]));
+		registry.register(ExchangeSchema::symmetric(&["CQ-VHF"], &[Grid]));
+		registry.register(ExchangeSchema::symmetric(&["IARU-HF"], &[Rst, Other]));
+		registry.register(ExchangeSchema::symmetric(&["RDXC"], &[Rst, Other]));
+		registry
+	}
+
+	pub fn register<S: ContestSchema + 'static>(&mut self, schema: S) {
+		self.0.push(Arc::new(schema));
+	}
+
+	/// The schema for `contest`, if there is one.
+	pub fn find(&self, contest: &str) -> Option<&dyn ContestSchema> {
+		self.0.iter()
+			.rev()
+			.find(|schema| schema.matches(contest))
+			.map(|schema| schema.as_ref())
+	}
+
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+}
+
+impl Default for SchemaRegistry {
+	fn default() -> Self {
+		Self::builtin()
+	}
+}
+
+impl fmt::Debug for SchemaRegistry {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "SchemaRegistry({} schemas)", self.0.len())
+	}
+}
+
+impl PartialEq for SchemaRegistry {
+	fn eq(&self, other: &Self) -> bool {
+		self.0.len() == other.0.len() && self.0.iter()
+			.zip(&other.0)
+			.all(|(schema, other)| Arc::ptr_eq(schema, other))
+	}
+}
+
+lazy_static! {
+	/// Shared by every registry made with `builtin()`, so that they compare equal.
+	pub(crate) static ref BUILTIN_SCHEMAS: SchemaRegistry = SchemaRegistry::build_builtin();
+}
+
+impl SchemaRegistry {
+	/// The schema for the contest of `log`.
+	pub(crate) fn for_log(&self, log: &CabrilloLog) -> Option<&dyn ContestSchema> {
+		log.contest.as_deref().and_then(|contest| self.find(contest))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+	use ExchangeFieldKind::*;
+
+	struct Sprint;
+
+	impl ContestSchema for Sprint {
+		fn matches(&self, contest: &str) -> bool {
+			contest == "NA-SPRINT-CW"
+		}
+
+		fn sent(&self) -> &[ExchangeFieldKind] {
+			&[Serial, Name, State]
+		}
+
+		fn received(&self) -> &[ExchangeFieldKind] {
+			&[Serial, Name, State]
+		}
+	}
+
+	#[test]
+	fn contest_schemas() {
+		let registry = SchemaRegistry::builtin();
+		assert_eq!(registry.find("ARRL-SS-SSB").unwrap().sent().len(), 4);
+		assert_eq!(registry.find("CQ-WW-CW").unwrap().received(), &[Rst, Zone]);
+		assert!(registry.find("CQ-WWX").is_none());
+
+		let log = CabrilloLog::from_path("test_data/cqww.txt").unwrap();
+		let exchange = log.entries()[0].typed_exchange_received().as_ref().unwrap();
+		assert_eq!((exchange.rst(), exchange.zone()), (Some(SignalReport(5, 9, 0)), Some(4)));
+
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"CONTEST: NA-SPRINT-CW\n",
+			"QSO: 14025 CW 2023-09-09 0005 N5KO 1 TREY CA K1AR 12 JOHN NH\n",
+			"QSO: 14025 CW 2023-09-09 0006 N5KO 2 TREY CA K1ZZ 7 IAN\n",
+			"END-OF-LOG:"
+		);
+
+		let log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+		assert!(log.entries()[0].typed_exchange_sent().is_none());
+
+		let mut options = ParseOptions::default();
+		options.schemas.register(Sprint);
+		assert_ne!(options, ParseOptions::default());
+
+		let error = CabrilloLog::from_buffer_with(buf.as_bytes(), &options).unwrap_err();
+		assert_eq!((error.tag().as_str(), error.line()), ("QSO", 3));
+
+		let mut options = ParseOptions { skip_bad_lines: true, ..options };
+		options.schemas.register(ExchangeSchema::symmetric(&["NA-SPRINT"], &[Serial, Name, Zone]));
+		let (log, report) = CabrilloLog::from_buffer_validated(buf.as_bytes(), &options).unwrap();
+		assert_eq!(log.entries().len(), 1);
+		assert!(log.entries()[0].typed_exchange_received().is_none());
+		assert_eq!(report.warnings()[0].tag(), "QSO");
+		assert_eq!(report.warnings()[0].line(), 2);
+	}
+}
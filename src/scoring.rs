@@ -0,0 +1,689 @@
+use crate::*;
+use ExchangeFieldKind::*;
+
+/// Precedences of ARRL Sweepstakes: QRP, low power, high power, unlimited,
+/// multi-op, and school club.
+const SS_PRECEDENCES: [char; 6] = ['Q', 'A', 'B', 'U', 'M', 'S'];
+
+/// Layout of the received exchange of ARRL Sweepstakes.
+const SS_EXCHANGE: [ExchangeFieldKind; 4] = [Serial, Precedence, Check, Section];
+
+/// Points, multipliers, and final score of a log, worked out by a `Score`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ScoreBreakdown {
+	qsos: usize,
+	dupes: usize,
+	invalid: usize,
+	points: u64,
+	multipliers: Vec<String>,
+	score: u64
+}
+
+impl ScoreBreakdown {
+	/// QSOs which counted, leaving out dupes and invalid QSOs.
+	pub fn qsos(&self) -> usize {
+		self.qsos
+	}
+
+	/// QSOs with a station already worked, which score nothing.
+	pub fn dupes(&self) -> usize {
+		self.dupes
+	}
+
+	/// QSOs which do not count under the rules, such as those with a station
+	/// whose country is not known.
+	pub fn invalid(&self) -> usize {
+		self.invalid
+	}
+
+	/// Total QSO points.
+	pub fn points(&self) -> u64 {
+		self.points
+	}
+
+	/// The multipliers worked, in the order they were first worked.
+	pub fn multipliers(&self) -> &Vec<String> {
+		&self.multipliers
+	}
+
+	pub fn score(&self) -> u64 {
+		self.score
+	}
+}
+
+/// The scoring rules of a contest. A log is scored QSO by QSO, in the order
+/// of the log: invalid QSOs and dupes are skipped, and the others add their
+/// points and any multipliers not yet worked. Implementations give the points
+/// and multipliers of a QSO, and may change how the totals make a score.
+pub trait Score: Send + Sync {
+	/// Points for `qso`, which is neither a dupe nor invalid.
+	fn qso_points(&self, qso: &Qso, log: &CabrilloLog) -> u32;
+
+	/// Multipliers `qso` counts toward, each named by a key such as `20M DL`.
+	/// Keys worked more than once count once.
+	fn qso_multipliers(&self, _qso: &Qso, _log: &CabrilloLog) -> Vec<String> {
+		Vec::new()
+	}
+
+	/// Whether `qso` counts at all.
+	fn is_valid(&self, _qso: &Qso, _log: &CabrilloLog) -> bool {
+		true
+	}
+
+	/// QSOs with the same key are dupes of the first of them. By default this
+	/// is `Qso::dupe_key()`.
+	fn dupe_key(&self, qso: &Qso) -> String {
+		let (band, mode, call) = qso.dupe_key();
+		format!("{:?} {} {}", band, mode, call)
+	}
+
+	/// Final score from the totals of the log. By default the points times
+	/// the number of multipliers, or just the points if there are none.
+	fn total(&self, points: u64, multipliers: usize, _log: &CabrilloLog) -> u64 {
+		if multipliers == 0 { points } else { points * multipliers as u64 }
+	}
+
+	/// Score every QSO of `log`. Checklogs are not scored, so their breakdown
+	/// is empty.
+	fn score(&self, log: &CabrilloLog) -> ScoreBreakdown {
+		if log.is_checklog() {
+			return ScoreBreakdown::default();
+		}
+
+		let counted = CountedQsos::new(self, log);
+		let mut breakdown = ScoreBreakdown {
+			qsos: counted.qsos.len(),
+			dupes: counted.dupes,
+			invalid: counted.invalid,
+			..ScoreBreakdown::default()
+		};
+
+		for qso in counted.qsos {
+			breakdown.points += self.qso_points(qso, log) as u64;
+
+			for multiplier in self.qso_multipliers(qso, log) {
+				if !breakdown.multipliers.contains(&multiplier) {
+					breakdown.multipliers.push(multiplier);
+				}
+			}
+		}
+
+		breakdown.score = self.total(breakdown.points, breakdown.multipliers.len(), log);
+		breakdown
+	}
+}
+
+/// The QSOs of a log which count under a `Score`, in order, and how many did
+/// not.
+struct CountedQsos<'a> {
+	qsos: Vec<&'a Qso>,
+	dupes: usize,
+	invalid: usize
+}
+
+impl<'a> CountedQsos<'a> {
+	fn new<S: Score + ?Sized>(rules: &S, log: &'a CabrilloLog) -> Self {
+		let mut counted = Self { qsos: Vec::new(), dupes: 0, invalid: 0 };
+		let mut worked: HashSet<String> = HashSet::new();
+
+		for qso in &log.entries {
+			// an invalid QSO does not stop the station being worked again
+			if !rules.is_valid(qso, log) {
+				counted.invalid += 1;
+			} else if !worked.insert(rules.dupe_key(qso)) {
+				counted.dupes += 1;
+			} else {
+				counted.qsos.push(qso);
+			}
+		}
+
+		counted
+	}
+}
+
+/// A function giving the points of a QSO is a `Score` without multipliers,
+/// for local contests which only add up points.
+impl<F: Fn(&Qso, &CabrilloLog) -> u32 + Send + Sync> Score for F {
+	fn qso_points(&self, qso: &Qso, log: &CabrilloLog) -> u32 {
+		self(qso, log)
+	}
+}
+
+/// A `Score` from a function giving the points of each QSO, with multipliers
+/// counted once per band and mode by a `MultiplierKey`, for small contests
+/// which do not need a type of their own.
+#[derive(Clone)]
+pub struct QsoPoints<'a, F> {
+	points: F,
+	multipliers: Option<MultiplierKey<'a>>
+}
+
+impl<'a, F: Fn(&Qso, &CabrilloLog) -> u32 + Send + Sync> QsoPoints<'a, F> {
+	pub fn new(points: F) -> Self {
+		Self { points, multipliers: None }
+	}
+
+	pub fn with_multipliers(mut self, key: MultiplierKey<'a>) -> Self {
+		self.multipliers = Some(key);
+		self
+	}
+}
+
+impl<F: Fn(&Qso, &CabrilloLog) -> u32 + Send + Sync> Score for QsoPoints<'_, F> {
+	fn qso_points(&self, qso: &Qso, log: &CabrilloLog) -> u32 {
+		(self.points)(qso, log)
+	}
+
+	fn qso_multipliers(&self, qso: &Qso, _log: &CabrilloLog) -> Vec<String> {
+		self.multipliers.and_then(|key| key.value(qso))
+			.map(|value| vec![format!("{} {} {}", band_key(qso), qso.mode, value)])
+			.unwrap_or_default()
+	}
+}
+
+impl<F> fmt::Debug for QsoPoints<'_, F> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("QsoPoints").field("multipliers", &self.multipliers).finish_non_exhaustive()
+	}
+}
+
+/// Name of the band of `qso` for multiplier keys, or its frequency if it is
+/// outside the amateur bands.
+pub(crate) fn band_key(qso: &Qso) -> String {
+	qso.band().map(|band| band.to_string()).unwrap_or_else(|| qso.frequency.to_string())
+}
+
+/// Country of the station which made `log`, from its CALLSIGN or else the
+/// call sent in `qso`.
+fn own_country(countries: &CountryTable, qso: &Qso, log: &CabrilloLog) -> Option<Country> {
+	let call = log.callsign.as_deref().map(Callsign::from).unwrap_or_else(|| qso.call_sent.clone());
+	countries.lookup(&call)
+}
+
+/// The CQ World Wide DX Contest. QSOs with another continent score 3 points,
+/// with another country on the same continent 1 point, or 2 points between
+/// North American stations, and with the same country none. Each CQ zone and
+/// each country counts as a multiplier once per band. Countries are looked up
+/// in a `CountryTable`, and QSOs with stations whose country is not known do
+/// not count.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CqWw {
+	countries: CountryTable
+}
+
+impl CqWw {
+	pub fn new(countries: CountryTable) -> Self {
+		Self { countries }
+	}
+
+	/// CQ zone sent by the station worked, or that of its country if the
+	/// exchange has none.
+	fn zone_received(qso: &Qso, country: &Country) -> u8 {
+		qso.typed_recvd.as_ref()
+			.and_then(Exchange::zone)
+			.or_else(|| qso.exch_recvd.split_whitespace().last().and_then(|zone| zone.parse().ok()))
+			.filter(|zone| (1..=40).contains(zone))
+			.unwrap_or(country.cq_zone())
+	}
+}
+
+impl Score for CqWw {
+	fn qso_points(&self, qso: &Qso, log: &CabrilloLog) -> u32 {
+		let (Some(own), Some(worked)) = (own_country(&self.countries, qso, log), self.countries.lookup(&qso.call_recvd)) else { return 0 };
+
+		match (own.continent(), worked.continent()) {
+			_ if own.prefix() == worked.prefix() => 0,
+			(Continent::NorthAmerica, Continent::NorthAmerica) => 2,
+			(own, worked) if own == worked => 1,
+			_ => 3
+		}
+	}
+
+	fn qso_multipliers(&self, qso: &Qso, _log: &CabrilloLog) -> Vec<String> {
+		let Some(country) = self.countries.lookup(&qso.call_recvd) else { return Vec::new() };
+		let band = band_key(qso);
+
+		vec![
+			format!("{} zone {}", band, Self::zone_received(qso, &country)),
+			format!("{} {}", band, country.prefix())
+		]
+	}
+
+	fn is_valid(&self, qso: &Qso, _log: &CabrilloLog) -> bool {
+		self.countries.lookup(&qso.call_recvd).is_some()
+	}
+
+	/// Stations may be worked once per band, whatever the mode.
+	fn dupe_key(&self, qso: &Qso) -> String {
+		format!("{} {}", band_key(qso), qso.call_recvd.as_str().to_ascii_uppercase())
+	}
+}
+
+/// The CQ WPX Contest. QSOs with another continent score 3 points, with
+/// another country on the same continent 1 point, or 2 points between North
+/// American stations, and with the same country 1 point. Points other than
+/// those within a country are doubled on 40, 80, and 160 meters. Each WPX
+/// prefix, from `Callsign::wpx_prefix()`, counts as a multiplier once.
+/// QSOs with stations whose country is not known do not count.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CqWpx {
+	countries: CountryTable
+}
+
+impl CqWpx {
+	pub fn new(countries: CountryTable) -> Self {
+		Self { countries }
+	}
+}
+
+impl Score for CqWpx {
+	fn qso_points(&self, qso: &Qso, log: &CabrilloLog) -> u32 {
+		let (Some(own), Some(worked)) = (own_country(&self.countries, qso, log), self.countries.lookup(&qso.call_recvd)) else { return 0 };
+		let low_band = matches!(qso.band(), Some(Band::Band160M | Band::Band80M | Band::Band40M));
+
+		let points = match (own.continent(), worked.continent()) {
+			_ if own.prefix() == worked.prefix() => return 1,
+			(Continent::NorthAmerica, Continent::NorthAmerica) => 2,
+			(own, worked) if own == worked => 1,
+			_ => 3
+		};
+
+		if low_band { points * 2 } else { points }
+	}
+
+	fn qso_multipliers(&self, qso: &Qso, _log: &CabrilloLog) -> Vec<String> {
+		vec![qso.call_recvd.wpx_prefix()]
+	}
+
+	fn is_valid(&self, qso: &Qso, _log: &CabrilloLog) -> bool {
+		self.countries.lookup(&qso.call_recvd).is_some()
+	}
+
+	/// Stations may be worked once per band, whatever the mode.
+	fn dupe_key(&self, qso: &Qso) -> String {
+		format!("{} {}", band_key(qso), qso.call_recvd.as_str().to_ascii_uppercase())
+	}
+}
+
+/// ARRL November Sweepstakes. Each station may be worked once, whatever the
+/// band or mode, for 2 points, and each ARRL or RAC section counts as a
+/// multiplier once. QSOs whose received exchange is not a serial number, a
+/// precedence, a two digit check, and a section do not count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sweepstakes;
+
+impl Sweepstakes {
+	/// The received exchange of `qso`, or why it is not a valid one.
+	pub fn check_exchange(qso: &Qso) -> Result<Exchange, String> {
+		let exchange = qso.exchange_received_as(&SS_EXCHANGE).map_err(CabrilloErrorKind::into_message)?;
+
+		match exchange.get(Precedence) {
+			Some(ExchangeField::Precedence(precedence)) if !SS_PRECEDENCES.contains(&precedence.to_ascii_uppercase()) =>
+				Err(format!("'{}' is not a Sweepstakes precedence", precedence)),
+			_ => Ok(exchange)
+		}
+	}
+}
+
+impl Score for Sweepstakes {
+	fn qso_points(&self, _qso: &Qso, _log: &CabrilloLog) -> u32 {
+		2
+	}
+
+	fn qso_multipliers(&self, qso: &Qso, _log: &CabrilloLog) -> Vec<String> {
+		Self::check_exchange(qso).ok()
+			.and_then(|exchange| exchange.section())
+			.map(|section| vec![section.to_string()])
+			.unwrap_or_default()
+	}
+
+	fn is_valid(&self, qso: &Qso, _log: &CabrilloLog) -> bool {
+		Self::check_exchange(qso).is_ok()
+	}
+
+	/// Stations may be worked once in the contest.
+	fn dupe_key(&self, qso: &Qso) -> String {
+		qso.call_recvd.as_str().to_ascii_uppercase()
+	}
+}
+
+/// Lowest frequency of the free VHF station of ARRL Field Day, in kHz.
+const FREE_VHF_MIN_KHZ: u32 = 50_000;
+
+/// The Field Day score of a log, worked out by `FieldDay::breakdown()`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FieldDayScore {
+	cw_qsos: usize,
+	phone_qsos: usize,
+	digital_qsos: usize,
+	gota_qsos: usize,
+	free_vhf_qsos: usize,
+	points: u64,
+	power_multiplier: u32,
+	bonus_points: u32,
+	score: u64
+}
+
+impl FieldDayScore {
+	pub fn cw_qsos(&self) -> usize {
+		self.cw_qsos
+	}
+
+	pub fn phone_qsos(&self) -> usize {
+		self.phone_qsos
+	}
+
+	pub fn digital_qsos(&self) -> usize {
+		self.digital_qsos
+	}
+
+	/// QSOs made by the GOTA station, which are also counted by mode.
+	pub fn gota_qsos(&self) -> usize {
+		self.gota_qsos
+	}
+
+	/// QSOs made by the free VHF station, which are also counted by mode.
+	pub fn free_vhf_qsos(&self) -> usize {
+		self.free_vhf_qsos
+	}
+
+	/// QSO points before the power multiplier.
+	pub fn points(&self) -> u64 {
+		self.points
+	}
+
+	pub fn power_multiplier(&self) -> u32 {
+		self.power_multiplier
+	}
+
+	pub fn bonus_points(&self) -> u32 {
+		self.bonus_points
+	}
+
+	/// QSO points times the power multiplier, plus the bonus points.
+	pub fn score(&self) -> u64 {
+		self.score
+	}
+}
+
+/// ARRL Field Day. Phone QSOs score 1 point and CW and digital QSOs 2, with
+/// each station worked once per band and mode group. The QSO points are
+/// multiplied by a power multiplier, and bonus points claimed by the entrant
+/// are added; there are no other multipliers.
+///
+/// A GOTA station, which signs its own call in the QSOs it makes, may work
+/// stations the main station has worked. QSOs from the transmitter of a free
+/// VHF station only count on 50 MHz and up.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FieldDay {
+	gota_call: Option<String>,
+	free_vhf_transmitter: Option<u8>,
+	power_multiplier: Option<u32>,
+	bonus_points: u32
+}
+
+impl FieldDay {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Count QSOs sent from `call` as those of the GOTA station.
+	pub fn with_gota_call<S: Into<String>>(mut self, call: S) -> Self {
+		self.gota_call = Some(call.into().to_ascii_uppercase());
+		self
+	}
+
+	/// Count QSOs with transmitter ID `transmitter` as those of the free VHF
+	/// station.
+	pub fn with_free_vhf_transmitter(mut self, transmitter: u8) -> Self {
+		self.free_vhf_transmitter = Some(transmitter);
+		self
+	}
+
+	/// Use `multiplier` in place of the one for the CATEGORY-POWER of the log.
+	/// A log cannot say how the station was powered, so this is how the 5
+	/// times multiplier of QRP on batteries or other natural power is given.
+	pub fn with_power_multiplier(mut self, multiplier: u32) -> Self {
+		self.power_multiplier = Some(multiplier);
+		self
+	}
+
+	/// Add bonus points, such as for emergency power or media publicity.
+	pub fn with_bonus_points(mut self, points: u32) -> Self {
+		self.bonus_points = points;
+		self
+	}
+
+	/// The power multiplier for `log`: 1 for high power, and 2 for low power
+	/// or QRP, unless given with `with_power_multiplier()`.
+	pub fn power_multiplier(&self, log: &CabrilloLog) -> u32 {
+		self.power_multiplier.unwrap_or(match log.category_power {
+			Some(PowerCategory::High) => 1,
+			_ => 2
+		})
+	}
+
+	fn is_gota(&self, qso: &Qso) -> bool {
+		self.gota_call.as_ref().is_some_and(|call| qso.call_sent.as_str().eq_ignore_ascii_case(call))
+	}
+
+	fn is_free_vhf(&self, qso: &Qso) -> bool {
+		self.free_vhf_transmitter.is_some() && qso.transmitter_id == self.free_vhf_transmitter
+	}
+
+	/// Score `log`, counting the QSOs of each kind. A checklog scores nothing.
+	pub fn breakdown(&self, log: &CabrilloLog) -> FieldDayScore {
+		if log.is_checklog() {
+			return FieldDayScore::default();
+		}
+
+		let mut score = FieldDayScore {
+			power_multiplier: self.power_multiplier(log),
+			bonus_points: self.bonus_points,
+			..FieldDayScore::default()
+		};
+
+		for qso in CountedQsos::new(self, log).qsos {
+			match qso.mode {
+				Mode::Cw => score.cw_qsos += 1,
+				Mode::Rtty | Mode::Digital => score.digital_qsos += 1,
+				_ => score.phone_qsos += 1
+			}
+
+			score.gota_qsos += usize::from(self.is_gota(qso));
+			score.free_vhf_qsos += usize::from(self.is_free_vhf(qso));
+			score.points += self.qso_points(qso, log) as u64;
+		}
+
+		score.score = score.points * score.power_multiplier as u64 + score.bonus_points as u64;
+		score
+	}
+}
+
+impl Score for FieldDay {
+	fn qso_points(&self, qso: &Qso, _log: &CabrilloLog) -> u32 {
+		match qso.mode {
+			Mode::Cw | Mode::Rtty | Mode::Digital => 2,
+			_ => 1
+		}
+	}
+
+	fn is_valid(&self, qso: &Qso, _log: &CabrilloLog) -> bool {
+		!self.is_free_vhf(qso) || qso.frequency.as_khz().is_none_or(|khz| khz >= FREE_VHF_MIN_KHZ)
+	}
+
+	/// Stations may be worked once per band in each of CW, phone, and
+	/// digital, by each of the main and GOTA stations.
+	fn dupe_key(&self, qso: &Qso) -> String {
+		let mode = match qso.mode {
+			Mode::Cw => "CW",
+			Mode::Rtty | Mode::Digital => "DIG",
+			_ => "PH"
+		};
+
+		format!("{} {} {} {}", self.is_gota(qso), band_key(qso), mode, qso.call_recvd.as_str().to_ascii_uppercase())
+	}
+
+	fn total(&self, points: u64, _multipliers: usize, log: &CabrilloLog) -> u64 {
+		points * self.power_multiplier(log) as u64 + self.bonus_points as u64
+	}
+}
+
+impl CabrilloLog {
+	/// Score this log with `rules`. A checklog is not scored, even by rules
+	/// which replace `Score::score()`, and gets an empty breakdown.
+	pub fn score_with(&self, rules: &dyn Score) -> ScoreBreakdown {
+		if self.is_checklog() {
+			return ScoreBreakdown::default();
+		}
+
+		rules.score(self)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn cq_ww() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"CALLSIGN: W1AW\n",
+			"CONTEST: CQ-WW-CW\n",
+			"QSO: 14025 CW 2023-11-25 0000 W1AW 599 05 K1AB 599 05\n",
+			"QSO: 14025 CW 2023-11-25 0001 W1AW 599 05 VE3XX 599 04\n",
+			"QSO: 14025 CW 2023-11-25 0002 W1AW 599 05 DL1ABC 599 14\n",
+			"QSO: 14025 CW 2023-11-25 0003 W1AW 599 05 JA1XYZ 599 25\n",
+			"QSO: 14025 CW 2023-11-25 0004 W1AW 599 05 PY2AA 599 11\n",
+			"QSO: 14025 CW 2023-11-25 0005 W1AW 599 05 DL1ABC 599 14\n",
+			"QSO: 14025 CW 2023-11-25 0006 W1AW 599 05 ZS1ABC 599 38\n",
+			"QSO: 7025 CW 2023-11-25 0007 W1AW 599 05 DL1ABC 599 14\n",
+			"END-OF-LOG:"
+		);
+
+		let log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+		let scorer = CqWw::new(CountryTable::from_path("test_data/cty.dat").unwrap());
+		let breakdown = log.score_with(&scorer);
+
+		assert_eq!(breakdown.qsos(), 6);
+		assert_eq!(breakdown.dupes(), 1);
+		assert_eq!(breakdown.invalid(), 1);
+		assert_eq!(breakdown.points(), 14);
+		assert_eq!(breakdown.multipliers().len(), 12);
+		assert_eq!(&breakdown.multipliers()[..4], ["20M zone 5", "20M K", "20M zone 4", "20M VE"]);
+		assert_eq!(breakdown.score(), 14 * 12);
+
+		let mut log = log;
+		log.set_category_operator(Some(OperatorCategory::CheckLog));
+		assert_eq!(log.score_with(&scorer), ScoreBreakdown::default());
+		assert_eq!(scorer.score(&log).score(), 0);
+	}
+
+	#[test]
+	fn cq_wpx() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"CALLSIGN: W1AW\n",
+			"CONTEST: CQ-WPX-CW\n",
+			"QSO: 14025 CW 2023-05-27 0000 W1AW 599 1 K1AB 599 10\n",
+			"QSO: 14025 CW 2023-05-27 0001 W1AW 599 2 VE3XX 599 20\n",
+			"QSO: 14025 CW 2023-05-27 0002 W1AW 599 3 DL1ABC 599 30\n",
+			"QSO: 7025 CW 2023-05-27 0003 W1AW 599 4 DL1ABC 599 40\n",
+			"QSO: 7025 CW 2023-05-27 0004 W1AW 599 5 VE3XX 599 50\n",
+			"QSO: 7025 CW 2023-05-27 0005 W1AW 599 6 K1AB 599 60\n",
+			"QSO: 7025 CW 2023-05-27 0006 W1AW 599 7 DL1ABC 599 70\n",
+			"END-OF-LOG:"
+		);
+
+		let log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+		let breakdown = log.score_with(&CqWpx::new(CountryTable::from_path("test_data/cty.dat").unwrap()));
+		assert_eq!((breakdown.qsos(), breakdown.dupes()), (6, 1));
+		assert_eq!(breakdown.points(), 1 + 2 + 3 + 6 + 4 + 1);
+		assert_eq!(breakdown.multipliers(), &vec!["K1", "VE3", "DL1"]);
+		assert_eq!(breakdown.score(), 17 * 3);
+	}
+
+	#[test]
+	fn qso_points() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"QSO: 14025 CW 2023-01-14 0000 W1AW 599 CT K1AB 599 MA\n",
+			"QSO: 14200 PH 2023-01-14 0010 W1AW 59 CT K1AC 59 MA\n",
+			"QSO: 14025 CW 2023-01-14 0020 W1AW 599 CT W1XYZ 599 NH\n",
+			"QSO: 14025 CW 2023-01-14 0030 W1AW 599 CT W1XYZ 599 NH\n",
+			"END-OF-LOG:"
+		);
+
+		let log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+		let in_state = |qso: &Qso, _: &CabrilloLog| if qso.exchange_received().ends_with("MA") { 5 } else { 1 };
+		let breakdown = log.score_with(&in_state);
+		assert_eq!((breakdown.points(), breakdown.dupes(), breakdown.score()), (11, 1, 11));
+
+		let by_mode = |qso: &Qso, _: &CabrilloLog| if *qso.mode() == Mode::Cw { 2 } else { 1 };
+		let rules = QsoPoints::new(by_mode).with_multipliers(MultiplierKey::ExchangeField(ExchangeFieldKind::State));
+		let breakdown = log.score_with(&rules);
+		assert_eq!(breakdown.multipliers(), &vec!["20M CW MA", "20M SSB MA", "20M CW NH"]);
+		assert_eq!(breakdown.score(), 5 * 3);
+	}
+
+	#[test]
+	fn sweepstakes() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"CONTEST: ARRL-SS-CW\n",
+			"QSO: 21042 CW 2023-11-04 2102 N5KO 1 B 74 STX K9ZO 2 A 69 IL\n",
+			"QSO: 21043 CW 2023-11-04 2103 N5KO 2 B 74 STX W1AW 3 Q 36 CT\n",
+			"QSO: 14042 CW 2023-11-04 2104 N5KO 3 B 74 STX K9ZO 2 A 69 IL\n",
+			"QSO: 14043 CW 2023-11-04 2105 N5KO 4 B 74 STX K1AB 9 X 81 EMA\n",
+			"QSO: 14044 CW 2023-11-04 2106 N5KO 5 B 74 STX K2AB 10 U 7 ENY\n",
+			"QSO: 14045 CW 2023-11-04 2107 N5KO 6 B 74 STX K9AB 11 M 88 IL\n",
+			"END-OF-LOG:"
+		);
+
+		let log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+		let breakdown = log.score_with(&Sweepstakes);
+		assert_eq!((breakdown.qsos(), breakdown.dupes(), breakdown.invalid()), (3, 1, 2));
+		assert_eq!(breakdown.multipliers(), &vec!["IL", "CT"]);
+		assert_eq!(breakdown.score(), 6 * 2);
+
+		assert_eq!(Sweepstakes::check_exchange(&log.entries()[3]).unwrap_err(), "'X' is not a Sweepstakes precedence");
+		assert!(Sweepstakes::check_exchange(&log.entries()[4]).is_err());
+	}
+
+	#[test]
+	fn field_day() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"CALLSIGN: W1AW\n",
+			"CONTEST: ARRL-FD\n",
+			"CATEGORY-POWER: LOW\n",
+			"QSO: 14025 CW 2023-06-24 1800 W1AW 3A CT K1AB 1D EMA 0\n",
+			"QSO: 14200 PH 2023-06-24 1801 W1AW 3A CT K1AB 1D EMA 0\n",
+			"QSO: 14210 FM 2023-06-24 1802 W1AW 3A CT K1AB 1D EMA 0\n",
+			"QSO: 14080 RY 2023-06-24 1803 W1AW 3A CT K1AC 2A WMA 0\n",
+			"QSO: 14200 PH 2023-06-24 1804 W1AW/G 3A CT K1AB 1D EMA 0\n",
+			"QSO: 50125 PH 2023-06-24 1805 W1AW 3A CT K1AD 1A NH 2\n",
+			"QSO: 14205 PH 2023-06-24 1806 W1AW 3A CT K1AE 1A VT 2\n",
+			"END-OF-LOG:"
+		);
+
+		let log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+		let rules = FieldDay::new()
+			.with_gota_call("W1AW/G")
+			.with_free_vhf_transmitter(2)
+			.with_bonus_points(100);
+
+		let score = rules.breakdown(&log);
+		assert_eq!((score.cw_qsos(), score.phone_qsos(), score.digital_qsos()), (1, 3, 1));
+		assert_eq!((score.gota_qsos(), score.free_vhf_qsos()), (1, 1));
+		assert_eq!(score.points(), 2 + 1 + 2 + 1 + 1);
+		assert_eq!(score.power_multiplier(), 2);
+		assert_eq!(score.score(), 7 * 2 + 100);
+
+		let breakdown = log.score_with(&rules);
+		assert_eq!((breakdown.dupes(), breakdown.invalid()), (1, 1));
+		assert_eq!(breakdown.score(), score.score());
+		assert_eq!(rules.with_power_multiplier(5).breakdown(&log).score(), 7 * 5 + 100);
+	}
+}
@@ -0,0 +1,53 @@
+use std::io::{self, Write};
+use std::collections::BTreeSet;
+use crate::*;
+
+/// Collect the unique callsigns received in the QSOs of `logs`, uppercased and
+/// sorted. Ignored (X-QSO) entries are skipped, since they often hold busted calls.
+pub fn scp_callsigns<'a, I: IntoIterator<Item = &'a CabrilloLog>>(logs: I) -> BTreeSet<String> {
+	logs.into_iter()
+		.flat_map(|log| log.entries.iter())
+		.map(|qso| qso.call_recvd.as_str().trim().to_ascii_uppercase())
+		.filter(|call| !call.is_empty())
+		.collect()
+}
+
+/// Write a Super Check Partial (MASTER.SCP style) file listing the unique
+/// callsigns received in `logs`, one per line, after a comment line.
+pub fn write_scp<'a, W: Write, I: IntoIterator<Item = &'a CabrilloLog>>(writer: &mut W, logs: I) -> io::Result<()> {
+	let callsigns = scp_callsigns(logs);
+
+	writeln!(writer, "# Super Check Partial file with {} callsigns", callsigns.len())?;
+
+	for call in callsigns {
+		writeln!(writer, "{}", call)?;
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn scp_from_logs() {
+		let cqww = CabrilloLog::from_path("test_data/cqww.txt").unwrap();
+		let vhf = CabrilloLog::from_path("test_data/cqww_vhf.txt").unwrap();
+
+		let mut output = Vec::new();
+		write_scp(&mut output, [&cqww, &vhf, &cqww]).unwrap();
+		let output = String::from_utf8(output).unwrap();
+		let mut lines = output.lines();
+
+		assert!(lines.next().unwrap().starts_with('#'));
+		let calls: Vec<&str> = lines.collect();
+		let mut sorted = calls.clone();
+		sorted.sort();
+		sorted.dedup();
+
+		assert_eq!(calls, sorted);
+		assert!(calls.contains(&"K9QZO"));
+		assert!(calls.contains(&"W2AJM"));
+	}
+}
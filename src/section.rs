@@ -0,0 +1,135 @@
+use nom::{IResult, combinator::map_opt, character::complete::alpha1};
+use crate::*;
+
+/// ARRL and RAC sections, as used by the ARRL-SECTION tag and by the exchange
+/// of many North American contests.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Section {
+	// call area 1
+	Ct, Ema, Me, Nh, Ri, Vt, Wma,
+	// call area 2
+	Eny, Nli, Nnj, Nny, Snj, Wny,
+	// call area 3
+	De, Epa, Mdc, Wpa,
+	// call area 4
+	Al, Ga, Ky, Nc, Nfl, Pr, Sc, Sfl, Tn, Va, Vi, Wcf,
+	// call area 5
+	Ar, La, Ms, Nm, Ntx, Ok, Stx, Wtx,
+	// call area 6
+	Eb, Lax, Org, Pac, Sb, Scv, Sdg, Sf, Sjv, Sv,
+	// call area 7
+	Ak, Az, Ewa, Id, Mt, Nv, Or, Ut, Wwa, Wy,
+	// call area 8
+	Mi, Oh, Wv,
+	// call area 9
+	Il, In, Wi,
+	// call area 0
+	Co, Ia, Ks, Mn, Mo, Nd, Ne, Sd,
+	// Canada
+	Ab, Bc, Gh, Mb, Nb, Nl, Ns, One, Onn, Ons, Pe, Qc, Sk, Ter
+}
+
+const SECTIONS: &[(Section, &str)] = &[
+	(Section::Ct, "CT"), (Section::Ema, "EMA"), (Section::Me, "ME"), (Section::Nh, "NH"),
+	(Section::Ri, "RI"), (Section::Vt, "VT"), (Section::Wma, "WMA"),
+	(Section::Eny, "ENY"), (Section::Nli, "NLI"), (Section::Nnj, "NNJ"), (Section::Nny, "NNY"),
+	(Section::Snj, "SNJ"), (Section::Wny, "WNY"),
+	(Section::De, "DE"), (Section::Epa, "EPA"), (Section::Mdc, "MDC"), (Section::Wpa, "WPA"),
+	(Section::Al, "AL"), (Section::Ga, "GA"), (Section::Ky, "KY"), (Section::Nc, "NC"),
+	(Section::Nfl, "NFL"), (Section::Pr, "PR"), (Section::Sc, "SC"), (Section::Sfl, "SFL"),
+	(Section::Tn, "TN"), (Section::Va, "VA"), (Section::Vi, "VI"), (Section::Wcf, "WCF"),
+	(Section::Ar, "AR"), (Section::La, "LA"), (Section::Ms, "MS"), (Section::Nm, "NM"),
+	(Section::Ntx, "NTX"), (Section::Ok, "OK"), (Section::Stx, "STX"), (Section::Wtx, "WTX"),
+	(Section::Eb, "EB"), (Section::Lax, "LAX"), (Section::Org, "ORG"), (Section::Pac, "PAC"),
+	(Section::Sb, "SB"), (Section::Scv, "SCV"), (Section::Sdg, "SDG"), (Section::Sf, "SF"),
+	(Section::Sjv, "SJV"), (Section::Sv, "SV"),
+	(Section::Ak, "AK"), (Section::Az, "AZ"), (Section::Ewa, "EWA"), (Section::Id, "ID"),
+	(Section::Mt, "MT"), (Section::Nv, "NV"), (Section::Or, "OR"), (Section::Ut, "UT"),
+	(Section::Wwa, "WWA"), (Section::Wy, "WY"),
+	(Section::Mi, "MI"), (Section::Oh, "OH"), (Section::Wv, "WV"),
+	(Section::Il, "IL"), (Section::In, "IN"), (Section::Wi, "WI"),
+	(Section::Co, "CO"), (Section::Ia, "IA"), (Section::Ks, "KS"), (Section::Mn, "MN"),
+	(Section::Mo, "MO"), (Section::Nd, "ND"), (Section::Ne, "NE"), (Section::Sd, "SD"),
+	(Section::Ab, "AB"), (Section::Bc, "BC"), (Section::Gh, "GH"), (Section::Mb, "MB"),
+	(Section::Nb, "NB"), (Section::Nl, "NL"), (Section::Ns, "NS"), (Section::One, "ONE"),
+	(Section::Onn, "ONN"), (Section::Ons, "ONS"), (Section::Pe, "PE"), (Section::Qc, "QC"),
+	(Section::Sk, "SK"), (Section::Ter, "TER")
+];
+
+impl Section {
+	/// Every section, in the order of their call areas.
+	pub fn all() -> impl Iterator<Item = Section> {
+		SECTIONS.iter().map(|(section, _)| *section)
+	}
+
+	/// Whether this is one of the Radio Amateurs of Canada sections.
+	pub fn is_canadian(&self) -> bool {
+		matches!(self,
+			Section::Ab | Section::Bc | Section::Gh | Section::Mb | Section::Nb | Section::Nl |
+			Section::Ns | Section::One | Section::Onn | Section::Ons | Section::Pe | Section::Qc |
+			Section::Sk | Section::Ter)
+	}
+
+	fn abbreviation(&self) -> &'static str {
+		SECTIONS.iter()
+			.find(|(section, _)| section == self)
+			.map(|(_, abbreviation)| *abbreviation)
+			.unwrap()
+	}
+}
+
+pub(crate) fn cabrillo_section(input: &str) -> IResult<&str, Section> {
+	map_opt(alpha1, |token: &str| {
+		SECTIONS.iter()
+			.find(|(_, abbreviation)| *abbreviation == token)
+			.map(|(section, _)| *section)
+	})(input)
+}
+
+impl Display for Section {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.abbreviation())
+	}
+}
+
+impl FromStr for Section {
+	type Err = CabrilloErrorKind;
+
+	fn from_str(input: &str) -> Result<Self, Self::Err> {
+		match cabrillo_section(input) {
+			Ok(("", section)) => Ok(section),
+			_ => Err(CabrilloErrorKind::ParseError(format!("'{}' is not a valid Section", input)))
+		}
+	}
+}
+
+impl TryFrom<&str> for Section {
+	type Error = CabrilloErrorKind;
+
+	fn try_from(input: &str) -> Result<Self, Self::Error> {
+		input.parse()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn parse_section() {
+		assert_eq!("WMA".parse::<Section>(), Ok(Section::Wma));
+		assert_eq!(Section::try_from("ONS"), Ok(Section::Ons));
+		assert!("WMAX".parse::<Section>().is_err());
+		assert!("DX".parse::<Section>().is_err());
+
+		assert_eq!(Section::all().count(), 85);
+		assert!(Section::all().all(|section| section.to_string().parse::<Section>() == Ok(section)));
+		assert!(Section::Qc.is_canadian());
+		assert!(!Section::Pr.is_canadian());
+
+		let log = CabrilloLog::from_buffer(b"START-OF-LOG: 2.0\nARRL-SECTION: EMA\nEND-OF-LOG:").unwrap();
+		assert_eq!(log.arrl_section(), &Some(Section::Ema));
+		assert!(log.other_tags().is_empty());
+		assert!(CabrilloLog::from_buffer(b"ARRL-SECTION: XYZ").is_err());
+	}
+}
@@ -0,0 +1,184 @@
+use std::str::FromStr;
+use std::fmt;
+use serde::{Serialize, Serializer, Deserialize, Deserializer, de::{Error, MapAccess, Visitor}, ser::SerializeMap};
+use crate::*;
+
+/// Serialize enums as their Cabrillo tokens, and deserialize them using the
+/// same parsers as the log, so that the accepted values always match.
+macro_rules! impl_serde_token {
+	($($type: ty),*) => {
+		$(
+			impl Serialize for $type {
+				fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+					serializer.serialize_str(&self.to_string())
+				}
+			}
+
+			impl<'de> Deserialize<'de> for $type {
+				fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+					let token = String::deserialize(deserializer)?;
+					<$type>::from_str(&token).map_err(D::Error::custom)
+				}
+			}
+		)*
+	}
+}
+
+impl_serde_token!(
+	Band,
+	Mode,
+	DigitalMode,
+	OperatorCategory,
+	PowerCategory,
+	StationCategory,
+	TimeCategory,
+	TransmitterCategory,
+	OverlayCategory,
+	GridSquare,
+	Section
+);
+
+impl Serialize for Callsign {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&self.to_string())
+	}
+}
+
+impl<'de> Deserialize<'de> for Callsign {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		String::deserialize(deserializer).map(Callsign::from)
+	}
+}
+
+/// Unrecognized tags are an object keyed by tag. A repeated tag appears as a
+/// repeated key, in order.
+impl Serialize for OtherTags {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let mut map = serializer.serialize_map(Some(self.len()))?;
+
+		for (tag, value) in self.iter() {
+			map.serialize_entry(tag, value)?;
+		}
+
+		map.end()
+	}
+}
+
+impl<'de> Deserialize<'de> for OtherTags {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		struct OtherTagsVisitor;
+
+		impl<'de> Visitor<'de> for OtherTagsVisitor {
+			type Value = OtherTags;
+
+			fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				f.write_str("a map of tags to values")
+			}
+
+			fn visit_map<M: MapAccess<'de>>(self, mut access: M) -> Result<Self::Value, M::Error> {
+				let mut tags = OtherTags::default();
+
+				while let Some((tag, value)) = access.next_entry::<String, String>()? {
+					tags.push(tag, value);
+				}
+
+				Ok(tags)
+			}
+		}
+
+		deserializer.deserialize_map(OtherTagsVisitor)
+	}
+}
+
+/// The version is a number, as it was before unknown versions were kept, and
+/// a string only for versions which are not numeric.
+impl Serialize for CabrilloVersion {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match self.as_f32() {
+			Some(version) => serializer.serialize_f32(version),
+			None => serializer.serialize_str(&self.to_string())
+		}
+	}
+}
+
+impl<'de> Deserialize<'de> for CabrilloVersion {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		#[derive(Deserialize)]
+		#[serde(untagged)]
+		enum Version {
+			Number(f32),
+			Token(String)
+		}
+
+		let token = match Version::deserialize(deserializer)? {
+			Version::Number(version) => format!("{:.1}", version),
+			Version::Token(token) => token
+		};
+
+		CabrilloVersion::from_str(&token).map_err(D::Error::custom)
+	}
+}
+
+/// Lines such as the SOAPBOX, serialized joined with '\n' or as null when
+/// there are none.
+pub(crate) mod joined_lines {
+	use super::*;
+
+	pub fn serialize<S: Serializer>(lines: &[String], serializer: S) -> Result<S::Ok, S::Error> {
+		if lines.is_empty() {
+			serializer.serialize_none()
+		} else {
+			serializer.serialize_str(&lines.join("\n"))
+		}
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+		let text = Option::<String>::deserialize(deserializer)?;
+		Ok(text.iter().flat_map(|text| text.lines()).map(str::to_string).collect())
+	}
+}
+
+/// Dates and times as ISO 8601 without an offset, `2023-01-14T18:05:00`,
+/// whichever crate backs them.
+pub(crate) mod iso_datetime {
+	use super::*;
+
+	const FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+	pub fn serialize<S: Serializer>(value: &DateTime, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&datetime::format(value, FORMAT))
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime, D::Error> {
+		let text = String::deserialize(deserializer)?;
+		datetime::parse(&text, FORMAT).ok_or_else(|| D::Error::custom(format!("invalid date and time '{}'", text)))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn serialize_tokens() {
+		assert_eq!(serde_json::to_string(&Band::VhfFmOnly).unwrap(), "\"VHF-FM-ONLY\"");
+		assert_eq!(serde_json::from_str::<Mode>("\"PH\"").unwrap(), Mode::Phone);
+		assert!(serde_json::from_str::<PowerCategory>("\"MEDIUM\"").is_err());
+		assert_eq!(serde_json::to_string(&CabrilloVersion::V2).unwrap(), "2.0");
+		assert_eq!(serde_json::from_str::<CabrilloVersion>("3.0").unwrap(), CabrilloVersion::V3);
+		assert_eq!(serde_json::from_str::<CabrilloVersion>("\"3.1a\"").unwrap(), CabrilloVersion::Other("3.1a".into()));
+	}
+
+	#[test]
+	fn serialize_log() {
+		let log = CabrilloLog::from_path("test_data/cqww.txt").unwrap();
+		let value = serde_json::to_value(&log).unwrap();
+
+		assert_eq!(value["CALLSIGN"], "AA1ZZZ");
+		assert_eq!(value["CATEGORY-MODE"], "SSB");
+		assert_eq!(value["QSO"][0]["call_received"], "K9QZO");
+
+		let parsed: CabrilloLog = serde_json::from_value(value).unwrap();
+		assert_eq!(parsed.to_string(), log.to_string());
+	}
+}
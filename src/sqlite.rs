@@ -0,0 +1,217 @@
+use std::path::Path;
+use rusqlite::{params, Connection};
+use crate::*;
+
+const SQLITE_DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+const SCHEMA: &str = "
+	CREATE TABLE IF NOT EXISTS logs (
+		id INTEGER PRIMARY KEY,
+		version TEXT NOT NULL,
+		callsign TEXT,
+		contest TEXT,
+		category_assisted INTEGER,
+		category_band TEXT,
+		category_mode TEXT,
+		category_operator TEXT,
+		category_power TEXT,
+		category_station TEXT,
+		category_time TEXT,
+		category_transmitter TEXT,
+		category_overlay TEXT,
+		certificate INTEGER,
+		claimed_score INTEGER,
+		club TEXT,
+		created_by TEXT,
+		email TEXT,
+		grid_locator TEXT,
+		location TEXT,
+		name TEXT,
+		address TEXT,
+		operators TEXT,
+		soapbox TEXT
+	);
+
+	CREATE TABLE IF NOT EXISTS log_tags (
+		log_id INTEGER NOT NULL REFERENCES logs(id),
+		tag TEXT NOT NULL,
+		value TEXT NOT NULL
+	);
+
+	CREATE TABLE IF NOT EXISTS offtimes (
+		log_id INTEGER NOT NULL REFERENCES logs(id),
+		begin TEXT NOT NULL,
+		end TEXT NOT NULL
+	);
+
+	CREATE TABLE IF NOT EXISTS qsos (
+		id INTEGER PRIMARY KEY,
+		log_id INTEGER NOT NULL REFERENCES logs(id),
+		frequency_khz INTEGER,
+		mode TEXT NOT NULL,
+		datetime TEXT NOT NULL,
+		call_sent TEXT NOT NULL,
+		exchange_sent TEXT NOT NULL,
+		call_received TEXT NOT NULL,
+		exchange_received TEXT NOT NULL,
+		transmitter_id INTEGER,
+		ignored INTEGER NOT NULL
+	);
+
+	CREATE INDEX IF NOT EXISTS logs_callsign ON logs(callsign);
+	CREATE INDEX IF NOT EXISTS log_tags_log_id ON log_tags(log_id);
+	CREATE INDEX IF NOT EXISTS offtimes_log_id ON offtimes(log_id);
+	CREATE INDEX IF NOT EXISTS qsos_log_id ON qsos(log_id);
+	CREATE INDEX IF NOT EXISTS qsos_call_received ON qsos(call_received);
+	CREATE INDEX IF NOT EXISTS qsos_datetime ON qsos(datetime);
+";
+
+fn sqlite_error(err: rusqlite::Error) -> CabrilloError {
+	CabrilloError::new("", 0, CabrilloErrorKind::IoError(err.to_string()))
+}
+
+fn token<T: ToString>(value: &Option<T>) -> Option<String> {
+	value.as_ref().map(|value| value.to_string())
+}
+
+impl CabrilloLog {
+	/// Write this log to the SQLite database at `path`, creating the database and
+	/// its tables if needed. Returns the id of the new row in the `logs` table.
+	///
+	/// Header fields are stored in `logs`, custom tags in `log_tags`, offtimes in
+	/// `offtimes`, and QSOs in `qsos`, with X-QSO entries marked as `ignored`.
+	/// Category values are stored as their Cabrillo tokens and timestamps as
+	/// `YYYY-MM-DD HH:MM:SS` text in UTC. Writing several logs to the same
+	/// database appends to these tables.
+	pub fn to_sqlite<P: AsRef<Path>>(&self, path: P) -> CabrilloResult<i64> {
+		let mut conn = Connection::open(path).map_err(sqlite_error)?;
+		self.to_sqlite_connection(&mut conn)
+	}
+
+	/// Like `to_sqlite()`, but writing to an open connection. The log is written
+	/// in a single transaction.
+	pub fn to_sqlite_connection(&self, conn: &mut Connection) -> CabrilloResult<i64> {
+		let tx = conn.transaction().map_err(sqlite_error)?;
+		tx.execute_batch(SCHEMA).map_err(sqlite_error)?;
+
+		tx.execute(
+			"INSERT INTO logs (version, callsign, contest, category_assisted, category_band,
+				category_mode, category_operator, category_power, category_station, category_time,
+				category_transmitter, category_overlay, certificate, claimed_score, club, created_by,
+				email, grid_locator, location, name, address, operators, soapbox)
+			VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17,
+				?18, ?19, ?20, ?21, ?22, ?23)",
+			params![
+				self.version.to_string(),
+				self.callsign,
+				self.contest,
+				self.category_assisted,
+				token(&self.category_band),
+				token(&self.category_mode),
+				token(&self.category_operator),
+				token(&self.category_power),
+				token(&self.category_station),
+				token(&self.category_time),
+				token(&self.category_transmitter),
+				token(&self.category_overlay),
+				self.certificate,
+				self.claimed_score,
+				self.club,
+				self.created_by,
+				self.email,
+				self.grid_locator.as_ref().map(GridSquare::as_str),
+				self.location,
+				self.name,
+				self.address,
+				self.joined_operators(" "),
+				self.soapbox_text()
+			]
+		).map_err(sqlite_error)?;
+
+		let log_id = tx.last_insert_rowid();
+
+		{
+			let mut insert_tag = tx.prepare("INSERT INTO log_tags (log_id, tag, value) VALUES (?1, ?2, ?3)")
+				.map_err(sqlite_error)?;
+
+			for (tag, value) in self.other_tags.iter() {
+				insert_tag.execute(params![log_id, tag, value]).map_err(sqlite_error)?;
+			}
+
+			let mut insert_offtime = tx.prepare("INSERT INTO offtimes (log_id, begin, end) VALUES (?1, ?2, ?3)")
+				.map_err(sqlite_error)?;
+
+			for offtime in &self.offtimes {
+				insert_offtime.execute(params![
+					log_id,
+					datetime::format(&offtime.begin, SQLITE_DATETIME_FORMAT),
+					datetime::format(&offtime.end, SQLITE_DATETIME_FORMAT)
+				]).map_err(sqlite_error)?;
+			}
+
+			let mut insert_qso = tx.prepare(
+				"INSERT INTO qsos (log_id, frequency_khz, mode, datetime, call_sent, exchange_sent,
+					call_received, exchange_received, transmitter_id, ignored)
+				VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)"
+			).map_err(sqlite_error)?;
+
+			let entries = self.entries.iter().map(|qso| (qso, false))
+				.chain(self.ignored_entries.iter().map(|qso| (qso, true)));
+
+			for (qso, ignored) in entries {
+				insert_qso.execute(params![
+					log_id,
+					qso.frequency.as_khz(),
+					qso.mode.to_string(),
+					datetime::format(&qso.datetime, SQLITE_DATETIME_FORMAT),
+					qso.call_sent.as_str(),
+					qso.exch_sent,
+					qso.call_recvd.as_str(),
+					qso.exch_recvd,
+					qso.transmitter_id,
+					ignored
+				]).map_err(sqlite_error)?;
+			}
+		}
+
+		tx.commit().map_err(sqlite_error)?;
+		Ok(log_id)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use rusqlite::Connection;
+	use crate::*;
+
+	#[test]
+	fn export_sqlite() {
+		let log = CabrilloLog::from_path("test_data/cqww.txt").unwrap();
+		let mut conn = Connection::open_in_memory().unwrap();
+
+		let first = log.to_sqlite_connection(&mut conn).unwrap();
+		let second = log.to_sqlite_connection(&mut conn).unwrap();
+		assert_ne!(first, second);
+
+		let (version, callsign, band): (String, String, String) = conn.query_row(
+			"SELECT version, callsign, category_band FROM logs WHERE id = ?1", [first],
+			|row| Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+		).unwrap();
+		assert_eq!(version, "3.0");
+		assert_eq!(callsign, "AA1ZZZ");
+		assert_eq!(band, "ALL");
+
+		let count: i64 = conn.query_row(
+			"SELECT COUNT(*) FROM qsos WHERE log_id = ?1 AND ignored = 0", [second],
+			|row| row.get(0)
+		).unwrap();
+		assert_eq!(count as usize, log.entries().len());
+
+		let (freq, datetime): (u32, String) = conn.query_row(
+			"SELECT frequency_khz, datetime FROM qsos WHERE log_id = ?1 ORDER BY id LIMIT 1", [first],
+			|row| Ok((row.get(0)?, row.get(1)?))
+		).unwrap();
+		assert_eq!(freq, 3799);
+		assert_eq!(datetime, "2000-10-26 07:11:00");
+	}
+}
@@ -0,0 +1,163 @@
+use crate::*;
+
+/// Lengths of the best rate windows of `RateStats`, in minutes.
+const RATE_WINDOWS: [i64; 3] = [10, 30, 60];
+
+/// The period of a log with the most QSOs for its length, as found by
+/// `CabrilloLog::best_window()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateWindow {
+	start: DateTime,
+	length: Duration,
+	qsos: usize
+}
+
+impl RateWindow {
+	/// Time of the first QSO of the window.
+	pub fn start(&self) -> &DateTime {
+		&self.start
+	}
+
+	pub fn length(&self) -> Duration {
+		self.length
+	}
+
+	pub fn qsos(&self) -> usize {
+		self.qsos
+	}
+
+	/// QSOs per hour over the window.
+	pub fn rate(&self) -> f64 {
+		self.qsos as f64 * 60.0 / datetime::whole_minutes(&self.length) as f64
+	}
+}
+
+/// Rate statistics of a log, returned by `CabrilloLog::rate_stats()`: the
+/// numbers of a post-contest writeup.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateStats {
+	hourly: Vec<(DateTime, usize)>,
+	best_windows: Vec<RateWindow>,
+	longest_gap: Option<Offtime>
+}
+
+impl RateStats {
+	/// QSOs made in each clock hour from that of the first QSO to that of the
+	/// last, including hours without any.
+	pub fn hourly(&self) -> &Vec<(DateTime, usize)> {
+		&self.hourly
+	}
+
+	/// The best 10, 30, and 60 minute windows, in that order. Empty if the log
+	/// has no QSOs.
+	pub fn best_windows(&self) -> &Vec<RateWindow> {
+		&self.best_windows
+	}
+
+	/// The best window of `minutes`, if it is one of those worked out.
+	pub fn best(&self, minutes: i64) -> Option<&RateWindow> {
+		self.best_windows.iter().find(|window| datetime::whole_minutes(&window.length) == minutes)
+	}
+
+	/// The longest time between two QSOs.
+	pub fn longest_gap(&self) -> &Option<Offtime> {
+		&self.longest_gap
+	}
+}
+
+impl CabrilloLog {
+	/// Times of the QSOs of the log, in order. Ignored (X-QSO) entries are not
+	/// counted.
+	fn qso_times(&self) -> Vec<DateTime> {
+		let mut times: Vec<DateTime> = self.entries.iter().map(|qso| qso.datetime).collect();
+		times.sort();
+		times
+	}
+
+	/// The window of `minutes` starting at a QSO which holds the most QSOs,
+	/// the earliest if there are several.
+	pub fn best_window(&self, minutes: i64) -> Option<RateWindow> {
+		best_window(&self.qso_times(), Duration::minutes(minutes))
+	}
+
+	/// Work out the QSO rate of this log hour by hour, its best 10, 30, and 60
+	/// minute windows, and its longest gap between QSOs.
+	pub fn rate_stats(&self) -> RateStats {
+		let times = self.qso_times();
+		let mut hourly: Vec<(DateTime, usize)> = Vec::new();
+
+		if let (Some(first), Some(last)) = (times.first(), times.last()) {
+			let mut hour = datetime::start_of_hour(first);
+
+			while hour <= *last {
+				let next = hour + Duration::hours(1);
+				hourly.push((hour, times.iter().filter(|time| hour <= **time && **time < next).count()));
+				hour = next;
+			}
+		}
+
+		RateStats {
+			hourly,
+			best_windows: RATE_WINDOWS.iter().filter_map(|minutes| best_window(&times, Duration::minutes(*minutes))).collect(),
+			longest_gap: times.windows(2)
+				.max_by_key(|pair| (pair[1] - pair[0], std::cmp::Reverse(pair[0])))
+				.map(|pair| Offtime::new(pair[0], pair[1]))
+		}
+	}
+}
+
+fn best_window(times: &[DateTime], length: Duration) -> Option<RateWindow> {
+	let mut best: Option<RateWindow> = None;
+	let mut end = 0;
+
+	for (start, time) in times.iter().enumerate() {
+		while end < times.len() && times[end] < *time + length {
+			end += 1;
+		}
+
+		if best.is_none_or(|best| end - start > best.qsos) {
+			best = Some(RateWindow { start: *time, length, qsos: end - start });
+		}
+	}
+
+	best
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn rate_stats() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"QSO: 14025 CW 2023-01-14 0000 W1AW 599 CT K1AB 599 MA\n",
+			"QSO: 14025 CW 2023-01-14 0005 W1AW 599 CT K1AC 599 MA\n",
+			"QSO: 14025 CW 2023-01-14 0050 W1AW 599 CT K1AD 599 MA\n",
+			"QSO: 14025 CW 2023-01-14 0052 W1AW 599 CT K1AE 599 MA\n",
+			"QSO: 14025 CW 2023-01-14 0054 W1AW 599 CT K1AF 599 MA\n",
+			"QSO: 14025 CW 2023-01-14 0115 W1AW 599 CT K1AG 599 MA\n",
+			"QSO: 14025 CW 2023-01-14 0320 W1AW 599 CT K1AH 599 MA\n",
+			"END-OF-LOG:"
+		);
+
+		let log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+		let stats = log.rate_stats();
+		let counts: Vec<usize> = stats.hourly().iter().map(|(_, count)| *count).collect();
+		assert_eq!(counts, vec![5, 1, 0, 1]);
+		assert_eq!(datetime::format(&stats.hourly()[1].0, "%H%M"), "0100");
+
+		let best = stats.best(10).unwrap();
+		assert_eq!((datetime::format(best.start(), "%H%M"), best.qsos()), ("0050".to_string(), 3));
+		assert_eq!(best.rate(), 18.0);
+		assert_eq!(stats.best(30).unwrap().qsos(), 4);
+		assert_eq!(stats.best(60).unwrap().qsos(), 5);
+		assert_eq!(log.best_window(120).unwrap().qsos(), 6);
+
+		let gap = stats.longest_gap().as_ref().unwrap();
+		assert_eq!(gap.duration(), Duration::minutes(125));
+
+		let empty = CabrilloLog::new().rate_stats();
+		assert!(empty.hourly().is_empty() && empty.best_windows().is_empty() && empty.longest_gap().is_none());
+	}
+}
@@ -0,0 +1,156 @@
+use std::fmt::Write;
+use crate::*;
+
+const SUMMARY_DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M UTC";
+
+/// Modes in the order their columns are shown in the summary.
+const SUMMARY_MODES: [Mode; 6] = [Mode::Cw, Mode::Phone, Mode::Fm, Mode::Rtty, Mode::Digital, Mode::Mixed];
+
+fn line(out: &mut String, label: &str, value: Option<String>) {
+	if let Some(value) = value {
+		writeln!(out, "{:<16}{}", format!("{}:", label), value).unwrap();
+	}
+}
+
+/// Band a QSO was made on, or its frequency if it is outside the amateur bands.
+fn band_name(qso: &Qso) -> String {
+	qso.band()
+		.map(|band| band.to_string())
+		.unwrap_or_else(|| qso.frequency.to_string())
+}
+
+fn format_duration(duration: Duration) -> String {
+	format!("{}h {:02}m", datetime::whole_hours(&duration), datetime::whole_minutes(&duration) % 60)
+}
+
+impl CabrilloLog {
+	/// The category tags joined into one line, e.g. "SINGLE-OP ALL HIGH SSB".
+	fn summary_category(&self) -> Option<String> {
+		let fields: Vec<String> = [
+			self.category_operator.map(|op| op.to_string()),
+			self.category_assisted.map(|assisted| if assisted { "ASSISTED" } else { "NON-ASSISTED" }.to_string()),
+			self.category_band.map(|band| band.to_string()),
+			self.category_power.map(|power| power.to_string()),
+			self.category_mode.map(|mode| mode.to_string()),
+			self.category_station.map(|station| station.to_string()),
+			self.category_transmitter.map(|xmitter| xmitter.to_string()),
+			self.category_time.map(|time| time.to_string()),
+			self.category_overlay.as_ref().map(|overlay| overlay.to_string())
+		]
+			.into_iter()
+			.flatten()
+			.collect();
+
+		if fields.is_empty() { None } else { Some(fields.join(" ")) }
+	}
+
+	/// Render a human-readable summary of this log: entrant information, QSO
+	/// counts per band and mode, the claimed score, and the operating time.
+	///
+	/// The operating time is the period from the first to the last QSO, less any
+	/// offtime. Ignored (X-QSO) entries are not counted. A checklog is shown as
+	/// such in place of its claimed score.
+	pub fn summary(&self) -> String {
+		let mut out = String::new();
+
+		line(&mut out, "Callsign", self.callsign.clone());
+		line(&mut out, "Contest", self.contest.clone());
+		line(&mut out, "Name", self.name.clone());
+		line(&mut out, "Club", self.club.clone());
+		line(&mut out, "Location", self.location.clone());
+		line(&mut out, "Category", self.summary_category());
+		line(&mut out, "Operators", Some(self.joined_operators(" ")).filter(|ops| !ops.is_empty()));
+
+		// bands are ordered by the lowest frequency worked on them
+		let mut bands: Vec<(String, u32)> = Vec::new();
+		for qso in &self.entries {
+			let name = band_name(qso);
+			let freq = qso.frequency.as_khz().unwrap_or(u32::MAX);
+
+			match bands.iter_mut().find(|(band, _)| band == &name) {
+				Some((_, lowest)) => *lowest = (*lowest).min(freq),
+				None => bands.push((name, freq))
+			}
+		}
+		bands.sort_by_key(|(_, freq)| *freq);
+
+		let modes: Vec<Mode> = SUMMARY_MODES.iter()
+			.filter(|mode| self.entries.iter().any(|qso| &qso.mode == *mode))
+			.copied()
+			.collect();
+
+		let count = |band: Option<&str>, mode: Option<Mode>| {
+			self.entries.iter()
+				.filter(|qso| mode.is_none_or(|mode| qso.mode == mode))
+				.filter(|qso| band.is_none_or(|band| band_name(qso) == band))
+				.count()
+		};
+
+		writeln!(out).unwrap();
+		write!(out, "{:<8}", "Band").unwrap();
+		for mode in &modes {
+			write!(out, "{:>8}", mode.to_string()).unwrap();
+		}
+		writeln!(out, "{:>8}", "Total").unwrap();
+
+		for (band, _) in &bands {
+			write!(out, "{:<8}", band).unwrap();
+			for mode in &modes {
+				write!(out, "{:>8}", count(Some(band), Some(*mode))).unwrap();
+			}
+			writeln!(out, "{:>8}", count(Some(band), None)).unwrap();
+		}
+
+		write!(out, "{:<8}", "Total").unwrap();
+		for mode in &modes {
+			write!(out, "{:>8}", count(None, Some(*mode))).unwrap();
+		}
+		writeln!(out, "{:>8}", self.entries.len()).unwrap();
+		writeln!(out).unwrap();
+
+		if self.is_checklog() {
+			line(&mut out, "Claimed score", Some("None, checklog".to_string()));
+		} else {
+			line(&mut out, "Claimed score", self.claimed_score.map(|score| score.to_string()));
+		}
+
+		let first = self.entries.iter().map(|qso| qso.datetime).min();
+		let last = self.entries.iter().map(|qso| qso.datetime).max();
+
+		if let (Some(first), Some(last)) = (first, last) {
+			let offtime = self.total_offtime();
+
+			line(&mut out, "First QSO", Some(datetime::format(&first, SUMMARY_DATETIME_FORMAT)));
+			line(&mut out, "Last QSO", Some(datetime::format(&last, SUMMARY_DATETIME_FORMAT)));
+			line(&mut out, "Operating time", Some(format_duration((last - first - offtime).max(Duration::default()))));
+		}
+
+		out
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn summary() {
+		let log = CabrilloLog::from_path("test_data/cqww_vhf.txt").unwrap();
+		let summary = log.summary();
+
+		assert!(summary.starts_with("Callsign:       AA1ZZZ\nContest:        CQ-VHF\n"));
+		assert!(summary.contains("Category:       SINGLE-OP ALL HIGH\n"));
+		assert!(summary.contains(concat!(
+			"Band          CW     SSB   Total\n",
+			"6M             1       1       2\n",
+			"2M             0       1       1\n",
+			"Total          1       2       3\n"
+		)));
+		assert!(summary.contains("Claimed score:  9447\n"));
+		assert!(summary.contains("Operating time: 0h 09m\n"));
+
+		let mut log = log;
+		log.set_category_operator(Some(OperatorCategory::CheckLog));
+		assert!(log.summary().contains("Claimed score:  None, checklog\n"));
+	}
+}
@@ -0,0 +1,731 @@
+use std::sync::Arc;
+use std::ops::Range;
+use crate::*;
+
+/// A check run against a whole log by `CabrilloLog::validate()`. Implement
+/// this for checks which are not built in, and add it to a `RuleSet`.
+pub trait Rule: Send + Sync {
+	/// Short name of the rule, recorded in each of its findings.
+	fn name(&self) -> &str;
+
+	/// Add a `Finding` to `report` for each problem found in `log`.
+	fn check(&self, log: &CabrilloLog, report: &mut ValidationReport);
+}
+
+/// The rules run by `CabrilloLog::validate()`, in order.
+#[derive(Clone)]
+pub struct RuleSet(Vec<Arc<dyn Rule>>);
+
+impl RuleSet {
+	/// A rule set without any rules.
+	pub fn empty() -> Self {
+		Self(Vec::new())
+	}
+
+	/// The rules built into this crate: `RequiredTags::default()`,
+	/// `LogStructure::default()`, `ValueRanges`, and `CategoryConsistency`.
+	/// This is the default.
+	pub fn standard() -> Self {
+		let mut rules = Self::empty();
+		rules.register(RequiredTags::default());
+		rules.register(LogStructure::default());
+		rules.register(ValueRanges);
+		rules.register(CategoryConsistency);
+		rules
+	}
+
+	pub fn register<R: Rule + 'static>(&mut self, rule: R) {
+		self.0.push(Arc::new(rule));
+	}
+
+	/// Remove every rule named `name`, returning whether there were any.
+	pub fn remove(&mut self, name: &str) -> bool {
+		let len = self.0.len();
+		self.0.retain(|rule| rule.name() != name);
+		self.0.len() != len
+	}
+
+	/// Names of the rules, in the order they run.
+	pub fn names(&self) -> Vec<&str> {
+		self.0.iter().map(|rule| rule.name()).collect()
+	}
+
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+}
+
+impl Default for RuleSet {
+	fn default() -> Self {
+		Self::standard()
+	}
+}
+
+impl fmt::Debug for RuleSet {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_list().entries(self.names()).finish()
+	}
+}
+
+/// Header tags which the Cabrillo 3 specification requires. CATEGORY-STATION,
+/// CATEGORY-TIME, and CATEGORY-OVERLAY have defaults, and the rest are
+/// optional.
+const V3_REQUIRED_TAGS: [&str; 11] = [
+	"CALLSIGN",
+	"CONTEST",
+	"CATEGORY-OPERATOR",
+	"CATEGORY-ASSISTED",
+	"CATEGORY-BAND",
+	"CATEGORY-MODE",
+	"CATEGORY-POWER",
+	"CATEGORY-TRANSMITTER",
+	"CLAIMED-SCORE",
+	"LOCATION",
+	"NAME"
+];
+
+/// Header tags which the Cabrillo 2 specification requires, where a single
+/// CATEGORY tag holds the category.
+const V2_REQUIRED_TAGS: [&str; 5] = [
+	"CALLSIGN",
+	"CONTEST",
+	"CATEGORY",
+	"CLAIMED-SCORE",
+	"NAME"
+];
+
+/// Reports header tags which are missing or empty. By default these are
+/// CALLSIGN and CONTEST, without which no sponsor can process a log, while
+/// `spec()` checks every tag required by the version of the log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequiredTags(Option<Vec<String>>);
+
+impl RequiredTags {
+	pub fn new(tags: &[&str]) -> Self {
+		Self(Some(tags.iter().map(|tag| tag.to_string()).collect()))
+	}
+
+	/// The tags required by the specification of the version of each log.
+	/// Logs of an unknown version are checked as version 3.
+	pub fn spec() -> Self {
+		Self(None)
+	}
+
+	/// The tags required of a log of `version`.
+	pub fn tags(&self, version: &CabrilloVersion) -> Vec<&str> {
+		match (&self.0, version) {
+			(Some(tags), _) => tags.iter().map(String::as_str).collect(),
+			(None, CabrilloVersion::V2) => V2_REQUIRED_TAGS.to_vec(),
+			(None, _) => V3_REQUIRED_TAGS.to_vec()
+		}
+	}
+}
+
+impl Default for RequiredTags {
+	fn default() -> Self {
+		Self::new(&["CALLSIGN", "CONTEST"])
+	}
+}
+
+impl Rule for RequiredTags {
+	fn name(&self) -> &str {
+		"required-tags"
+	}
+
+	fn check(&self, log: &CabrilloLog, report: &mut ValidationReport) {
+		for tag in self.tags(&log.version).into_iter().filter(|tag| !log.has_tag(tag) && !log.is_exempt(tag)) {
+			report.add(Finding::new(self.name(), Severity::Error, tag, "Missing required tag".into()));
+		}
+	}
+}
+
+/// Reports problems with the shape of a log: a log without QSOs, offtimes
+/// which end before they begin or overlap, QSOs sent from another callsign
+/// than the CALLSIGN tag, and exchanges which do not match the schema of the
+/// contest or, if there is none, whose sent and received halves differ in
+/// length. Contests are looked up in the built in schemas unless others are
+/// given with `new()`, such as the `schemas` of the `ParseOptions` the log was
+/// parsed with.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct LogStructure {
+	schemas: SchemaRegistry
+}
+
+impl LogStructure {
+	pub fn new(schemas: SchemaRegistry) -> Self {
+		Self { schemas }
+	}
+}
+
+impl Rule for LogStructure {
+	fn name(&self) -> &str {
+		"structure"
+	}
+
+	fn check(&self, log: &CabrilloLog, report: &mut ValidationReport) {
+		let finding = |severity, tag: &str, line, message: String| {
+			Finding::new(self.name(), severity, tag, message).with_line(line)
+		};
+
+		if log.entries.is_empty() {
+			report.add(finding(Severity::Warning, "QSO", None, "Log has no QSOs".into()));
+		}
+
+		for (idx, offtime) in log.offtimes.iter().enumerate() {
+			let line = log.tag_line("OFFTIME", idx);
+
+			if let Some(message) = offtime.check_order() {
+				report.add(finding(Severity::Error, "OFFTIME", line, message));
+			}
+
+			if let Some(message) = offtime.check_overlap(&log.offtimes[..idx]) {
+				report.add(finding(Severity::Warning, "OFFTIME", line, message));
+			}
+		}
+
+		let schema = self.schemas.for_log(log);
+
+		for qso in &log.entries {
+			if let Some(callsign) = &log.callsign {
+				if !qso.call_sent.as_str().eq_ignore_ascii_case(callsign) {
+					report.add(finding(Severity::Warning, "QSO", qso.line,
+						format!("Sent callsign {} is not the log callsign {}", qso.call_sent, callsign)));
+				}
+			}
+
+			match schema {
+				Some(schema) => {
+					for message in qso.check_exchanges(schema.sent(), schema.received()) {
+						report.add(finding(Severity::Warning, "QSO", qso.line, message));
+					}
+				},
+				None => {
+					if let Some(message) = qso.check_exchange_lengths() {
+						report.add(finding(Severity::Info, "QSO", qso.line, message));
+					}
+				}
+			}
+		}
+	}
+}
+
+/// Reports values which parse but cannot be right: QSO frequencies outside the
+/// amateur bands, empty exchanges, and transmitter IDs in a log which claims a
+/// single transmitter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValueRanges;
+
+impl Rule for ValueRanges {
+	fn name(&self) -> &str {
+		"value-ranges"
+	}
+
+	fn check(&self, log: &CabrilloLog, report: &mut ValidationReport) {
+		let single_transmitter = log.category_transmitter == Some(TransmitterCategory::One);
+
+		for qso in &log.entries {
+			let mut warn = |message: String| {
+				report.add(Finding::new(self.name(), Severity::Warning, "QSO", message).with_line(qso.line));
+			};
+
+			if let Some(message) = qso.check_band() {
+				warn(message);
+			}
+
+			if qso.exch_sent.trim().is_empty() {
+				warn("Sent exchange is empty".into());
+			}
+
+			if qso.exch_recvd.trim().is_empty() {
+				warn("Received exchange is empty".into());
+			}
+
+			if let Some(id) = qso.transmitter_id.filter(|&id| single_transmitter && id > 0) {
+				warn(format!("Transmitter ID {} in a single transmitter log", id));
+			}
+		}
+	}
+}
+
+/// Reports categories which the QSOs of the log do not fit: QSOs on another
+/// band than CATEGORY-BAND, QSOs in another mode than CATEGORY-MODE, a single
+/// operator log which lists more than one operator, and a multi operator log
+/// which lists none. Each category is reported once, with the line of the
+/// first QSO which does not fit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CategoryConsistency;
+
+impl CategoryConsistency {
+	fn band_fits(category: Band, qso: &Qso) -> bool {
+		match category {
+			Band::All | Band::Vhf3Band | Band::VhfFmOnly => true,
+			band => qso.band() == Some(band)
+		}
+	}
+
+	pub(crate) fn mode_fits(category: Mode, qso: &Qso) -> bool {
+		match category {
+			Mode::Mixed => true,
+			Mode::Phone => matches!(qso.mode, Mode::Phone | Mode::Fm),
+			mode => qso.mode == mode
+		}
+	}
+
+	fn report_misfits<F: Fn(&Qso) -> bool>(&self, log: &CabrilloLog, report: &mut ValidationReport, tag: &str, category: String, fits: F) {
+		let misfits: Vec<&Qso> = log.entries.iter().filter(|qso| !fits(qso)).collect();
+		let Some(first) = misfits.first() else { return };
+
+		report.add(
+			Finding::new(self.name(), Severity::Warning, tag,
+				format!("{} is {} but {} of {} QSOs do not fit it", tag, category, misfits.len(), log.entries.len()))
+				.with_line(first.line)
+		);
+	}
+}
+
+impl Rule for CategoryConsistency {
+	fn name(&self) -> &str {
+		"category"
+	}
+
+	fn check(&self, log: &CabrilloLog, report: &mut ValidationReport) {
+		if let Some(band) = log.category_band {
+			self.report_misfits(log, report, "CATEGORY-BAND", band.to_string(), |qso| Self::band_fits(band, qso));
+		}
+
+		if let Some(mode) = log.category_mode {
+			self.report_misfits(log, report, "CATEGORY-MODE", mode.to_string(), |qso| Self::mode_fits(mode, qso));
+		}
+
+		// the host station is not an operator, and neither is a call listed twice
+		let operators: HashSet<String> = log.operators.iter()
+			.filter(|operator| !operator.is_host())
+			.map(|operator| operator.as_str().to_ascii_uppercase())
+			.collect();
+
+		let message = match log.category_operator {
+			Some(OperatorCategory::SingleOp) if operators.len() > 1 =>
+				format!("CATEGORY-OPERATOR is SINGLE-OP but OPERATORS lists {} operators", operators.len()),
+			Some(OperatorCategory::MultiOp) if operators.is_empty() =>
+				"CATEGORY-OPERATOR is MULTI-OP but OPERATORS lists no operators".to_string(),
+			_ => return
+		};
+
+		report.add(
+			Finding::new(self.name(), Severity::Warning, "CATEGORY-OPERATOR", message)
+				.with_line(log.tag_line("CATEGORY-OPERATOR", 0))
+		);
+	}
+}
+
+/// Reports each QSO made in a mode other than a single mode CATEGORY-MODE,
+/// such as an RTTY QSO in a CW log. `CabrilloLog::downgrade_category_mode()`
+/// changes the category to MIXED instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModeCategory;
+
+impl Rule for ModeCategory {
+	fn name(&self) -> &str {
+		"mode-category"
+	}
+
+	fn check(&self, log: &CabrilloLog, report: &mut ValidationReport) {
+		let Some(category) = log.category_mode else { return };
+
+		for qso in log.entries.iter().filter(|qso| !CategoryConsistency::mode_fits(category, qso)) {
+			report.add(
+				Finding::new(self.name(), Severity::Warning, "QSO",
+					format!("{} QSO does not fit CATEGORY-MODE {}", qso.mode, category))
+					.with_line(qso.line)
+			);
+		}
+	}
+}
+
+/// Reports QSOs which go back in time, often because the logger wrote local
+/// time for some of them instead of UTC. Each run of QSOs earlier than the one
+/// before the run is reported once, with the line of its first QSO.
+/// `CabrilloLog::sort_entries()` puts the QSOs back in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chronology;
+
+impl Rule for Chronology {
+	fn name(&self) -> &str {
+		"chronology"
+	}
+
+	fn check(&self, log: &CabrilloLog, report: &mut ValidationReport) {
+		for run in log.backwards_runs() {
+			let first = &log.entries[run.start];
+			let last = &log.entries[run.end - 1];
+			let latest = log.entries[..run.start].iter().map(|qso| qso.datetime).max().unwrap_or(first.datetime);
+
+			let mut message = format!("{} QSOs from {} are earlier than the QSO at {} before them",
+				run.len(), datetime::format(&first.datetime, "%Y-%m-%d %H%M"), datetime::format(&latest, "%Y-%m-%d %H%M"));
+
+			if let (Some(start), Some(end)) = (first.line, last.line) {
+				message.push_str(&format!(", lines {} to {}", start, end));
+			}
+
+			report.add(Finding::new(self.name(), Severity::Warning, "QSO", message).with_line(first.line));
+		}
+	}
+}
+
+/// Reports OFFTIMEs which a contest would not count: breaks shorter than its
+/// minimum, such as 30 or 60 minutes, and breaks which overlap another. Each
+/// overlapping pair is reported once, at the later of the two.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OfftimeRule {
+	min_break: Duration
+}
+
+impl OfftimeRule {
+	pub fn new(min_break: Duration) -> Self {
+		Self { min_break }
+	}
+
+	pub fn min_break(&self) -> Duration {
+		self.min_break
+	}
+}
+
+impl Rule for OfftimeRule {
+	fn name(&self) -> &str {
+		"offtime"
+	}
+
+	fn check(&self, log: &CabrilloLog, report: &mut ValidationReport) {
+		for (idx, offtime) in log.offtimes.iter().enumerate() {
+			let line = log.tag_line("OFFTIME", idx);
+			let finding = |message| Finding::new(self.name(), Severity::Warning, "OFFTIME", message).with_line(line);
+
+			// an inverted offtime is an error of its own, see LogStructure
+			if offtime.duration() >= Duration::default() && offtime.duration() < self.min_break {
+				report.add(finding(format!("Offtime of {} minutes is shorter than the minimum of {} minutes",
+					datetime::whole_minutes(&offtime.duration()), datetime::whole_minutes(&self.min_break))));
+			}
+
+			for other in log.offtimes[..idx].iter().filter(|other| other.overlaps(offtime)) {
+				report.add(finding(format!("Offtime from {} to {} overlaps the offtime from {} to {}",
+					datetime::format(&offtime.begin, "%Y-%m-%d %H%M"), datetime::format(&offtime.end, "%Y-%m-%d %H%M"),
+					datetime::format(&other.begin, "%Y-%m-%d %H%M"), datetime::format(&other.end, "%Y-%m-%d %H%M"))));
+			}
+		}
+	}
+}
+
+impl CabrilloLog {
+	/// Tags required by the specification of the version of this log which
+	/// it does not have, so that it would be rejected by a sponsor robot.
+	pub fn missing_tags(&self) -> Vec<&'static str> {
+		let required: &[&'static str] = match self.version {
+			CabrilloVersion::V2 => &V2_REQUIRED_TAGS,
+			_ => &V3_REQUIRED_TAGS
+		};
+
+		required.iter().copied().filter(|tag| !self.has_tag(tag) && !self.is_exempt(tag)).collect()
+	}
+
+	/// Whether a required `tag` may be left out of this log, as CLAIMED-SCORE
+	/// may from a checklog.
+	fn is_exempt(&self, tag: &str) -> bool {
+		tag == "CLAIMED-SCORE" && self.is_checklog()
+	}
+
+	/// Runs of QSOs, as ranges of `entries()`, which are earlier than a QSO
+	/// before them. A run ends at the first QSO which is not.
+	pub fn backwards_runs(&self) -> Vec<Range<usize>> {
+		let mut runs: Vec<Range<usize>> = Vec::new();
+		let mut latest: Option<DateTime> = None;
+
+		for (idx, qso) in self.entries.iter().enumerate() {
+			match latest {
+				Some(time) if qso.datetime < time => match runs.last_mut() {
+					Some(run) if run.end == idx => run.end += 1,
+					_ => runs.push(idx..idx + 1)
+				},
+				_ => latest = Some(qso.datetime)
+			}
+		}
+
+		runs
+	}
+
+	/// Run `rules` against this log and report what they find. This does not
+	/// depend on how the log was made, but findings only have line numbers if
+	/// it was parsed, and for header tags only if the source was preserved.
+	pub fn validate(&self, rules: &RuleSet) -> ValidationReport {
+		let mut report = ValidationReport::new();
+
+		for rule in &rules.0 {
+			rule.check(self, &mut report);
+		}
+
+		report
+	}
+
+	/// Line of the given occurrence of `tag`, counting from zero, if the
+	/// source of the log was preserved.
+	pub(crate) fn tag_line(&self, tag: &str, occurrence: usize) -> Option<usize> {
+		self.source.as_ref()?
+			.iter()
+			.enumerate()
+			.filter(|(_, line)| line.tag() == tag)
+			.nth(occurrence)
+			.map(|(line_no, _)| line_no)
+	}
+
+	/// Whether the log has a value for `tag`.
+	pub(crate) fn has_tag(&self, tag: &str) -> bool {
+		match tag {
+			"START-OF-LOG" | "END-OF-LOG" => true,
+			"CALLSIGN" => self.callsign.as_ref().is_some_and(|callsign| !callsign.is_empty()),
+			"CONTEST" => self.contest.as_ref().is_some_and(|contest| !contest.is_empty()),
+			"CATEGORY" => self.category_operator.is_some(),
+			"CATEGORY-ASSISTED" => self.category_assisted.is_some(),
+			"CATEGORY-BAND" => self.category_band.is_some(),
+			"CATEGORY-MODE" => self.category_mode.is_some(),
+			"CATEGORY-OPERATOR" => self.category_operator.is_some(),
+			"CATEGORY-POWER" => self.category_power.is_some(),
+			"CATEGORY-STATION" => self.category_station.is_some(),
+			"CATEGORY-TIME" => self.category_time.is_some(),
+			"CATEGORY-TRANSMITTER" => self.category_transmitter.is_some(),
+			"CATEGORY-OVERLAY" => self.category_overlay.is_some(),
+			"CERTIFICATE" => self.certificate.is_some(),
+			"CLAIMED-SCORE" => self.claimed_score.is_some(),
+			"CLUB" => self.club.is_some(),
+			"CREATED-BY" => self.created_by.is_some(),
+			"EMAIL" => self.email.is_some(),
+			"GRID-LOCATOR" => self.grid_locator.is_some(),
+			"LOCATION" => self.location.is_some(),
+			"ARRL-SECTION" => self.arrl_section.is_some(),
+			"NAME" => self.name.is_some(),
+			"ADDRESS" => self.address.is_some(),
+			"OPERATORS" => !self.operators.is_empty(),
+			"OFFTIME" => !self.offtimes.is_empty(),
+			"SOAPBOX" => !self.soapbox.is_empty(),
+			"QSO" => !self.entries.is_empty(),
+			"X-QSO" => !self.ignored_entries.is_empty(),
+			_ => self.other_tags.contains(tag)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn validate_rules() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"CALLSIGN: W1AW\n",
+			"CATEGORY-TRANSMITTER: ONE\n",
+			"OFFTIME: 2023-01-14 0200 2023-01-14 0100\n",
+			"QSO: 14025 CW 2023-01-14 0000 W1AW 599 CT K1AB 599 MA\n",
+			"QSO: 14025 CW 2023-01-14 0010 W1AX 599 CT K1AC 599 MA 1\n",
+			"QSO: 25000 CW 2023-01-14 0020 W1AW 599 CT K1AD 599\n",
+			"END-OF-LOG:"
+		);
+
+		let options = ParseOptions { preserve_source: true, ..ParseOptions::lenient() };
+		let log = CabrilloLog::from_buffer_with(buf.as_bytes(), &options).unwrap();
+		let report = log.validate(&RuleSet::standard());
+		let findings: Vec<(&str, Severity, Option<usize>)> = report.findings().iter()
+			.map(|finding| (finding.rule().as_str(), finding.severity(), finding.line()))
+			.collect();
+
+		assert_eq!(findings, vec![
+			("required-tags", Severity::Error, None),
+			("structure", Severity::Error, Some(3)),
+			("structure", Severity::Warning, Some(5)),
+			("structure", Severity::Info, Some(6)),
+			("value-ranges", Severity::Warning, Some(5)),
+			("value-ranges", Severity::Warning, Some(6))
+		]);
+		assert_eq!(report.findings()[0].tag(), "CONTEST");
+		assert!(report.has_errors());
+		assert_eq!(report.findings_at_least(Severity::Warning).count(), 5);
+
+		let mut rules = RuleSet::empty();
+		rules.register(RequiredTags::new(&["CALLSIGN"]));
+		assert!(log.validate(&rules).is_empty());
+		assert!(RuleSet::standard().remove("structure"));
+		assert_eq!(format!("{:?}", RuleSet::standard()), r#"["required-tags", "structure", "value-ranges", "category"]"#);
+
+		let buf = "START-OF-LOG: 3.0\nCALLSIGN: W1AW\nCONTEST: NA-SPRINT\nQSO: 14025 CW 2023-01-14 0000 W1AW 1 HIRAM K1AB 2 ALEC\nEND-OF-LOG:\n";
+		let log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+		let mut schemas = SchemaRegistry::builtin();
+		schemas.register(ExchangeSchema::symmetric(&["NA-SPRINT"], &[ExchangeFieldKind::Serial, ExchangeFieldKind::Name, ExchangeFieldKind::Zone]));
+
+		let mut rules = RuleSet::empty();
+		rules.register(LogStructure::default());
+		assert!(log.validate(&rules).is_empty());
+
+		let mut rules = RuleSet::empty();
+		rules.register(LogStructure::new(schemas));
+		assert_eq!(log.validate(&rules).findings().len(), 2);
+	}
+
+	#[test]
+	fn required_tags() {
+		let log = CabrilloLog::from_path("test_data/cqww.txt").unwrap();
+		assert!(log.missing_tags().is_empty(), "{:?}", log.missing_tags());
+
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"CALLSIGN: W1AW\n",
+			"CONTEST: CQ-WW-CW\n",
+			"CATEGORY-OPERATOR: SINGLE-OP\n",
+			"CATEGORY-BAND: ALL\n",
+			"CATEGORY-MODE: CW\n",
+			"CATEGORY-POWER: HIGH\n",
+			"NAME: Hiram Percy Maxim\n",
+			"END-OF-LOG:"
+		);
+
+		let log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+		assert_eq!(log.missing_tags(), vec!["CATEGORY-ASSISTED", "CATEGORY-TRANSMITTER", "CLAIMED-SCORE", "LOCATION"]);
+
+		let mut rules = RuleSet::empty();
+		rules.register(RequiredTags::spec());
+		let report = log.validate(&rules);
+		let tags: Vec<&str> = report.findings().iter().map(|finding| finding.tag().as_str()).collect();
+		assert_eq!(tags, log.missing_tags());
+
+		let log = CabrilloLog::from_buffer(b"START-OF-LOG: 2.0\nCALLSIGN: W1AW\nCATEGORY: SINGLE-OP ALL LOW\nEND-OF-LOG:\n").unwrap();
+		assert_eq!(log.missing_tags(), vec!["CONTEST", "CLAIMED-SCORE", "NAME"]);
+
+		let log = CabrilloLog::from_buffer(b"START-OF-LOG: 2.0\nCALLSIGN: W1AW\nCATEGORY: CHECKLOG\nEND-OF-LOG:\n").unwrap();
+		assert!(log.is_checklog());
+		assert_eq!(log.missing_tags(), vec!["CONTEST", "NAME"]);
+	}
+
+	#[test]
+	fn category_consistency() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"CATEGORY-BAND: 20M\n",
+			"CATEGORY-MODE: SSB\n",
+			"CATEGORY-OPERATOR: SINGLE-OP\n",
+			"OPERATORS: K1AB W1XYZ @N1MM\n",
+			"QSO: 14200 PH 2023-01-14 0000 W1AW 59 CT K1AB 59 MA\n",
+			"QSO: 7200 PH 2023-01-14 0010 W1AW 59 CT K1AC 59 MA\n",
+			"QSO: 14025 CW 2023-01-14 0020 W1AW 599 CT K1AD 599 MA\n",
+			"QSO: 7025 CW 2023-01-14 0030 W1AW 599 CT K1AE 599 MA\n",
+			"END-OF-LOG:"
+		);
+
+		let options = ParseOptions { preserve_source: true, ..ParseOptions::default() };
+		let log = CabrilloLog::from_buffer_with(buf.as_bytes(), &options).unwrap();
+		let mut rules = RuleSet::empty();
+		rules.register(CategoryConsistency);
+		let report = log.validate(&rules);
+		let findings: Vec<(&str, Option<usize>)> = report.findings().iter()
+			.map(|finding| (finding.tag().as_str(), finding.line()))
+			.collect();
+
+		assert_eq!(findings, vec![("CATEGORY-BAND", Some(6)), ("CATEGORY-MODE", Some(7)), ("CATEGORY-OPERATOR", Some(3))]);
+		assert_eq!(report.findings()[0].message(), "CATEGORY-BAND is 20M but 2 of 4 QSOs do not fit it");
+	}
+
+	#[test]
+	fn operator_category() {
+		let mut rules = RuleSet::empty();
+		rules.register(CategoryConsistency);
+		let messages = |buf: &str| -> Vec<String> {
+			let log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+			log.validate(&rules).findings().iter().map(|finding| finding.message().to_string()).collect()
+		};
+
+		assert!(messages("CATEGORY-OPERATOR: SINGLE-OP\nOPERATORS: K1AB k1ab @W1AW\n").is_empty());
+		assert_eq!(messages("CATEGORY-OPERATOR: SINGLE-OP\nOPERATORS: K1AB W1XYZ\n"),
+			vec!["CATEGORY-OPERATOR is SINGLE-OP but OPERATORS lists 2 operators"]);
+		assert_eq!(messages("CATEGORY-OPERATOR: MULTI-OP\nOPERATORS: @W1AW\n"),
+			vec!["CATEGORY-OPERATOR is MULTI-OP but OPERATORS lists no operators"]);
+		assert!(messages("CATEGORY-OPERATOR: MULTI-OP\nOPERATORS: K1AB W1XYZ\n").is_empty());
+	}
+
+	#[test]
+	fn mode_category() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"CATEGORY-MODE: CW\n",
+			"QSO: 14025 CW 2023-01-14 0000 W1AW 599 CT K1AB 599 MA\n",
+			"QSO: 14080 RY 2023-01-14 0010 W1AW 599 CT K1AC 599 MA\n",
+			"QSO: 14200 PH 2023-01-14 0020 W1AW 59 CT K1AD 59 MA\n",
+			"END-OF-LOG:"
+		);
+
+		let mut log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+		let mut rules = RuleSet::empty();
+		rules.register(ModeCategory);
+		let report = log.validate(&rules);
+		let lines: Vec<Option<usize>> = report.findings().iter().map(Finding::line).collect();
+		assert_eq!(lines, vec![Some(3), Some(4)]);
+		assert_eq!(report.findings()[0].message(), "RTTY QSO does not fit CATEGORY-MODE CW");
+
+		log.normalize_with(&NormalizeOptions { downgrade_category_mode: true });
+		assert!(log.validate(&rules).is_empty());
+	}
+
+	#[test]
+	fn chronology() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"QSO: 14025 CW 2023-01-14 0000 W1AW 599 CT K1AB 599 MA\n",
+			"QSO: 14025 CW 2023-01-14 0500 W1AW 599 CT K1AC 599 MA\n",
+			"QSO: 14025 CW 2023-01-14 0010 W1AW 599 CT K1AD 599 MA\n",
+			"QSO: 14025 CW 2023-01-14 0020 W1AW 599 CT K1AE 599 MA\n",
+			"QSO: 14025 CW 2023-01-14 0510 W1AW 599 CT K1AF 599 MA\n",
+			"QSO: 14025 CW 2023-01-14 0505 W1AW 599 CT K1AG 599 MA\n",
+			"END-OF-LOG:"
+		);
+
+		let mut log = CabrilloLog::from_buffer(buf.as_bytes()).unwrap();
+		assert_eq!(log.backwards_runs(), vec![2..4, 5..6]);
+
+		let mut rules = RuleSet::empty();
+		rules.register(Chronology);
+		let report = log.validate(&rules);
+		assert_eq!(report.findings().len(), 2);
+		assert_eq!(report.findings()[0].line(), Some(3));
+		assert_eq!(report.findings()[0].message(),
+			"2 QSOs from 2023-01-14 0010 are earlier than the QSO at 2023-01-14 0500 before them, lines 3 to 4");
+
+		log.sort_entries();
+		assert!(log.backwards_runs().is_empty());
+		assert_eq!(log.entries()[1].call_received(), "K1AD");
+	}
+
+	#[test]
+	fn offtime_rule() {
+		let buf = concat!(
+			"START-OF-LOG: 3.0\n",
+			"OFFTIME: 2023-01-14 0100 2023-01-14 0200\n",
+			"OFFTIME: 2023-01-14 0130 2023-01-14 0300\n",
+			"OFFTIME: 2023-01-14 0400 2023-01-14 0420\n",
+			"END-OF-LOG:"
+		);
+
+		let options = ParseOptions { preserve_source: true, allow_overlapping_offtimes: true, ..ParseOptions::default() };
+		let log = CabrilloLog::from_buffer_with(buf.as_bytes(), &options).unwrap();
+		let mut rules = RuleSet::empty();
+		rules.register(OfftimeRule::new(Duration::minutes(30)));
+		let report = log.validate(&rules);
+		let lines: Vec<Option<usize>> = report.findings().iter().map(Finding::line).collect();
+		assert_eq!(lines, vec![Some(2), Some(3)]);
+		assert_eq!(report.findings()[0].message(),
+			"Offtime from 2023-01-14 0130 to 2023-01-14 0300 overlaps the offtime from 2023-01-14 0100 to 2023-01-14 0200");
+		assert_eq!(report.findings()[1].message(), "Offtime of 20 minutes is shorter than the minimum of 30 minutes");
+
+		let mut rules = RuleSet::empty();
+		rules.register(OfftimeRule::new(Duration::hours(2)));
+		assert_eq!(log.validate(&rules).findings().len(), 4);
+	}
+}
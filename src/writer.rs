@@ -0,0 +1,1078 @@
+use std::io::{self, Write, BufWriter};
+use std::fs::File;
+use std::path::Path;
+use std::collections::HashSet;
+use std::fmt::{self, Display};
+use crate::*;
+
+const DATETIME_FORMAT: &str = "%Y-%m-%d %H%M";
+
+fn qso_mode_token(mode: &Mode) -> &'static str {
+	match mode {
+		Mode::Cw      => "CW",
+		Mode::Phone   => "PH",
+		Mode::Fm      => "FM",
+		Mode::Rtty    => "RY",
+		Mode::Digital => "DG",
+		Mode::Mixed   => "MIXED"
+	}
+}
+
+fn yes_no(value: bool) -> &'static str {
+	if value { "YES" } else { "NO" }
+}
+
+fn write_tag<W: Write>(writer: &mut W, tag: &str, value: &str) -> io::Result<()> {
+	writeln!(writer, "{}: {}", tag, value)
+}
+
+/// Split `value` on whitespace into chunks which fit within `max_length` once
+/// prefixed with the tag. Values which already fit are returned unchanged, and
+/// words longer than the limit are kept whole on a line of their own.
+fn wrap_value(tag: &str, value: &str, max_length: Option<usize>) -> Vec<String> {
+	let max_length = match max_length {
+		Some(max_length) if tag.len() + 2 + value.len() > max_length => {
+			max_length.saturating_sub(tag.len() + 2)
+		},
+		_ => return vec![value.to_string()]
+	};
+
+	let mut lines = Vec::new();
+	let mut line = String::new();
+
+	for word in value.split_whitespace() {
+		if !line.is_empty() && line.len() + 1 + word.len() > max_length {
+			lines.push(std::mem::take(&mut line));
+		}
+
+		if !line.is_empty() {
+			line.push(' ');
+		}
+
+		line.push_str(word);
+	}
+
+	if !line.is_empty() || lines.is_empty() {
+		lines.push(line);
+	}
+
+	lines
+}
+
+/// Wrap each line of a multi-line value.
+fn wrap_multiline(tag: &str, value: &Option<String>, max_length: Option<usize>) -> Vec<String> {
+	value.iter()
+		.flat_map(|value| value.lines())
+		.flat_map(|line| wrap_value(tag, line, max_length))
+		.collect()
+}
+
+fn single<T: ToString>(value: Option<T>) -> Vec<String> {
+	value.map(|value| value.to_string()).into_iter().collect()
+}
+
+/// Header tags in the order they are written.
+const HEADER_TAGS: &[&str] = &[
+	"START-OF-LOG",
+	"CALLSIGN",
+	"CONTEST",
+	"CATEGORY-ASSISTED",
+	"CATEGORY-BAND",
+	"CATEGORY-MODE",
+	"CATEGORY-OPERATOR",
+	"CATEGORY-POWER",
+	"CATEGORY-STATION",
+	"CATEGORY-TIME",
+	"CATEGORY-TRANSMITTER",
+	"CATEGORY-OVERLAY",
+	"CERTIFICATE",
+	"CLAIMED-SCORE",
+	"CLUB",
+	"CREATED-BY",
+	"EMAIL",
+	"GRID-LOCATOR",
+	"LOCATION",
+	"ARRL-SECTION",
+	"NAME",
+	"ADDRESS",
+	"OPERATORS",
+	"OFFTIME",
+	"SOAPBOX"
+];
+
+/// Header tags in the order they are written for Cabrillo 2.0 logs.
+const HEADER_TAGS_V2: &[&str] = &[
+	"START-OF-LOG",
+	"ARRL-SECTION",
+	"CALLSIGN",
+	"CATEGORY",
+	"CLAIMED-SCORE",
+	"CLUB",
+	"CONTEST",
+	"CREATED-BY",
+	"NAME",
+	"ADDRESS",
+	"OPERATORS",
+	"OFFTIME",
+	"SOAPBOX"
+];
+
+/// Tags which are combined into a single field when parsed, and so have to be
+/// compared and written out together.
+fn tag_group(tag: &str) -> &str {
+	if tag.starts_with("ADDRESS") { "ADDRESS" } else { tag }
+}
+
+/// Column widths used when writing QSO lines. Each field is padded with spaces
+/// to its width, and fields are always separated by at least one space. The
+/// exchange is split on whitespace and each of its fields is padded using the
+/// matching entry in the exchange widths, repeating the last entry as needed.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct QsoFormat {
+	pub frequency: usize,
+	pub mode: usize,
+	pub call_sent: usize,
+	pub exch_sent: Vec<usize>,
+	pub call_recvd: usize,
+	pub exch_recvd: Vec<usize>
+}
+
+impl QsoFormat {
+	/// No padding, every field is separated by a single space.
+	pub fn compact() -> Self {
+		Self::default()
+	}
+
+	/// The column layout used by the examples in the Cabrillo specification for
+	/// contests with an RST and one other exchange field (e.g. CQ-WW).
+	pub fn standard() -> Self {
+		Self {
+			frequency: 5,
+			mode: 2,
+			call_sent: 13,
+			exch_sent: vec![3, 6],
+			call_recvd: 13,
+			exch_recvd: vec![3, 6]
+		}
+	}
+
+	/// A layout with the given widths for the calls and each exchange field.
+	pub fn with_widths(call: usize, exchange: &[usize]) -> Self {
+		Self {
+			frequency: 5,
+			mode: 2,
+			call_sent: call,
+			exch_sent: exchange.to_vec(),
+			call_recvd: call,
+			exch_recvd: exchange.to_vec()
+		}
+	}
+}
+
+/// Line terminator used when writing a log.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum LineEnding {
+	#[default]
+	Lf,
+	CrLf
+}
+
+/// Adapter which expands every '\n' written to it into "\r\n".
+struct CrLfWriter<'a, W: Write> {
+	inner: &'a mut W
+}
+
+impl<W: Write> Write for CrLfWriter<'_, W> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		for (idx, line) in buf.split(|c| c == &b'\n').enumerate() {
+			if idx > 0 {
+				self.inner.write_all(b"\r\n")?;
+			}
+
+			self.inner.write_all(line)?;
+		}
+
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		self.inner.flush()
+	}
+}
+
+/// Options controlling how a log is written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WriteOptions {
+	/// Version of the Cabrillo format to write. Defaults to the version of the log.
+	pub version: Option<CabrilloVersion>,
+	pub qso_format: QsoFormat,
+	/// Maximum length of ADDRESS, SOAPBOX, and OPERATORS lines. Longer values are
+	/// split across repeated tags. The specification limits lines to 75 characters.
+	pub max_line_length: Option<usize>,
+	pub line_ending: LineEnding,
+	/// Follow the original lines of a log parsed with `from_buffer_preserving()`,
+	/// keeping the text of anything which has not been modified.
+	pub preserve_source: bool
+}
+
+impl Default for WriteOptions {
+	fn default() -> Self {
+		Self {
+			version: None,
+			qso_format: QsoFormat::default(),
+			max_line_length: Some(MAX_LINE_LENGTH),
+			line_ending: LineEnding::default(),
+			preserve_source: true
+		}
+	}
+}
+
+fn pad_exchange(line: &mut String, exchange: &str, widths: &[usize]) {
+	for (idx, field) in exchange.split_whitespace().enumerate() {
+		let width = widths.get(idx).or(widths.last()).copied().unwrap_or(0);
+		line.push_str(&format!("{:<width$} ", field, width = width));
+	}
+}
+
+/// Information in a log which cannot be represented when it is written as an
+/// older version of the format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionWarning {
+	tag: String,
+	message: String
+}
+
+impl VersionWarning {
+	fn new(tag: &str, message: String) -> Self {
+		Self {
+			tag: tag.to_string(),
+			message
+		}
+	}
+
+	/// The 3.0 tag whose information is lost.
+	pub fn tag(&self) -> &String {
+		&self.tag
+	}
+
+	pub fn message(&self) -> &String {
+		&self.message
+	}
+}
+
+impl Display for VersionWarning {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}: {}", self.tag, self.message)
+	}
+}
+
+/// Cabrillo 3.0 tags which have no equivalent in 2.0 and are omitted.
+const TAGS_NOT_IN_V2: &[&str] = &[
+	"CATEGORY-TIME",
+	"CATEGORY-OVERLAY",
+	"CERTIFICATE",
+	"EMAIL",
+	"GRID-LOCATOR"
+];
+
+fn format_qso(tag: &str, qso: &Qso, format: &QsoFormat) -> String {
+	let mut line = format!(
+		"{}: {:>freq$} {:<mode$} {} {:<call_sent$} ",
+		tag,
+		qso.frequency.token(),
+		qso.submode.map_or_else(|| qso_mode_token(&qso.mode).to_string(), |submode| submode.to_string()),
+		datetime::format(&qso.datetime, DATETIME_FORMAT),
+		qso.call_sent,
+		freq = format.frequency,
+		mode = format.mode,
+		call_sent = format.call_sent
+	);
+
+	pad_exchange(&mut line, &qso.exch_sent, &format.exch_sent);
+	line.push_str(&format!("{:<width$} ", qso.call_recvd, width = format.call_recvd));
+	pad_exchange(&mut line, &qso.exch_recvd, &format.exch_recvd);
+
+	if let Some(transmitter_id) = qso.transmitter_id {
+		line.push_str(&transmitter_id.to_string());
+	}
+
+	line.truncate(line.trim_end().len());
+	line
+}
+
+fn write_qso<W: Write>(writer: &mut W, tag: &str, qso: &Qso, format: &QsoFormat) -> io::Result<()> {
+	writeln!(writer, "{}", format_qso(tag, qso, format))
+}
+
+impl CabrilloLog {
+	/// Serialize this log as Cabrillo text to the given writer. Header tags are
+	/// written first, followed by QSO and X-QSO lines and finally END-OF-LOG.
+	pub fn to_writer<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+		self.to_writer_with(writer, &WriteOptions::default())
+	}
+
+	/// Serialize this log as Cabrillo text using the given options.
+	pub fn to_writer_with<W: Write>(&self, writer: &mut W, options: &WriteOptions) -> io::Result<()> {
+		match options.line_ending {
+			LineEnding::Lf => self.write_log(writer, options),
+			LineEnding::CrLf => self.write_log(&mut CrLfWriter { inner: writer }, options)
+		}
+	}
+
+	/// Serialize this log in the structure of a specific Cabrillo version. Version
+	/// 2.0 combines the category tags into a single CATEGORY tag, writes LOCATION
+	/// as ARRL-SECTION, and omits tags which did not exist in that version.
+	pub fn to_writer_versioned<W: Write>(&self, writer: &mut W, version: CabrilloVersion) -> io::Result<()> {
+		let options = WriteOptions {
+			version: Some(version),
+			..Default::default()
+		};
+
+		self.to_writer_with(writer, &options)
+	}
+
+	/// Serialize this log as Cabrillo 2.0, as `to_writer_versioned()` does, and
+	/// return warnings for the information which could not be represented.
+	pub fn to_writer_v2<W: Write>(&self, writer: &mut W) -> io::Result<Vec<VersionWarning>> {
+		self.to_writer_versioned(writer, CabrilloVersion::V2)?;
+		Ok(self.version_warnings(CabrilloVersion::V2))
+	}
+
+	/// Information in this log which would be lost by writing it as `version`.
+	pub fn version_warnings(&self, version: CabrilloVersion) -> Vec<VersionWarning> {
+		if version != CabrilloVersion::V2 {
+			return Vec::new();
+		}
+
+		let mut warnings = Vec::new();
+		let v2_operator = self.v2_operator_category();
+		let operator_is_replaced = matches!(v2_operator, Some("ROVER") | Some("SCHOOL-CLUB"));
+
+		for tag in TAGS_NOT_IN_V2 {
+			if !self.tag_values(tag, &WriteOptions::default()).is_empty() {
+				warnings.push(VersionWarning::new(tag, "Tag does not exist in 2.0 and is omitted".into()));
+			}
+		}
+
+		if let (Some(section), Some(location)) = (self.arrl_section, &self.location) {
+			warnings.push(VersionWarning::new("LOCATION",
+				format!("{} is omitted, 2.0 only has ARRL-SECTION, which is written as {}", location, section)));
+		}
+
+		if let (true, Some(op)) = (operator_is_replaced, self.category_operator) {
+			warnings.push(VersionWarning::new("CATEGORY-OPERATOR",
+				format!("{} is replaced by {}", op, v2_operator.unwrap_or_default())));
+		}
+
+		if let Some(station) = self.category_station {
+			let represented = match station {
+				StationCategory::Rover | StationCategory::School => true,
+				StationCategory::Portable => v2_operator == Some("SINGLE-OP-PORTABLE"),
+				_ => false
+			};
+
+			if matches!(station, StationCategory::RoverLimited | StationCategory::RoverUnlimited) {
+				warnings.push(VersionWarning::new("CATEGORY-STATION",
+					format!("{} is written as ROVER", station)));
+			} else if !represented {
+				warnings.push(VersionWarning::new("CATEGORY-STATION",
+					format!("{} cannot be represented in the CATEGORY tag", station)));
+			}
+		}
+
+		if self.category_assisted == Some(true) && v2_operator != Some("SINGLE-OP-ASSISTED") {
+			warnings.push(VersionWarning::new("CATEGORY-ASSISTED",
+				"ASSISTED can only be represented for single operator logs".into()));
+		}
+
+		if let Some(xmitter) = self.category_transmitter {
+			let represented = match v2_operator {
+				Some("MULTI-MULTI") => xmitter != TransmitterCategory::Swl,
+				Some(op) if op.starts_with("MULTI-") => true,
+				_ => xmitter == TransmitterCategory::One
+			};
+
+			if !represented {
+				warnings.push(VersionWarning::new("CATEGORY-TRANSMITTER",
+					format!("{} cannot be represented in the CATEGORY tag", xmitter)));
+			}
+		}
+
+		if let Some(mode @ (Mode::Fm | Mode::Digital)) = self.category_mode {
+			warnings.push(VersionWarning::new("CATEGORY-MODE",
+				format!("{} is not a 2.0 category", mode)));
+		}
+
+		let digital = self.entries.iter()
+			.chain(&self.ignored_entries)
+			.filter(|qso| qso.mode == Mode::Digital)
+			.count();
+
+		if digital > 0 {
+			warnings.push(VersionWarning::new("QSO",
+				format!("{} QSOs use the DG mode, which does not exist in 2.0", digital)));
+		}
+
+		warnings
+	}
+
+	/// Write this log to a file at `path`, creating or truncating it.
+	pub fn write_to_path<P: AsRef<Path>>(&self, path: P, line_ending: LineEnding) -> io::Result<()> {
+		let options = WriteOptions {
+			line_ending,
+			..Default::default()
+		};
+
+		let mut writer = BufWriter::new(File::create(path)?);
+		self.to_writer_with(&mut writer, &options)?;
+		writer.flush()
+	}
+
+	fn output_version(&self, options: &WriteOptions) -> CabrilloVersion {
+		options.version.clone().unwrap_or_else(|| self.version.clone())
+	}
+
+	/// The operator category as written in a Cabrillo 2.0 CATEGORY tag, which
+	/// also encodes some of the assisted, station, and transmitter categories.
+	fn v2_operator_category(&self) -> Option<&'static str> {
+		if let Some(StationCategory::Rover | StationCategory::RoverLimited | StationCategory::RoverUnlimited) = self.category_station {
+			return Some("ROVER");
+		}
+
+		if self.category_station == Some(StationCategory::School) {
+			return Some("SCHOOL-CLUB");
+		}
+
+		self.category_operator.map(|op| match op {
+			OperatorCategory::SingleOp if self.category_assisted == Some(true) => "SINGLE-OP-ASSISTED",
+			OperatorCategory::SingleOp if self.category_station == Some(StationCategory::Portable) => "SINGLE-OP-PORTABLE",
+			OperatorCategory::SingleOp => "SINGLE-OP",
+			OperatorCategory::MultiOp => match self.category_transmitter {
+				Some(TransmitterCategory::One)       => "MULTI-ONE",
+				Some(TransmitterCategory::Two)       => "MULTI-TWO",
+				Some(TransmitterCategory::Limited)   => "MULTI-LIMITED",
+				Some(TransmitterCategory::Unlimited) => "MULTI-UNLIMITED",
+				_                                    => "MULTI-MULTI"
+			},
+			OperatorCategory::CheckLog => "CHECKLOG"
+		})
+	}
+
+	/// The combined Cabrillo 2.0 CATEGORY value, e.g. "SINGLE-OP ALL HIGH CW".
+	fn v2_category(&self) -> Option<String> {
+		let fields: Vec<String> = [
+			self.v2_operator_category().map(|op| op.to_string()),
+			self.category_band.map(|band| band.to_string()),
+			self.category_power.map(|power| power.to_string()),
+			self.category_mode.map(|mode| mode.to_string())
+		]
+			.into_iter()
+			.flatten()
+			.collect();
+
+		if fields.is_empty() { None } else { Some(fields.join(" ")) }
+	}
+
+	/// The values written for a header tag, one per line.
+	fn tag_values(&self, tag: &str, options: &WriteOptions) -> Vec<String> {
+		let version = self.output_version(options);
+
+		match tag {
+			"START-OF-LOG"         => vec![version.to_string()],
+			"CATEGORY" if version == CabrilloVersion::V2 => {
+				single(self.v2_category().or_else(|| self.other_tags.get(tag).cloned()))
+			},
+			"ARRL-SECTION" if version == CabrilloVersion::V2 => {
+				single(self.arrl_section.map(|section| section.to_string())
+					.or_else(|| self.location.clone())
+					.or_else(|| self.other_tags.get(tag).cloned()))
+			},
+			"ARRL-SECTION"         => single(self.arrl_section),
+			"CALLSIGN"             => single(self.callsign.as_ref()),
+			"CONTEST"              => single(self.contest.as_ref()),
+			"CATEGORY-ASSISTED"    => single(self.category_assisted
+				.map(|assisted| if assisted { "ASSISTED" } else { "NON-ASSISTED" })),
+			"CATEGORY-BAND"        => single(self.category_band),
+			"CATEGORY-MODE"        => single(self.category_mode),
+			"CATEGORY-OPERATOR"    => single(self.category_operator),
+			"CATEGORY-POWER"       => single(self.category_power),
+			"CATEGORY-STATION"     => single(self.category_station),
+			"CATEGORY-TIME"        => single(self.category_time),
+			"CATEGORY-TRANSMITTER" => single(self.category_transmitter),
+			"CATEGORY-OVERLAY"     => single(self.category_overlay.as_ref()),
+			"CERTIFICATE"          => single(self.certificate.map(yes_no)),
+			"CLAIMED-SCORE"        => single(self.claimed_score),
+			"CLUB"                 => single(self.club.as_ref()),
+			"CREATED-BY"           => single(self.created_by.as_ref()),
+			"EMAIL"                => single(self.email.as_ref()),
+			"GRID-LOCATOR"         => single(self.grid_locator.as_ref()),
+			"LOCATION"             => single(self.location.as_ref()),
+			"NAME"                 => single(self.name.as_ref()),
+			"ADDRESS"              => wrap_multiline(tag, &self.address, options.max_line_length),
+			"OPERATORS"            => {
+				if self.operators.is_empty() {
+					Vec::new()
+				} else {
+					wrap_value(tag, &self.joined_operators(" "), options.max_line_length)
+				}
+			},
+			"OFFTIME"              => {
+				self.offtimes.iter()
+					.map(|offtime| format!("{} {}",
+						datetime::format(&offtime.begin, DATETIME_FORMAT),
+						datetime::format(&offtime.end, DATETIME_FORMAT)))
+					.collect()
+			},
+			"SOAPBOX"              => self.soapbox.iter()
+				.flat_map(|line| wrap_value(tag, line, options.max_line_length))
+				.collect(),
+			"DEBUG"                => single(Some("1").filter(|_| self.debug)),
+			_                      => self.other_tags.get_all(tag).cloned().collect()
+		}
+	}
+
+	fn write_log<W: Write>(&self, writer: &mut W, options: &WriteOptions) -> io::Result<()> {
+		let version = self.output_version(options);
+
+		if let Some(ref source) = self.source {
+			if options.preserve_source && version == self.version {
+				return self.write_preserved(writer, source, options);
+			}
+		}
+
+		self.write_header(writer, options)?;
+
+		for qso in &self.entries {
+			write_qso(writer, "QSO", qso, &options.qso_format)?;
+		}
+
+		for qso in &self.ignored_entries {
+			write_qso(writer, "X-QSO", qso, &options.qso_format)?;
+		}
+
+		writeln!(writer, "END-OF-LOG:")
+	}
+
+	fn write_header<W: Write>(&self, writer: &mut W, options: &WriteOptions) -> io::Result<()> {
+		let header_tags = match self.output_version(options) {
+			CabrilloVersion::V2 => HEADER_TAGS_V2,
+			_ => HEADER_TAGS
+		};
+
+		for tag in header_tags {
+			self.write_tag_values(writer, tag, options)?;
+		}
+
+		let other_tags = self.other_tags.iter()
+			.filter(|(tag, _)| !header_tags.contains(&tag.as_str()));
+
+		for (tag, value) in other_tags {
+			write_tag(writer, tag, value)?;
+		}
+
+		self.write_tag_values(writer, "DEBUG", options)
+	}
+
+	fn write_tag_values<W: Write>(&self, writer: &mut W, tag: &str, options: &WriteOptions) -> io::Result<()> {
+		for value in self.tag_values(tag, options) {
+			write_tag(writer, tag, &value)?;
+		}
+
+		Ok(())
+	}
+
+	/// Write the log following the order of the original source lines. Header
+	/// tags whose values are unchanged since parsing, and QSOs which are still
+	/// present, are written using their original text.
+	fn write_preserved<W: Write>(&self, writer: &mut W, source: &[SourceLine], options: &WriteOptions) -> io::Result<()> {
+		let mut written = HashSet::new();
+		let mut unchanged = HashMap::new();
+		let mut entries = QsoCursor::new("QSO", &self.entries, source);
+		let mut ignored = QsoCursor::new("X-QSO", &self.ignored_entries, source);
+		let mut header_done = false;
+		let mut ended = false;
+
+		for line in source {
+			let tag = line.tag.as_str();
+
+			match tag {
+				"" => {
+					writeln!(writer, "{}", line.raw)?;
+					continue;
+				},
+				"QSO" | "X-QSO" | "END-OF-LOG" if !header_done => {
+					self.write_new_tags(writer, source, options)?;
+					header_done = true;
+				},
+				_ => ()
+			}
+
+			match tag {
+				"QSO" => entries.write_line(writer, line, options)?,
+				"X-QSO" => ignored.write_line(writer, line, options)?,
+				"END-OF-LOG" => {
+					entries.write_remaining(writer, options)?;
+					ignored.write_remaining(writer, options)?;
+					writeln!(writer, "{}", line.raw)?;
+					ended = true;
+				},
+				_ => {
+					let group = tag_group(tag);
+					let is_unchanged = *unchanged
+						.entry(group)
+						.or_insert_with(|| self.is_group_unchanged(group, source, options));
+
+					if is_unchanged {
+						writeln!(writer, "{}", line.raw)?;
+					} else if written.insert(group) {
+						self.write_tag_values(writer, group, options)?;
+					}
+				}
+			}
+		}
+
+		if !ended {
+			if !header_done {
+				self.write_new_tags(writer, source, options)?;
+			}
+
+			entries.write_remaining(writer, options)?;
+			ignored.write_remaining(writer, options)?;
+			writeln!(writer, "END-OF-LOG:")?;
+		}
+
+		Ok(())
+	}
+
+	/// Compare the current values of a tag group with those parsed from its original lines.
+	fn is_group_unchanged(&self, group: &str, source: &[SourceLine], options: &WriteOptions) -> bool {
+		let mut original = CabrilloLog::new();
+
+		for (line_no, line) in source.iter().enumerate() {
+			if tag_group(&line.tag) == group {
+				if let Ok((_, (tag, value))) = cabrillo_tag(&line.raw) {
+					let _ = original.parse_tag(line_no, tag, value, 0);
+				}
+			}
+		}
+
+		original.tag_values(group, options) == self.tag_values(group, options)
+	}
+
+	/// Write header tags which have a value but did not appear in the original source.
+	fn write_new_tags<W: Write>(&self, writer: &mut W, source: &[SourceLine], options: &WriteOptions) -> io::Result<()> {
+		let present: HashSet<&str> = source.iter()
+			.map(|line| tag_group(&line.tag))
+			.collect();
+
+		let header_tags = match self.version {
+			CabrilloVersion::V2 => HEADER_TAGS_V2,
+			_ => HEADER_TAGS
+		};
+
+		let other_tags: Vec<&str> = self.other_tags.tags().into_iter()
+			.map(|tag| tag.as_str())
+			.filter(|tag| !header_tags.contains(tag))
+			.collect();
+
+		for tag in header_tags.iter().copied().chain(other_tags).chain(["DEBUG"]) {
+			if !present.contains(tag) {
+				self.write_tag_values(writer, tag, options)?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Tracks which QSOs have been written while following the original source lines.
+struct QsoCursor<'a> {
+	tag: &'static str,
+	entries: &'a [Qso],
+	compact: Vec<String>,
+	/// Number of source lines not yet visited for each compact QSO line.
+	pending: HashMap<String, usize>,
+	next: usize
+}
+
+impl<'a> QsoCursor<'a> {
+	fn new(tag: &'static str, entries: &'a [Qso], source: &[SourceLine]) -> Self {
+		let mut pending = HashMap::new();
+
+		for line in source.iter().filter(|line| line.tag == tag) {
+			if let Some(compact) = Self::parse_compact(tag, line) {
+				*pending.entry(compact).or_insert(0) += 1;
+			}
+		}
+
+		Self {
+			tag,
+			entries,
+			compact: entries.iter()
+				.map(|qso| format_qso(tag, qso, &QsoFormat::compact()))
+				.collect(),
+			pending,
+			next: 0
+		}
+	}
+
+	fn parse_compact(tag: &str, line: &SourceLine) -> Option<String> {
+		cabrillo_tag(&line.raw)
+			.ok()
+			.and_then(|(_, (_, value))| cabrillo_qso(None)(value).ok())
+			.map(|(_, qso)| format_qso(tag, &qso, &QsoFormat::compact()))
+	}
+
+	/// Write the original line if its QSO is next, preceded by any QSOs which
+	/// were not in the original source. Lines of QSOs which have been removed,
+	/// modified, or moved are dropped and the QSO is written where it now belongs.
+	fn write_line<W: Write>(&mut self, writer: &mut W, line: &SourceLine, options: &WriteOptions) -> io::Result<()> {
+		let original = match Self::parse_compact(self.tag, line) {
+			Some(original) => original,
+			None => return Ok(())
+		};
+
+		if let Some(count) = self.pending.get_mut(&original) {
+			*count -= 1;
+		}
+
+		while let Some(compact) = self.compact.get(self.next) {
+			if compact == &original {
+				writeln!(writer, "{}", line.raw)?;
+				self.next += 1;
+				break;
+			}
+
+			if self.pending.get(compact).copied().unwrap_or(0) > 0 {
+				// the next QSO appears later in the source
+				break;
+			}
+
+			write_qso(writer, self.tag, &self.entries[self.next], &options.qso_format)?;
+			self.next += 1;
+		}
+
+		Ok(())
+	}
+
+	fn write_remaining<W: Write>(&mut self, writer: &mut W, options: &WriteOptions) -> io::Result<()> {
+		for qso in &self.entries[self.next..] {
+			write_qso(writer, self.tag, qso, &options.qso_format)?;
+		}
+
+		self.next = self.entries.len();
+		Ok(())
+	}
+}
+
+/// Writes a log incrementally: the header is written when the writer is created,
+/// after which QSOs are appended and flushed one at a time as they are logged.
+/// `finish()` must be called to write END-OF-LOG.
+pub struct CabrilloWriter<W: Write> {
+	writer: W,
+	options: WriteOptions
+}
+
+impl<W: Write> CabrilloWriter<W> {
+	/// Write the header tags of `log`, followed by any QSOs it already contains.
+	pub fn new(writer: W, log: &CabrilloLog) -> io::Result<Self> {
+		Self::with_options(writer, log, WriteOptions::default())
+	}
+
+	pub fn with_options(writer: W, log: &CabrilloLog, options: WriteOptions) -> io::Result<Self> {
+		let mut new_writer = Self { writer, options };
+		let mut buf = Vec::new();
+
+		log.write_header(&mut buf, &new_writer.options)?;
+
+		for qso in &log.entries {
+			write_qso(&mut buf, "QSO", qso, &new_writer.options.qso_format)?;
+		}
+
+		for qso in &log.ignored_entries {
+			write_qso(&mut buf, "X-QSO", qso, &new_writer.options.qso_format)?;
+		}
+
+		new_writer.write_flushed(&buf)?;
+		Ok(new_writer)
+	}
+
+	/// Append a QSO and flush it to the underlying writer.
+	pub fn write_qso(&mut self, qso: &Qso) -> io::Result<()> {
+		self.write_entry("QSO", qso)
+	}
+
+	/// Append a QSO as 'X-QSO' and flush it to the underlying writer.
+	pub fn write_ignored_qso(&mut self, qso: &Qso) -> io::Result<()> {
+		self.write_entry("X-QSO", qso)
+	}
+
+	/// Write END-OF-LOG and return the underlying writer.
+	pub fn finish(mut self) -> io::Result<W> {
+		self.write_flushed(b"END-OF-LOG:\n")?;
+		Ok(self.writer)
+	}
+
+	fn write_entry(&mut self, tag: &str, qso: &Qso) -> io::Result<()> {
+		let mut buf = Vec::new();
+		write_qso(&mut buf, tag, qso, &self.options.qso_format)?;
+		self.write_flushed(&buf)
+	}
+
+	fn write_flushed(&mut self, buf: &[u8]) -> io::Result<()> {
+		match self.options.line_ending {
+			LineEnding::Lf => self.writer.write_all(buf)?,
+			LineEnding::CrLf => CrLfWriter { inner: &mut self.writer }.write_all(buf)?
+		}
+
+		self.writer.flush()
+	}
+}
+
+impl Display for CabrilloLog {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut buf = Vec::new();
+		self.to_writer(&mut buf).map_err(|_| fmt::Error)?;
+		f.write_str(&String::from_utf8_lossy(&buf))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs;
+	use crate::*;
+
+	#[test]
+	fn round_trip() {
+		[
+			"test_data/cqww.txt",
+			"test_data/cqww_vhf.txt",
+			"test_data/cqwpx_rtty.txt",
+			"test_data/rdxc.txt"
+		]
+			.iter()
+			.for_each(|path| {
+				let buf = fs::read(path).unwrap();
+				let log = CabrilloLog::from_buffer(&buf).unwrap();
+				let output = log.to_string();
+				let reparsed = CabrilloLog::from_buffer(output.as_bytes()).unwrap();
+
+				assert_eq!(output, reparsed.to_string());
+				assert_eq!(log.entries().len(), reparsed.entries().len());
+			});
+	}
+
+	#[test]
+	fn write_header() {
+		let buf = fs::read("test_data/cqww.txt").unwrap();
+		let log = CabrilloLog::from_buffer(&buf).unwrap();
+		let output = log.to_string();
+
+		assert!(output.starts_with("START-OF-LOG: 3.0\nCALLSIGN: AA1ZZZ\n"));
+		assert!(output.contains("CATEGORY-ASSISTED: NON-ASSISTED\n"));
+		assert!(output.contains("CATEGORY-MODE: SSB\n"));
+		assert!(output.contains("ADDRESS: 1 Main St\nADDRESS: Uxbridge\n"));
+		assert!(output.ends_with("END-OF-LOG:\n"));
+	}
+
+	#[test]
+	fn wrap_long_lines() {
+		let operators: Vec<String> = (0..20).map(|n| format!("K{}ABC", n)).collect();
+		let log = CabrilloLog::builder()
+			.callsign("W1AW")
+			.contest("ARRL-DX-CW")
+			.soapbox_line(&"WORD ".repeat(30))
+			.operators(operators.clone())
+			.build()
+			.unwrap();
+
+		let output = log.to_string();
+		assert!(output.lines().all(|line| line.len() <= 75));
+		assert!(output.lines().filter(|line| line.starts_with("SOAPBOX: ")).count() > 1);
+		assert!(output.lines().filter(|line| line.starts_with("OPERATORS: ")).count() > 1);
+
+		let reparsed = CabrilloLog::from_buffer(output.as_bytes()).unwrap();
+		let callsigns: Vec<&str> = reparsed.operators().iter().map(Callsign::as_str).collect();
+		assert_eq!(callsigns, operators.iter().collect::<Vec<_>>());
+
+		let options = WriteOptions {
+			max_line_length: None,
+			..Default::default()
+		};
+
+		let mut output = Vec::new();
+		log.to_writer_with(&mut output, &options).unwrap();
+		let output = String::from_utf8(output).unwrap();
+		assert_eq!(output.lines().filter(|line| line.starts_with("OPERATORS: ")).count(), 1);
+	}
+
+	#[test]
+	fn write_crlf() {
+		let path = std::env::temp_dir().join("cabrillo_write_crlf.txt");
+		let buf = fs::read("test_data/cqww.txt").unwrap();
+		let log = CabrilloLog::from_buffer(&buf).unwrap();
+
+		log.write_to_path(&path, LineEnding::CrLf).unwrap();
+		let output = fs::read_to_string(&path).unwrap();
+		fs::remove_file(&path).unwrap();
+
+		assert!(output.starts_with("START-OF-LOG: 3.0\r\nCALLSIGN: AA1ZZZ\r\n"));
+		assert!(output.ends_with("END-OF-LOG:\r\n"));
+		assert_eq!(output.replace("\r\n", "\n"), log.to_string());
+	}
+
+	#[test]
+	fn write_preserved() {
+		let input = concat!(
+			"START-OF-LOG: 3.0\n",
+			"X-SPONSOR: first\n",
+			"CALLSIGN: K1ABC\n",
+			"CLUB: My Club\n",
+			"X-SPONSOR: second\n",
+			"ADDRESS: 123 Any St\n",
+			"ADDRESS-CITY: Anytown\n",
+			"\n",
+			"QSO: 21010 CW 2004-03-20 1200 K1ABC         599 MA     RL3A          599 CT\n",
+			"X-QSO: 14256 PH 2004-03-20 1201 K1ABC       59  002    YM2ZF         59  005\n",
+			"QSO: 21010 CW 2004-03-20 1202 K1ABC         599 MA     RA1ARJ        599 NH\n",
+			"QSO: 28530 PH 2004-03-20 1205 K1ABC         59  004    EM1U          59  010\n",
+			"END-OF-LOG:\n"
+		);
+
+		let mut log = CabrilloLog::from_buffer_preserving(input.as_bytes()).unwrap();
+		assert_eq!(log.to_string(), input);
+
+		log.set_club(Some("Other Club".to_string()));
+		log.set_email(Some("k1abc@example.com".to_string()));
+		let removed = log.remove_entry(0).unwrap();
+		log.add_entry(removed);
+
+		assert_eq!(log.to_string(), concat!(
+			"START-OF-LOG: 3.0\n",
+			"X-SPONSOR: first\n",
+			"CALLSIGN: K1ABC\n",
+			"CLUB: Other Club\n",
+			"X-SPONSOR: second\n",
+			"ADDRESS: 123 Any St\n",
+			"ADDRESS-CITY: Anytown\n",
+			"\n",
+			"EMAIL: k1abc@example.com\n",
+			"X-QSO: 14256 PH 2004-03-20 1201 K1ABC       59  002    YM2ZF         59  005\n",
+			"QSO: 21010 CW 2004-03-20 1202 K1ABC         599 MA     RA1ARJ        599 NH\n",
+			"QSO: 28530 PH 2004-03-20 1205 K1ABC         59  004    EM1U          59  010\n",
+			"QSO: 21010 CW 2004-03-20 1200 K1ABC 599 MA RL3A 599 CT\n",
+			"END-OF-LOG:\n"
+		));
+
+		let options = WriteOptions {
+			preserve_source: false,
+			..Default::default()
+		};
+
+		let mut output = Vec::new();
+		log.to_writer_with(&mut output, &options).unwrap();
+		assert!(String::from_utf8(output).unwrap().starts_with("START-OF-LOG: 3.0\nCALLSIGN: K1ABC\n"));
+	}
+
+	#[test]
+	fn write_versioned() {
+		let buf = fs::read("test_data/cqwpx_rtty.txt").unwrap();
+		let log = CabrilloLog::from_buffer(&buf).unwrap();
+
+		let mut output = Vec::new();
+		log.to_writer_versioned(&mut output, CabrilloVersion::V2).unwrap();
+		let output = String::from_utf8(output).unwrap();
+
+		assert!(output.starts_with("START-OF-LOG: 2.0\nARRL-SECTION: PR\nCALLSIGN: NP3U\n"));
+		assert!(output.contains("CATEGORY: MULTI-TWO ALL HIGH RTTY\n"));
+		assert!(!output.contains("CATEGORY-"));
+		assert!(!output.contains("LOCATION:"));
+
+		let reparsed = CabrilloLog::from_buffer(output.as_bytes()).unwrap();
+		assert_eq!(reparsed.cabrillo_version(), &CabrilloVersion::V2);
+		assert_eq!(reparsed.entries().len(), log.entries().len());
+		assert_eq!(reparsed.category_transmitter(), log.category_transmitter());
+		assert_eq!(reparsed.category_power(), log.category_power());
+
+		let mut output = Vec::new();
+		reparsed.to_writer_versioned(&mut output, CabrilloVersion::V3).unwrap();
+		let output = String::from_utf8(output).unwrap();
+
+		assert!(output.starts_with("START-OF-LOG: 3.0\n"));
+		assert!(output.contains("ARRL-SECTION: PR\n"));
+	}
+
+	#[test]
+	fn write_v2_warnings() {
+		let buf = fs::read("test_data/cqww.txt").unwrap();
+		let log = CabrilloLog::from_buffer(&buf).unwrap();
+
+		let mut output = Vec::new();
+		let warnings = log.to_writer_v2(&mut output).unwrap();
+		let output = String::from_utf8(output).unwrap();
+		let tags: Vec<&str> = warnings.iter().map(|warning| warning.tag().as_str()).collect();
+
+		assert!(output.contains("CATEGORY: SINGLE-OP ALL HIGH SSB\n"));
+		assert_eq!(tags, vec!["CATEGORY-OVERLAY", "GRID-LOCATOR"]);
+		assert!(log.version_warnings(CabrilloVersion::V3).is_empty());
+
+		let mut log = log;
+		log.set_category_station(Some(StationCategory::RoverLimited));
+		log.set_category_mode(Some(Mode::Digital));
+		log.set_arrl_section(Some(Section::from_str("EMA").unwrap()));
+
+		let warnings: Vec<String> = log.version_warnings(CabrilloVersion::V2).iter()
+			.map(|warning| warning.to_string())
+			.collect();
+
+		assert!(warnings.contains(&"CATEGORY-OPERATOR: SINGLE-OP is replaced by ROVER".to_string()));
+		assert!(warnings.contains(&"CATEGORY-STATION: ROVER-LIMITED is written as ROVER".to_string()));
+		assert!(warnings.contains(&"CATEGORY-MODE: DIGI is not a 2.0 category".to_string()));
+		assert!(warnings.contains(&"LOCATION: WMA is omitted, 2.0 only has ARRL-SECTION, which is written as EMA".to_string()));
+	}
+
+	#[test]
+	fn write_streaming() {
+		let header = CabrilloLog::builder()
+			.callsign("W1AW")
+			.contest("ARRL-DX-CW")
+			.build()
+			.unwrap();
+
+		let datetime = datetime::parse("2021-02-20 0000", "%Y-%m-%d %H%M").unwrap();
+		let qso = Qso::new(Frequency::from_khz(14025), Mode::Cw, datetime, "W1AW", "599 CT", "DL1ABC", "599 100");
+
+		let mut writer = CabrilloWriter::new(Vec::new(), &header).unwrap();
+		assert_eq!(writer.writer, header.to_string().replace("END-OF-LOG:\n", "").into_bytes());
+
+		writer.write_qso(&qso).unwrap();
+		writer.write_ignored_qso(&qso).unwrap();
+		let output = String::from_utf8(writer.finish().unwrap()).unwrap();
+
+		assert!(output.ends_with(concat!(
+			"QSO: 14025 CW 2021-02-20 0000 W1AW 599 CT DL1ABC 599 100\n",
+			"X-QSO: 14025 CW 2021-02-20 0000 W1AW 599 CT DL1ABC 599 100\n",
+			"END-OF-LOG:\n"
+		)));
+	}
+
+	#[test]
+	fn write_aligned_qso() {
+		let buf = fs::read("test_data/cqww.txt").unwrap();
+		let log = CabrilloLog::from_buffer(&buf).unwrap();
+		let options = WriteOptions {
+			qso_format: QsoFormat::standard(),
+			..Default::default()
+		};
+
+		let mut output = Vec::new();
+		log.to_writer_with(&mut output, &options).unwrap();
+		let output = String::from_utf8(output).unwrap();
+
+		assert!(output.contains(
+			"QSO:  3799 PH 2000-10-26 0711 AA1ZZZ        59  05     K9QZO         59  04     0\n"));
+
+		let reparsed = CabrilloLog::from_buffer(output.as_bytes()).unwrap();
+		assert_eq!(reparsed.to_string(), log.to_string());
+	}
+}